@@ -0,0 +1,15 @@
+//! Regenerates the C header for the functions in `src/ffi.rs` when the `header` feature is enabled
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    #[cfg(feature = "header")]
+    {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+        cbindgen::generate(&dir)
+            .expect("Unable to generate the C header")
+            .write_to_file(format!("{}/include/advent_2015.h", dir));
+    }
+}
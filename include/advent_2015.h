@@ -0,0 +1,54 @@
+#ifndef ADVENT_2015_H
+#define ADVENT_2015_H
+
+/* Generated by cbindgen from src/ffi.rs with `cargo build --features header`. Don't edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Outcome of [advent_solve]. Every status except `Ok` comes with a message describing the problem.
+typedef enum AdventStatus {
+  // The part was solved and the answer written
+  ADVENT_STATUS_OK = 0,
+  // A pointer was null, the part wasn't 1 or 2, or the input wasn't UTF-8
+  ADVENT_STATUS_INVALID_ARGUMENT = 1,
+  // The day has no solver, either because it wasn't compiled in or because it has no such part
+  ADVENT_STATUS_NO_SOLVER = 2,
+  // The input couldn't be parsed
+  ADVENT_STATUS_PARSE_ERROR = 3,
+  // The solver failed, e.g. by overflowing
+  ADVENT_STATUS_SOLVE_ERROR = 4,
+} AdventStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Solve one part of a day, writing the answer, or a message if it couldn't be solved, to
+// `*answer` as a NUL-terminated string which must be released with [advent_free]
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes, or may be null if `len` is 0. `answer` must be null
+// or point to writable memory for a pointer. Nothing is written if it is null.
+enum AdventStatus advent_solve(uint8_t day,
+                               uint8_t part,
+                               const uint8_t *input,
+                               size_t len,
+                               char **answer);
+
+// Release a string written by [advent_solve]. Does nothing if `s` is null.
+//
+// # Safety
+//
+// `s` must be null or a string written by [advent_solve] which hasn't already been released.
+void advent_free(char *s);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* ADVENT_2015_H */
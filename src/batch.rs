@@ -0,0 +1,346 @@
+//! Running every day against the inputs of several accounts at once
+
+use crate::{
+    solver::{self, Solver},
+    verify::{Answers, Status, ANSWERS_FILE},
+    Part,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Outcome of running a single day and part against one account's input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Name of the account directory the input was read from
+    pub account: String,
+
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Part of the puzzle
+    pub part: Part,
+
+    /// Answer produced by the solver, if there was one
+    pub answer: Option<String>,
+
+    /// Comparison with the account's recorded answers, if it has any
+    pub status: Status,
+
+    /// Time taken to parse the input, which is shared by both parts
+    pub parse: Duration,
+
+    /// Time taken to solve this part
+    pub solve: Duration,
+}
+
+/// Run every day which has an input in each account directory, i.e. `<dir>/<account>/day<N>.txt`,
+/// comparing against `<dir>/<account>/answers.txt` where it exists. Days are run in parallel if the
+/// `parallel` feature is enabled.
+pub fn batch(dir: &Path) -> Result<Vec<Outcome>, String> {
+    let mut accounts = Vec::new();
+
+    for path in list(dir)? {
+        if !path.is_dir() {
+            continue;
+        }
+
+        let account = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+
+        let answers = match path.join(ANSWERS_FILE) {
+            answers if answers.exists() => Answers::read(&answers)?,
+            _ => Answers::default(),
+        };
+
+        accounts.push((account, path, answers));
+    }
+
+    let jobs = accounts
+        .iter()
+        .flat_map(|account| solver::solvers().map(move |solver| (account, solver)))
+        .filter(|((_, path, _), solver)| input_path(path, solver.day()).exists())
+        .collect::<Vec<_>>();
+
+    let run = |((account, path, answers), solver): (&(String, PathBuf, Answers), &dyn Solver)| {
+        run_day(account, path, answers, solver)
+    };
+
+    #[cfg(feature = "parallel")]
+    let outcomes = jobs.into_par_iter().map(run).collect::<Vec<_>>();
+
+    #[cfg(not(feature = "parallel"))]
+    let outcomes = jobs.into_iter().map(run).collect::<Vec<_>>();
+
+    Ok(outcomes.into_iter().flatten().collect())
+}
+
+fn list(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| format!("Unable to read {}: {}", dir.display(), e))?;
+
+    paths.sort();
+    Ok(paths)
+}
+
+fn input_path(account: &Path, day: u8) -> PathBuf {
+    account.join(format!("day{}.txt", day))
+}
+
+fn run_day(account: &str, path: &Path, answers: &Answers, solver: &dyn Solver) -> Vec<Outcome> {
+    let day = solver.day();
+    let parts = [Part::One, Part::Two]
+        .iter()
+        .copied()
+        .filter(|&part| solver.has_part(part));
+
+    let start = Instant::now();
+    let path = input_path(path, day);
+    let parsed = fs::read_to_string(&path)
+        .map_err(|e| format!("Unable to read {}: {}", path.display(), e))
+        .and_then(|input| solver::try_parse(solver, &input));
+    let parse = start.elapsed();
+
+    let outcome = |part, answer: Option<String>, status, solve| Outcome {
+        account: account.to_string(),
+        day,
+        part,
+        answer,
+        status,
+        parse,
+        solve,
+    };
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return parts
+                .map(|part| outcome(part, None, Status::Error(e.clone()), Duration::ZERO))
+                .collect()
+        }
+    };
+
+    parts
+        .map(|part| {
+            let start = Instant::now();
+            let result = solver::try_solve(solver, part, parsed.as_ref());
+            let solve = start.elapsed();
+
+            let (answer, status) = answers.check(day, part, result);
+            outcome(part, answer, status, solve)
+        })
+        .collect()
+}
+
+/// Formats batch outcomes as two matrices with a row per day and part and a column per account,
+/// the first showing answers and the second the time taken to solve each part. Answers which
+/// differ from the recorded answer are marked with `!`.
+pub struct Matrix<'a>(pub &'a [Outcome]);
+
+impl Matrix<'_> {
+    fn write_matrix(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        cell: impl Fn(&Outcome) -> String,
+    ) -> fmt::Result {
+        let accounts = self
+            .0
+            .iter()
+            .map(|o| o.account.as_str())
+            .collect::<BTreeSet<_>>();
+
+        let mut rows = BTreeMap::new();
+
+        for o in self.0 {
+            rows.entry((o.day, o.part))
+                .or_insert_with(BTreeMap::new)
+                .insert(o.account.as_str(), cell(o));
+        }
+
+        let widths = accounts
+            .iter()
+            .map(|&account| {
+                rows.values()
+                    .filter_map(|cells| cells.get(account).map(String::len))
+                    .max()
+                    .unwrap_or(0)
+                    .max(account.len())
+            })
+            .collect::<Vec<_>>();
+
+        let mut header = format!("{:>3}  {:>4}", "Day", "Part");
+
+        for (account, &width) in accounts.iter().zip(&widths) {
+            header.push_str(&format!("  {:<width$}", account, width = width));
+        }
+
+        write!(f, "{}", header.trim_end())?;
+
+        for ((day, part), cells) in &rows {
+            let mut row = format!("{:>3}  {:>4}", day, part);
+
+            for (account, &width) in accounts.iter().zip(&widths) {
+                let cell = cells.get(account).map_or("-", String::as_str);
+                row.push_str(&format!("  {:<width$}", cell, width = width));
+            }
+
+            write!(f, "\n{}", row.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Matrix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_matrix(f, |o| match (&o.answer, &o.status) {
+            (Some(answer), Status::Fail(_)) => format!("{}!", answer),
+            (Some(answer), _) => answer.clone(),
+            (None, _) => "ERROR".to_string(),
+        })?;
+
+        writeln!(f, "\n")?;
+        self.write_matrix(f, |o| format!("{:.3?}", o.solve))?;
+
+        let count = |f: fn(&Status) -> bool| self.0.iter().filter(|o| f(&o.status)).count();
+
+        write!(
+            f,
+            "\n\n{} passed, {} failed, {} missing, {} errors",
+            count(|s| *s == Status::Pass),
+            count(|s| matches!(s, Status::Fail(_))),
+            count(|s| *s == Status::Missing),
+            count(|s| matches!(s, Status::Error(_)))
+        )
+    }
+}
+
+/// Format batch outcomes as CSV with a row per account, day and part, and durations in
+/// nanoseconds
+pub fn to_csv(outcomes: &[Outcome]) -> String {
+    let mut csv = "account,day,part,answer,status,expected,error,parse_ns,solve_ns\n".to_string();
+
+    for o in outcomes {
+        let (status, expected, error) = match &o.status {
+            Status::Pass => ("pass", o.answer.as_deref(), None),
+            Status::Fail(expected) => ("fail", Some(expected.as_str()), None),
+            Status::Missing => ("missing", None, None),
+            Status::Error(e) => ("error", None, Some(e.as_str())),
+        };
+
+        let fields = [
+            field(&o.account),
+            o.day.to_string(),
+            o.part.to_string(),
+            field(o.answer.as_deref().unwrap_or_default()),
+            status.to_string(),
+            field(expected.unwrap_or_default()),
+            field(error.unwrap_or_default()),
+            o.parse.as_nanos().to_string(),
+            o.solve.as_nanos().to_string(),
+        ];
+
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quote a CSV field if it contains anything which would otherwise break the row
+fn field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[cfg(all(feature = "day2", feature = "day23"))]
+    static INPUT: &str = include_str!("../input/2015/day23.txt");
+
+    #[test]
+    #[cfg(all(feature = "day2", feature = "day23"))]
+    fn test_batch() {
+        let dir = env::temp_dir().join("advent-2015-batch");
+        let _ = fs::remove_dir_all(&dir);
+
+        for account in &["alice", "bob"] {
+            fs::create_dir_all(dir.join(account)).unwrap();
+        }
+
+        fs::write(dir.join("alice/day23.txt"), INPUT).unwrap();
+        fs::write(dir.join("alice/answers.txt"), "23 1 170\n23 2 1\n").unwrap();
+        fs::write(dir.join("bob/day2.txt"), "2x3x4\n1x1x10").unwrap();
+        fs::write(dir.join("bob/day23.txt"), "inc a\nfoo b").unwrap();
+        fs::write(dir.join("notes.txt"), "not an account").unwrap();
+
+        let outcomes = batch(&dir).unwrap();
+
+        let statuses = outcomes
+            .iter()
+            .map(|o| (o.account.as_str(), o.day, o.part, o.answer.as_deref()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("alice", 23, Part::One, Some("170")),
+                ("alice", 23, Part::Two, Some("247")),
+                ("bob", 2, Part::One, Some("101")),
+                ("bob", 2, Part::Two, Some("48")),
+                ("bob", 23, Part::One, None),
+                ("bob", 23, Part::Two, None),
+            ]
+        );
+
+        assert_eq!(outcomes[0].status, Status::Pass);
+        assert_eq!(outcomes[1].status, Status::Fail("1".to_string()));
+        assert_eq!(outcomes[2].status, Status::Missing);
+
+        let matrix = Matrix(&outcomes).to_string();
+        let lines = matrix.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "Day  Part  alice  bob");
+        assert_eq!(lines[1], "  2     1  -      101");
+        assert_eq!(lines[4], " 23     2  247!   ERROR");
+        assert!(matrix.ends_with("1 passed, 1 failed, 2 missing, 2 errors"));
+
+        let csv = to_csv(&outcomes);
+        let rows = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), 7);
+        assert!(rows[2].starts_with("alice,23,2,247,fail,1,,"));
+        assert!(rows[5].starts_with("bob,23,1,,error,,\"Day 23 input, line 2"));
+    }
+
+    #[test]
+    fn test_missing_dir() {
+        let dir = env::temp_dir().join("advent-2015-batch-missing");
+        assert!(batch(&dir).unwrap_err().starts_with("Unable to read"));
+    }
+
+    #[test]
+    fn test_field() {
+        assert_eq!(field("abc"), "abc");
+        assert_eq!(field("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+}
@@ -0,0 +1,556 @@
+//! Timing each phase of every solver, and comparing timings against a saved baseline
+
+use crate::{
+    memory::{self, Bytes, Usage},
+    solver::{self, Solver},
+    Part,
+};
+#[cfg(feature = "json")]
+use json::{object, JsonValue};
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+#[cfg(feature = "json")]
+use std::{fs, path::Path};
+
+/// Phase of running a solver which is timed separately
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Phase {
+    /// Parsing the raw input
+    Parse,
+
+    /// Solving one part using the parsed input
+    Solve(Part),
+}
+
+impl Phase {
+    #[cfg(feature = "json")]
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parse" => Some(Phase::Parse),
+            "part1" => Some(Phase::Solve(Part::One)),
+            "part2" => Some(Phase::Solve(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => f.pad("parse"),
+            Phase::Solve(Part::One) => f.pad("part1"),
+            Phase::Solve(Part::Two) => f.pad("part2"),
+        }
+    }
+}
+
+/// Timings of one phase of one day over every iteration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Phase which was timed
+    pub phase: Phase,
+
+    /// Fastest iteration
+    pub min: Duration,
+
+    /// Median iteration
+    pub median: Duration,
+
+    /// Slowest iteration
+    pub max: Duration,
+
+    /// Most memory allocated by any iteration, if built with the `allocations` feature
+    pub memory: Option<Usage>,
+}
+
+impl Timing {
+    fn from_samples(
+        day: u8,
+        phase: Phase,
+        mut samples: Vec<Duration>,
+        memory: Option<Usage>,
+    ) -> Self {
+        samples.sort_unstable();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Timing {
+            day,
+            phase,
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+            memory,
+        }
+    }
+}
+
+/// Time parsing the input and solving each part of the given solver. Each phase is run
+/// `iterations` times, which must be at least 1. The memory allocated by every run of each phase
+/// is also measured if the `allocations` feature is enabled, keeping the most of each count.
+pub fn bench(solver: &dyn Solver, input: &str, iterations: usize) -> Result<Vec<Timing>, String> {
+    assert!(iterations > 0, "At least one iteration is required");
+
+    let day = solver.day();
+    let mut timings = Vec::new();
+
+    let mut samples = Vec::with_capacity(iterations);
+    let mut parsed = None;
+    let mut usage = None;
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let (result, memory) = memory::measure(|| solver::try_parse(solver, input));
+        samples.push(start.elapsed());
+        parsed = Some(result?);
+        usage = most(usage, memory);
+    }
+
+    timings.push(Timing::from_samples(day, Phase::Parse, samples, usage));
+
+    let parsed = parsed.unwrap();
+
+    for part in [Part::One, Part::Two].iter().copied() {
+        if !solver.has_part(part) {
+            continue;
+        }
+
+        let mut samples = Vec::with_capacity(iterations);
+        let mut usage = None;
+
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let (result, memory) =
+                memory::measure(|| solver::try_solve(solver, part, parsed.as_ref()));
+            samples.push(start.elapsed());
+            result?;
+            usage = most(usage, memory);
+        }
+
+        timings.push(Timing::from_samples(
+            day,
+            Phase::Solve(part),
+            samples,
+            usage,
+        ));
+    }
+
+    Ok(timings)
+}
+
+/// The most memory allocated by either iteration, if any were measured
+fn most(a: Option<Usage>, b: Option<Usage>) -> Option<Usage> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.most(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Benchmark results which can be saved as JSON and used as a baseline for later runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Number of times each phase was run
+    pub iterations: usize,
+
+    /// Timings of each phase of each day, ordered by day
+    pub timings: Vec<Timing>,
+}
+
+impl Report {
+    /// Serialise the report to JSON, with durations in nanoseconds and memory in bytes
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let timings = self
+            .timings
+            .iter()
+            .map(|t| {
+                let mut timing = object! {
+                    day: t.day,
+                    phase: t.phase.to_string(),
+                    min_ns: t.min.as_nanos() as u64,
+                    median_ns: t.median.as_nanos() as u64,
+                    max_ns: t.max.as_nanos() as u64,
+                };
+
+                if let Some(memory) = t.memory {
+                    timing["allocations"] = memory.allocations.into();
+                    timing["allocated_bytes"] = memory.allocated.into();
+                    timing["peak_bytes"] = memory.peak.into();
+                }
+
+                timing
+            })
+            .collect::<Vec<_>>();
+
+        let report = object! {
+            iterations: self.iterations,
+            timings: JsonValue::Array(timings),
+        };
+
+        report.pretty(2)
+    }
+
+    /// Parse a report previously written by [Report::to_json]
+    #[cfg(feature = "json")]
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let report = json::parse(s).map_err(|e| format!("Invalid benchmark report: {}", e))?;
+
+        let iterations = report["iterations"]
+            .as_usize()
+            .ok_or("Invalid benchmark report: missing iterations")?;
+
+        let timings = report["timings"]
+            .members()
+            .enumerate()
+            .map(|(i, t)| {
+                let invalid = || format!("Invalid benchmark report: bad timing at index {}", i);
+                let nanos = |key: &str| {
+                    t[key]
+                        .as_u64()
+                        .map(Duration::from_nanos)
+                        .ok_or_else(invalid)
+                };
+
+                Ok(Timing {
+                    day: t["day"].as_u8().ok_or_else(invalid)?,
+                    phase: t["phase"]
+                        .as_str()
+                        .and_then(Phase::from_name)
+                        .ok_or_else(invalid)?,
+                    min: nanos("min_ns")?,
+                    median: nanos("median_ns")?,
+                    max: nanos("max_ns")?,
+                    memory: if t["allocations"].is_null() {
+                        None
+                    } else {
+                        Some(Usage {
+                            allocations: t["allocations"].as_u64().ok_or_else(invalid)?,
+                            allocated: t["allocated_bytes"].as_u64().ok_or_else(invalid)?,
+                            peak: t["peak_bytes"].as_u64().ok_or_else(invalid)?,
+                        })
+                    },
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Report {
+            iterations,
+            timings,
+        })
+    }
+
+    /// Read a report from a JSON file
+    #[cfg(feature = "json")]
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+        Report::from_json(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Median timings of one phase in a baseline report and the current report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Phase which was timed
+    pub phase: Phase,
+
+    /// Median in the baseline report
+    pub baseline: Duration,
+
+    /// Median in the current report
+    pub current: Duration,
+}
+
+impl Comparison {
+    /// Percentage change from the baseline, positive if the current run is slower
+    pub fn change(&self) -> f64 {
+        let baseline = self.baseline.as_secs_f64();
+
+        if baseline == 0.0 {
+            0.0
+        } else {
+            (self.current.as_secs_f64() - baseline) / baseline * 100.0
+        }
+    }
+
+    /// Check whether the current run is slower than the baseline by more than the given percentage
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+/// Compare the median timings of every phase present in both reports
+pub fn compare(current: &Report, baseline: &Report) -> Vec<Comparison> {
+    let baseline = baseline
+        .timings
+        .iter()
+        .map(|t| ((t.day, t.phase), t.median))
+        .collect::<HashMap<_, _>>();
+
+    current
+        .timings
+        .iter()
+        .filter_map(|t| {
+            baseline.get(&(t.day, t.phase)).map(|&median| Comparison {
+                day: t.day,
+                phase: t.phase,
+                baseline: median,
+                current: t.median,
+            })
+        })
+        .collect()
+}
+
+/// Formats benchmark timings as a table, with the memory allocated by each phase if it was
+/// measured
+pub struct Table<'a>(pub &'a [Timing]);
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let memory = self.0.iter().any(|t| t.memory.is_some());
+
+        write!(
+            f,
+            "{:>3}  {:<5}  {:>12}  {:>12}  {:>12}",
+            "Day", "Phase", "Min", "Median", "Max"
+        )?;
+
+        if memory {
+            write!(
+                f,
+                "  {:>11}  {:>11}  {:>11}",
+                "Allocations", "Allocated", "Peak"
+            )?;
+        }
+
+        for t in self.0 {
+            write!(
+                f,
+                "\n{:>3}  {:<5}  {:>12}  {:>12}  {:>12}",
+                t.day,
+                t.phase,
+                format!("{:.3?}", t.min),
+                format!("{:.3?}", t.median),
+                format!("{:.3?}", t.max)
+            )?;
+
+            match t.memory {
+                Some(usage) => write!(
+                    f,
+                    "  {:>11}  {:>11}  {:>11}",
+                    usage.allocations,
+                    Bytes(usage.allocated),
+                    Bytes(usage.peak)
+                )?,
+                None if memory => write!(f, "  {:>11}  {:>11}  {:>11}", "-", "-", "-")?,
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats comparisons against a baseline as a table, flagging regressions beyond the threshold
+pub struct ComparisonTable<'a> {
+    /// Comparisons to show, one per row
+    pub comparisons: &'a [Comparison],
+
+    /// Percentage slowdown above which a phase is flagged as a regression
+    pub threshold: f64,
+}
+
+impl fmt::Display for ComparisonTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}  {:<5}  {:>12}  {:>12}  {:>8}",
+            "Day", "Phase", "Baseline", "Current", "Change"
+        )?;
+
+        for c in self.comparisons {
+            write!(
+                f,
+                "\n{:>3}  {:<5}  {:>12}  {:>12}  {:>+7.1}%",
+                c.day,
+                c.phase,
+                format!("{:.3?}", c.baseline),
+                format!("{:.3?}", c.current),
+                c.change()
+            )?;
+
+            if c.is_regression(self.threshold) {
+                write!(f, "  REGRESSION")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "day23")]
+    static INPUT: &str = include_str!("../input/2015/day23.txt");
+
+    fn timing(day: u8, phase: Phase, median: u64) -> Timing {
+        Timing {
+            day,
+            phase,
+            min: Duration::from_nanos(median / 2),
+            median: Duration::from_nanos(median),
+            max: Duration::from_nanos(median * 2),
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_from_samples() {
+        let samples = [5, 1, 4, 2]
+            .iter()
+            .map(|&n| Duration::from_millis(n))
+            .collect();
+        let timing = Timing::from_samples(1, Phase::Parse, samples, None);
+
+        assert_eq!(timing.min, Duration::from_millis(1));
+        assert_eq!(timing.median, Duration::from_millis(3));
+        assert_eq!(timing.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    #[cfg(feature = "day23")]
+    fn test_bench() {
+        let timings = bench(solver::solver(23).unwrap(), INPUT, 3).unwrap();
+        let phases = timings.iter().map(|t| t.phase).collect::<Vec<_>>();
+
+        assert_eq!(
+            phases,
+            vec![
+                Phase::Parse,
+                Phase::Solve(Part::One),
+                Phase::Solve(Part::Two)
+            ]
+        );
+        assert!(timings
+            .iter()
+            .all(|t| t.min <= t.median && t.median <= t.max));
+        assert!(timings
+            .iter()
+            .all(|t| t.memory.is_some() == cfg!(feature = "allocations")));
+    }
+
+    #[test]
+    #[cfg(feature = "day2")]
+    fn test_bench_parse_error() {
+        let result = bench(solver::solver(2).unwrap(), "1x2", 1);
+        assert!(result.unwrap_err().starts_with("Day 2 input, line 1"));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_round_trip() {
+        let report = Report {
+            iterations: 10,
+            timings: vec![
+                timing(4, Phase::Parse, 1_000),
+                timing(4, Phase::Solve(Part::One), 123_456_789_000),
+                Timing {
+                    memory: Some(Usage {
+                        allocations: 3,
+                        allocated: 4_096,
+                        peak: 1_024,
+                    }),
+                    ..timing(25, Phase::Solve(Part::Two), 42)
+                },
+            ],
+        };
+
+        assert_eq!(Report::from_json(&report.to_json()), Ok(report));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_errors() {
+        assert!(Report::from_json("{").is_err());
+        assert!(Report::from_json(r#"{"timings": []}"#).is_err());
+        assert_eq!(
+            Report::from_json(r#"{"iterations": 1, "timings": [{"day": 1, "phase": "part3"}]}"#),
+            Err("Invalid benchmark report: bad timing at index 0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let timings = [
+            timing(10, Phase::Parse, 1_000),
+            Timing {
+                memory: Some(Usage {
+                    allocations: 50,
+                    allocated: 3 << 20,
+                    peak: 1_536,
+                }),
+                ..timing(10, Phase::Solve(Part::One), 2_000)
+            },
+        ];
+
+        assert_eq!(
+            Table(&timings).to_string(),
+            "\
+Day  Phase           Min        Median           Max  Allocations    Allocated         Peak
+ 10  parse     500.000ns       1.000µs       2.000µs            -            -            -
+ 10  part1       1.000µs       2.000µs       4.000µs           50      3.0 MiB      1.5 KiB"
+        );
+        assert!(!Table(&timings[..1]).to_string().contains("Peak"));
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = Report {
+            iterations: 5,
+            timings: vec![
+                timing(9, Phase::Solve(Part::One), 1_000),
+                timing(9, Phase::Solve(Part::Two), 1_000),
+            ],
+        };
+        let current = Report {
+            iterations: 5,
+            timings: vec![
+                timing(9, Phase::Parse, 1_000),
+                timing(9, Phase::Solve(Part::One), 1_050),
+                timing(9, Phase::Solve(Part::Two), 1_500),
+            ],
+        };
+
+        let comparisons = compare(&current, &baseline);
+
+        assert_eq!(comparisons.len(), 2);
+        assert!(!comparisons[0].is_regression(10.0));
+        assert!(comparisons[1].is_regression(10.0));
+        assert_eq!(comparisons[1].change().round(), 50.0);
+
+        let table = ComparisonTable {
+            comparisons: &comparisons,
+            threshold: 10.0,
+        }
+        .to_string();
+
+        assert!(table.contains("  9  part1       1.000µs       1.050µs     +5.0%\n"));
+        assert!(table.ends_with("  9  part2       1.000µs       1.500µs    +50.0%  REGRESSION"));
+    }
+}
@@ -0,0 +1,155 @@
+//! Arithmetic for solvers which can overflow on large inputs. With the `checked` feature enabled an
+//! overflow returns an [Error::Overflow] naming the day and operation, which the solver passes on
+//! to its caller. Otherwise the arithmetic is unchecked as usual, panicking in debug builds and
+//! wrapping in release builds, and never returns an error.
+
+use crate::error::Error;
+use std::ops::{Add, Mul, Shl, Shr};
+
+/// Integer types which solvers do checked arithmetic on
+pub trait Integer:
+    Copy + Add<Output = Self> + Mul<Output = Self> + Shl<u32, Output = Self> + Shr<u32, Output = Self>
+{
+    /// Zero, the sum of nothing
+    const ZERO: Self;
+
+    /// One, the product of nothing
+    const ONE: Self;
+
+    /// Add, returning `None` on overflow
+    fn try_add(self, other: Self) -> Option<Self>;
+
+    /// Multiply, returning `None` on overflow
+    fn try_mul(self, other: Self) -> Option<Self>;
+
+    /// Shift left, returning `None` if the shift is at least as wide as the type
+    fn try_shl(self, bits: u32) -> Option<Self>;
+
+    /// Shift right, returning `None` if the shift is at least as wide as the type
+    fn try_shr(self, bits: u32) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn try_add(self, other: Self) -> Option<Self> {
+                    self.checked_add(other)
+                }
+
+                fn try_mul(self, other: Self) -> Option<Self> {
+                    self.checked_mul(other)
+                }
+
+                fn try_shl(self, bits: u32) -> Option<Self> {
+                    self.checked_shl(bits)
+                }
+
+                fn try_shr(self, bits: u32) -> Option<Self> {
+                    self.checked_shr(bits)
+                }
+            }
+        )*
+    };
+}
+
+integer!(u8, u16, u32, u64, usize, i32, i64);
+
+/// Add two numbers for the given day, where `operation` describes what the sum is for
+pub fn add<T: Integer>(day: u8, operation: &'static str, a: T, b: T) -> Result<T, Error> {
+    if cfg!(feature = "checked") {
+        a.try_add(b).ok_or(Error::Overflow { day, operation })
+    } else {
+        Ok(a + b)
+    }
+}
+
+/// Multiply two numbers for the given day, where `operation` describes what the product is for
+pub fn mul<T: Integer>(day: u8, operation: &'static str, a: T, b: T) -> Result<T, Error> {
+    if cfg!(feature = "checked") {
+        a.try_mul(b).ok_or(Error::Overflow { day, operation })
+    } else {
+        Ok(a * b)
+    }
+}
+
+/// Shift a number left for the given day, where `operation` describes what the shift is for
+pub fn shl<T: Integer>(day: u8, operation: &'static str, a: T, bits: u32) -> Result<T, Error> {
+    if cfg!(feature = "checked") {
+        a.try_shl(bits).ok_or(Error::Overflow { day, operation })
+    } else {
+        Ok(a << bits)
+    }
+}
+
+/// Shift a number right for the given day, where `operation` describes what the shift is for
+pub fn shr<T: Integer>(day: u8, operation: &'static str, a: T, bits: u32) -> Result<T, Error> {
+    if cfg!(feature = "checked") {
+        a.try_shr(bits).ok_or(Error::Overflow { day, operation })
+    } else {
+        Ok(a >> bits)
+    }
+}
+
+/// Add up the numbers for the given day, where `operation` describes what the sum is for
+pub fn sum<T: Integer>(
+    day: u8,
+    operation: &'static str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, Error> {
+    values
+        .into_iter()
+        .try_fold(T::ZERO, |acc, value| add(day, operation, acc, value))
+}
+
+/// Multiply the numbers together for the given day, where `operation` describes what the product
+/// is for
+pub fn product<T: Integer>(
+    day: u8,
+    operation: &'static str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, Error> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |acc, value| mul(day, operation, acc, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(add(1, "adding", 2u8, 3), Ok(5));
+        assert_eq!(mul(1, "multiplying", 4u32, 5), Ok(20));
+        assert_eq!(sum(1, "adding", vec![1usize, 2, 3]), Ok(6));
+        assert_eq!(product(1, "multiplying", vec![2i64, 3, 4]), Ok(24));
+        assert_eq!(product(1, "multiplying", Vec::<u64>::new()), Ok(1));
+        assert_eq!(shl(7, "shifting", 3u16, 2), Ok(12));
+        assert_eq!(shr(7, "shifting", 12u16, 3), Ok(1));
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        assert_eq!(
+            add(6, "brightening a light", 255u8, 1),
+            Err(Error::Overflow {
+                day: 6,
+                operation: "brightening a light"
+            })
+        );
+        assert_eq!(
+            product(24, "multiplying weights", vec![u32::MAX, 2])
+                .unwrap_err()
+                .to_string(),
+            "Day 24: Overflow while multiplying weights"
+        );
+        assert_eq!(mul(15, "scoring", 3i32, -4), Ok(-12));
+        assert!(shl(7, "shifting", 1u16, 16).is_err());
+        assert!(shr(7, "shifting", 1u16, 16).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! Running every strategy for a day on the same input, to check that they agree and see which is
+//! fastest
+
+use crate::{
+    params::Params,
+    solver::{self, Solver},
+    Part,
+};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Result of solving one part with one strategy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    /// Name of the strategy
+    pub strategy: &'static str,
+
+    /// Answer the strategy gave, or why it couldn't give one
+    pub answer: Result<String, String>,
+
+    /// Time taken to solve the part, not including parsing
+    pub time: Duration,
+}
+
+/// Results of solving one part of a day with every strategy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Part of the puzzle
+    pub part: Part,
+
+    /// Result of each strategy, in the order the day lists them
+    pub runs: Vec<Run>,
+}
+
+impl Comparison {
+    /// Check whether every strategy gave the same answer. A strategy which failed disagrees with
+    /// the others.
+    pub fn agrees(&self) -> bool {
+        match self.runs.first() {
+            Some(first) => self
+                .runs
+                .iter()
+                .all(|run| run.answer.is_ok() && run.answer == first.answer),
+            None => true,
+        }
+    }
+
+    /// Time taken by the fastest strategy which gave an answer
+    pub fn fastest(&self) -> Option<Duration> {
+        self.runs
+            .iter()
+            .filter(|run| run.answer.is_ok())
+            .map(|run| run.time)
+            .min()
+    }
+}
+
+/// Parse the input once, then solve each part of the day with every strategy it has
+pub fn compare(
+    solver: &dyn Solver,
+    input: &str,
+    params: &Params,
+) -> Result<Vec<Comparison>, String> {
+    let strategies = solver.strategies();
+
+    if strategies.is_empty() {
+        return Err(format!("Day {} only has one strategy", solver.day()));
+    }
+
+    let parsed = solver::try_parse_with_source(solver, input)?;
+
+    Ok([Part::One, Part::Two]
+        .iter()
+        .copied()
+        .filter(|&part| solver.has_part(part))
+        .map(|part| Comparison {
+            day: solver.day(),
+            part,
+            runs: strategies
+                .iter()
+                .map(|&strategy| {
+                    let start = Instant::now();
+                    let answer =
+                        solver::try_run_strategy(solver, strategy, part, parsed.as_ref(), params);
+
+                    Run {
+                        strategy,
+                        answer: answer.map(|answer| answer.to_string()),
+                        time: start.elapsed(),
+                    }
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Formats comparisons as a table with a row per strategy, showing how many times slower than the
+/// fastest strategy each one was and flagging disagreements with `!`
+pub struct Table<'a>(pub &'a [Comparison]);
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}  {:>4}  {:<24}  {:>16}  {:>12}  {:>8}",
+            "Day", "Part", "Strategy", "Answer", "Time", "Relative"
+        )?;
+
+        for c in self.0 {
+            let flag = if c.agrees() { "" } else { " !" };
+            let fastest = c.fastest();
+
+            for run in &c.runs {
+                let answer = match &run.answer {
+                    Ok(answer) => answer.clone(),
+                    Err(_) => "error".to_string(),
+                };
+                let relative = match (&run.answer, fastest) {
+                    (Ok(_), Some(fastest)) if !fastest.is_zero() => {
+                        format!("x{:.1}", run.time.as_secs_f64() / fastest.as_secs_f64())
+                    }
+                    _ => "-".to_string(),
+                };
+
+                write!(
+                    f,
+                    "\n{:>3}  {:>4}  {:<24}  {:>16}  {:>12}  {:>8}{}",
+                    c.day,
+                    c.part,
+                    run.strategy,
+                    answer,
+                    format!("{:.3?}", run.time),
+                    relative,
+                    flag
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(strategy: &'static str, answer: Result<&str, &str>, millis: u64) -> Run {
+        Run {
+            strategy,
+            answer: answer.map(str::to_string).map_err(str::to_string),
+            time: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day17")]
+    fn test_compare() {
+        let solver = solver::solver(17).unwrap();
+        let mut params = Params::default();
+        params.day17.litres = 25;
+
+        let comparisons = compare(solver, "20\n15\n10\n5\n5", &params).unwrap();
+
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons.iter().all(Comparison::agrees));
+
+        let answers = comparisons[1]
+            .runs
+            .iter()
+            .map(|run| (run.strategy, run.answer.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            vec![
+                ("combinations", Ok("3".to_string())),
+                ("dynamic", Ok("3".to_string()))
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day9"))]
+    fn test_compare_errors() {
+        let params = Params::default();
+
+        assert_eq!(
+            compare(solver::solver(1).unwrap(), "(", &params),
+            Err("Day 1 only has one strategy".to_string())
+        );
+        assert!(compare(solver::solver(9).unwrap(), "London", &params).is_err());
+    }
+
+    #[test]
+    fn test_agrees() {
+        let comparison = |runs| Comparison {
+            day: 9,
+            part: Part::One,
+            runs,
+        };
+
+        assert!(comparison(vec![run("a", Ok("1"), 5), run("b", Ok("1"), 2)]).agrees());
+        assert!(!comparison(vec![run("a", Ok("1"), 5), run("b", Ok("2"), 2)]).agrees());
+        assert!(!comparison(vec![run("a", Err("x"), 5), run("b", Err("x"), 2)]).agrees());
+    }
+
+    #[test]
+    fn test_table() {
+        let comparisons = [Comparison {
+            day: 9,
+            part: Part::Two,
+            runs: vec![
+                run("permutations", Ok("909"), 30),
+                run("held_karp", Ok("910"), 10),
+                run("broken", Err("Solver panicked"), 1),
+            ],
+        }];
+
+        assert_eq!(
+            Table(&comparisons).to_string(),
+            "\
+Day  Part  Strategy                            Answer          Time  Relative
+  9     2  permutations                           909      30.000ms      x3.0 !
+  9     2  held_karp                              910      10.000ms      x1.0 !
+  9     2  broken                               error       1.000ms         - !"
+        );
+    }
+}
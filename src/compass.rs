@@ -1,40 +1,51 @@
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+//! Points and directions on an unbounded grid, shared by the grid-based days
+
+/// One of the four compass directions, with north as increasing `y`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 pub enum Direction {
+    /// Up, written `^`
     North,
+
+    /// Down, written `v`
     South,
+
+    /// Right, written `>`
     East,
+
+    /// Left, written `<`
     West,
 }
 
 impl Direction {
-    pub fn from_char(c: &char) -> Self {
+    /// Parse one of the arrows `^`, `v`, `>` or `<`
+    pub fn from_char(c: &char) -> Option<Self> {
         match c {
-            '^' => Direction::North,
-            'v' => Direction::South,
-            '>' => Direction::East,
-            '<' => Direction::West,
-            _ => panic!("Unrecognised direction"),
+            '^' => Some(Direction::North),
+            'v' => Some(Direction::South),
+            '>' => Some(Direction::East),
+            '<' => Some(Direction::West),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+/// Position on the grid, where the default is the origin
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 pub struct Point {
+    /// Horizontal coordinate, increasing to the east
     pub x: i32,
-    pub y: i32,
-}
 
-impl Default for Point {
-    fn default() -> Self {
-        Point { x: 0, y: 0 }
-    }
+    /// Vertical coordinate, increasing to the north
+    pub y: i32,
 }
 
 impl Point {
+    /// Create a point from its coordinates
     pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
 
+    /// The adjacent point one step in the given direction
     pub fn move_direction(&self, direction: &Direction) -> Point {
         match direction {
             Direction::North => Point {
@@ -56,6 +67,7 @@ impl Point {
         }
     }
 
+    /// The eight points surrounding this one, including diagonals, in rows from the lowest `y`
     pub fn neighbours(&self) -> [Point; 8] {
         [
             Point::new(self.x - 1, self.y - 1),
@@ -1,24 +1,42 @@
-use aoc_runner_derive::aoc;
+//! Day 1: Not Quite Lisp
 
+use crate::error::{lines, Error};
+use aoc_runner_derive::{aoc, aoc_generator};
+
+/// Parse the input to a list of floor changes, +1 for up and -1 for down
+#[aoc_generator(day1)]
+pub fn generator(input: &str) -> Result<Vec<isize>, Error> {
+    let mut steps = Vec::with_capacity(input.len());
+
+    for line in lines(1, input) {
+        for (i, c) in line.text.char_indices() {
+            steps.push(match c {
+                '(' => 1,
+                ')' => -1,
+                _ => {
+                    let fragment = &line.text[i..i + c.len_utf8()];
+                    return Err(line.error(fragment, format!("Unexpected char {}", c)));
+                }
+            });
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Floor Santa ends up on
 #[aoc(day1, part1)]
-pub fn part1(input: &str) -> isize {
-    input.chars().fold(0, |total, c| match c {
-        '(' => total + 1,
-        ')' => total - 1,
-        _ => panic!("Unexpected char {}", c),
-    })
+pub fn part1(input: &[isize]) -> isize {
+    input.iter().sum()
 }
 
+/// Position of the first instruction which takes Santa into the basement, starting at 1
 #[aoc(day1, part2)]
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &[isize]) -> usize {
     let mut floor = 0;
 
-    for (i, c) in input.chars().enumerate() {
-        floor += match c {
-            '(' => 1,
-            ')' => -1,
-            _ => panic!("Unexpected char {}", c),
-        };
+    for (i, step) in input.iter().enumerate() {
+        floor += step;
 
         if floor == -1 {
             return i + 1;
@@ -30,7 +48,8 @@ pub fn part2(input: &str) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{generator, part1, part2};
+    use crate::error::Error;
     use test_case::test_case;
 
     static INPUT: &str = include_str!("../input/2015/day1.txt");
@@ -42,23 +61,31 @@ mod tests {
     #[test_case("()()" => 0; "up down up down")]
     #[test_case("(())" => 0; "up up down down")]
     fn test_part1_examples(x: &str) -> isize {
-        part1(x)
+        part1(&generator(x).unwrap())
     }
 
     #[test]
     fn test_part1_real() {
-        assert_eq!(part1(INPUT), 138);
+        assert_eq!(part1(&generator(INPUT).unwrap()), 138);
     }
 
     #[test_case(")" => 1)]
     #[test_case("(()))" => 5)]
     #[test_case("()()())" => 7)]
     fn test_part2_examples(x: &str) -> usize {
-        part2(x)
+        part2(&generator(x).unwrap())
     }
 
     #[test]
     fn test_part2_real() {
-        assert_eq!(part2(INPUT), 1771);
+        assert_eq!(part2(&generator(INPUT).unwrap()), 1771);
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("(()x)"),
+            Err(Error::parse(1, 1, 4, "(()x)", "Unexpected char x"))
+        );
     }
 }
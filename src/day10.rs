@@ -1,16 +1,44 @@
-use aoc_runner_derive::aoc;
+//! Day 10: Elves Look, Elves Say
 
+use crate::error::{lines, Error};
+use aoc_runner_derive::{aoc, aoc_generator};
+
+/// Longest sequence worth building, far beyond the length reached from any puzzle input
+const MAX_LENGTH: usize = 1 << 26;
+
+/// Parse the starting sequence of digits
+#[aoc_generator(day10)]
+pub fn generator(input: &str) -> Result<String, Error> {
+    let line = lines(10, input)
+        .next()
+        .ok_or_else(|| Error::end_of_input(10, input, "Expected a sequence of digits"))?;
+    let text = line.text.trim();
+
+    if text.is_empty() {
+        return Err(line.error(text, "Expected a sequence of digits"));
+    }
+
+    match text.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, c)) => {
+            Err(line.error(&text[i..i + c.len_utf8()], format!("Unexpected char {}", c)))
+        }
+        None => Ok(text.to_string()),
+    }
+}
+
+/// Length of the result after applying look-and-say 40 times
 #[aoc(day10, part1)]
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> Result<usize, Error> {
     solve(input, 40)
 }
 
+/// Length of the result after applying look-and-say 50 times
 #[aoc(day10, part2)]
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> Result<usize, Error> {
     solve(input, 50)
 }
 
-fn solve(input: &str, i: i32) -> usize {
+fn solve(input: &str, i: i32) -> Result<usize, Error> {
     let mut current = input.trim().to_string();
     let mut temp = String::with_capacity(3_600_000);
 
@@ -20,7 +48,11 @@ fn solve(input: &str, i: i32) -> usize {
 
         while let Some(c) = chars.next() {
             if chars.peek().is_none() || c != *chars.peek().unwrap() {
-                temp.push(std::char::from_digit(count, 10).expect("Count > 9 is not supported"));
+                // only the starting sequence can have runs of more than three digits
+                match std::char::from_digit(count, 10) {
+                    Some(digit) => temp.push(digit),
+                    None => temp.push_str(&count.to_string()),
+                }
                 temp.push(c);
                 count = 1;
             } else {
@@ -28,11 +60,18 @@ fn solve(input: &str, i: i32) -> usize {
             }
         }
 
+        if temp.len() > MAX_LENGTH {
+            return Err(Error::unsolvable(
+                10,
+                format!("The sequence grows longer than {} digits", MAX_LENGTH),
+            ));
+        }
+
         current = temp.clone();
         temp.clear();
     }
 
-    current.len()
+    Ok(current.len())
 }
 
 #[cfg(test)]
@@ -43,11 +82,41 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 252594);
+        assert_eq!(part1(INPUT), Ok(252594));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 3579328);
+        assert_eq!(part2(INPUT), Ok(3579328));
+    }
+
+    #[test]
+    fn test_long_runs() {
+        assert_eq!(solve("1111111111", 1), Ok("101".len()));
+        assert_eq!(solve("1111111111", 2), Ok("111011".len()));
+    }
+
+    #[test]
+    fn test_too_long() {
+        assert_eq!(
+            part2(&"1113222113".repeat(100)),
+            Err(Error::unsolvable(
+                10,
+                format!("The sequence grows longer than {} digits", MAX_LENGTH)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(generator("1113222113\n"), Ok("1113222113".to_string()));
+        assert_eq!(
+            generator("1113x22113"),
+            Err(Error::parse(10, 1, 5, "1113x22113", "Unexpected char x"))
+        );
+        assert_eq!(
+            generator(""),
+            Err(Error::parse(10, 1, 1, "", "Expected a sequence of digits"))
+        );
     }
 }
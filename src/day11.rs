@@ -1,6 +1,38 @@
-use aoc_runner_derive::aoc;
+//! Day 11: Corporate Policy
+
+use crate::error::{lines, Error};
+use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 
+/// Number of letters in a password
+const LENGTH: usize = 8;
+
+/// Parse the current password, which must be eight lowercase letters
+#[aoc_generator(day11)]
+pub fn generator(input: &str) -> Result<String, Error> {
+    let line = lines(11, input)
+        .next()
+        .ok_or_else(|| Error::end_of_input(11, input, "Expected a password"))?;
+    let text = line.text.trim();
+
+    if let Some((i, c)) = text.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+        return Err(line.error(
+            &text[i..i + c.len_utf8()],
+            format!("Unexpected char {}, passwords are lowercase letters", c),
+        ));
+    }
+
+    if text.len() != LENGTH {
+        return Err(line.error(
+            text,
+            format!("Expected {} letters, found {}", LENGTH, text.len()),
+        ));
+    }
+
+    Ok(text.to_string())
+}
+
+/// Next valid password after the input
 #[aoc(day11, part1)]
 pub fn part1(input: &str) -> String {
     let mut password = increment(input.trim());
@@ -12,6 +44,7 @@ pub fn part1(input: &str) -> String {
     password
 }
 
+/// Valid password after the next one
 #[aoc(day11, part2)]
 pub fn part2(input: &str) -> String {
     part1(&part1(input))
@@ -86,6 +119,41 @@ mod tests {
         assert_eq!(part2(INPUT), "cqkaabcc");
     }
 
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(generator("abcdefgh\n"), Ok("abcdefgh".to_string()));
+        assert_eq!(
+            generator("abcDefgh"),
+            Err(Error::parse(
+                11,
+                1,
+                4,
+                "abcDefgh",
+                "Unexpected char D, passwords are lowercase letters"
+            ))
+        );
+        assert_eq!(
+            generator("é!"),
+            Err(Error::parse(
+                11,
+                1,
+                1,
+                "é!",
+                "Unexpected char é, passwords are lowercase letters"
+            ))
+        );
+        assert_eq!(
+            generator("abcdefg"),
+            Err(Error::parse(
+                11,
+                1,
+                1,
+                "abcdefg",
+                "Expected 8 letters, found 7"
+            ))
+        );
+    }
+
     #[test_case("xx" => "xy"; "two chars")]
     #[test_case("xz" => "ya"; "roll over")]
     #[test_case("zz" => "aa"; "overflow")]
@@ -1,16 +1,36 @@
+//! Day 12: JSAbacusFramework.io
+
+use crate::{error::Error, input};
 use aoc_runner_derive::{aoc, aoc_generator};
 use json::JsonValue;
 
+/// Parse the input as a JSON document
 #[aoc_generator(day12)]
-pub fn generator(input: &str) -> JsonValue {
-    json::parse(input).unwrap()
+pub fn generator(input: &str) -> Result<JsonValue, Error> {
+    let input = &*input::normalise(input);
+
+    json::parse(input).map_err(|e| match e {
+        json::Error::UnexpectedCharacter { ch, line, column } => {
+            let text = input.lines().nth(line - 1).unwrap_or_default();
+            Error::parse(
+                12,
+                line,
+                column,
+                text,
+                format!("Unexpected character {}", ch),
+            )
+        }
+        e => Error::end_of_input(12, input, e.to_string()),
+    })
 }
 
+/// Sum of every number in the document
 #[aoc(day12, part1)]
 pub fn part1(json: &JsonValue) -> i32 {
     add_children(json, false)
 }
 
+/// Sum of every number in the document, ignoring any object with a `red` value
 #[aoc(day12, part2)]
 pub fn part2(json: &JsonValue) -> i32 {
     add_children(json, true)
@@ -45,13 +65,31 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let json = generator(INPUT);
+        let json = generator(INPUT).unwrap();
         assert_eq!(part1(&json), 111754);
     }
 
     #[test]
     fn test_part2() {
-        let json = generator(INPUT);
+        let json = generator(INPUT).unwrap();
         assert_eq!(part2(&json), 65402);
     }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("[1,\n {\"a\": x}]").err(),
+            Some(Error::parse(
+                12,
+                2,
+                8,
+                " {\"a\": x}]",
+                "Unexpected character x"
+            ))
+        );
+        assert_eq!(
+            generator("[1, 2").err(),
+            Some(Error::parse(12, 2, 1, "", "Unexpected end of JSON"))
+        );
+    }
 }
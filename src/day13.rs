@@ -1,37 +1,72 @@
+//! Day 13: Knights of the Dinner Table
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Parsed},
+    progress::{self, Progress},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, digit1},
-    combinator::{map, value},
-    IResult,
+    character::complete::char,
+    combinator::{opt, value},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
-#[derive(Clone, Debug, PartialEq)]
+/// Change in one person's happiness from sitting next to another person
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Instruction {
-    first_person: String,
-    second_person: String,
-    happiness: i32,
+    /// Person whose happiness changes
+    pub first_person: String,
+
+    /// Person they would be sitting next to
+    pub second_person: String,
+
+    /// Change in happiness, which is negative if they would lose happiness
+    pub happiness: i32,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = if self.happiness < 0 { "lose" } else { "gain" };
+
+        write!(
+            f,
+            "{} would {} {} happiness units by sitting next to {}.",
+            self.first_person,
+            change,
+            self.happiness.unsigned_abs(),
+            self.second_person
+        )
+    }
 }
 
+/// Parse each line as a happiness rule like `Alice would gain 54 happiness units by sitting next to Bob.`
 #[aoc_generator(day13)]
-pub fn generator(input: &str) -> Vec<Instruction> {
-    input
-        .trim()
-        .lines()
-        .map(|l| parse_instruction(l.trim()).unwrap().1)
-        .collect::<_>()
+pub fn generator(input: &str) -> Result<Vec<Instruction>, Error> {
+    lines(13, input)
+        .map(|line| {
+            parse::line(
+                &line,
+                parse_instruction,
+                "Expected a sentence like 'Alice would gain 54 happiness units by sitting next to Bob.'",
+            )
+        })
+        .collect()
 }
 
-fn parse_instruction(s: &str) -> IResult<&str, Instruction> {
-    let (s, first_person) = alpha1(s)?;
+fn parse_instruction(s: &str) -> Parsed<'_, Instruction> {
+    let (s, first_person) = parse::name(s)?;
     let (s, _) = tag(" would ")(s)?;
     let (s, multiplier) = alt((value(-1, tag("lose ")), value(1, tag("gain "))))(s)?;
-    let (s, value) = map(digit1, |v: &str| v.parse::<i32>().unwrap())(s)?;
+    let (s, value) = parse::integer::<i32>(s)?;
     let (s, _) = tag(" happiness units by sitting next to ")(s)?;
-    let (s, second_person) = alpha1(s)?;
+    let (s, second_person) = parse::name(s)?;
+    let (s, _) = opt(char('.'))(s)?;
 
     let happiness = value * multiplier;
 
@@ -45,17 +80,24 @@ fn parse_instruction(s: &str) -> IResult<&str, Instruction> {
     ))
 }
 
+/// Greatest total change in happiness from any seating arrangement
 #[aoc(day13, part1)]
-pub fn part1(input: &[Instruction]) -> i32 {
-    solve(input, true)
+pub fn part1(input: &[Instruction]) -> Result<i32, Error> {
+    run(input, Part::One, &Progress::new())
 }
 
+/// Greatest total change in happiness after adding yourself, who everyone is indifferent to
 #[aoc(day13, part2)]
-pub fn part2(input: &[Instruction]) -> i32 {
-    solve(input, false)
+pub fn part2(input: &[Instruction]) -> Result<i32, Error> {
+    run(input, Part::Two, &Progress::new())
 }
 
-fn solve(input: &[Instruction], part1: bool) -> i32 {
+/// Solve either part, reporting each seating arrangement tried
+pub fn run(input: &[Instruction], part: Part, progress: &Progress) -> Result<i32, Error> {
+    solve(input, part == Part::One, progress)
+}
+
+fn solve(input: &[Instruction], part1: bool, progress: &Progress) -> Result<i32, Error> {
     let people = input
         .iter()
         .map(|i| i.first_person.as_str())
@@ -72,49 +114,101 @@ fn solve(input: &[Instruction], part1: bool) -> i32 {
         })
         .collect::<HashMap<_, _>>();
 
+    // change in happiness of two people sitting next to each other
+    let pair = |a, b| checked::add(13, "adding up happiness", lookup[&(a, b)], lookup[&(b, a)]);
+
     let mut max = i32::MIN;
 
+    progress.set_total(progress::factorial(people.len()));
+
     for arrangement in people.iter().permutations(people.len()) {
-        let chain: i32 = arrangement
-            .iter()
-            .zip(arrangement.iter().skip(1))
-            .map(|(&&a, &&b)| lookup[&(a, b)] + lookup[&(b, a)])
-            .sum();
+        progress.tick()?;
 
-        let total = if part1 {
+        let mut total = 0;
+
+        for (&&a, &&b) in arrangement.iter().zip(arrangement.iter().skip(1)) {
+            total = checked::add(13, "adding up happiness", total, pair(a, b)?)?;
+        }
+
+        // effectively we are sitting at the "end" of the chain in part 2 and have 0 cost, so no
+        // need to close the circle
+        if part1 {
             // need to create a circle so join last element back to first again
             let first = *arrangement[0];
             let last = *arrangement[arrangement.len() - 1];
-            chain + lookup[&(first, last)] + lookup[&(last, first)]
-        } else {
-            // effectively we are sitting at the "end" of the chain and have 0 cost, so no need to close the circle
-            chain
-        };
+            total = checked::add(13, "adding up happiness", total, pair(first, last)?)?;
+        }
 
         if total > max {
             max = total;
         }
     }
 
-    max
+    Ok(max)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, random};
 
     static INPUT: &str = include_str!("../input/2015/day13.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 664);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(664));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 640);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(640));
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        let input = generator(
+            "Alice would gain 2147483647 happiness units by sitting next to Bob.\n\
+             Bob would gain 2147483647 happiness units by sitting next to Alice.",
+        )
+        .unwrap();
+
+        assert_eq!(
+            part1(&input),
+            Err(Error::Overflow {
+                day: 13,
+                operation: "adding up happiness"
+            })
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let text = "Alice would lose 79 happiness units by sitting next to Carol.";
+        let input = generator(text).unwrap();
+        assert_eq!(input[0].to_string(), text);
+
+        for text in &[INPUT.to_string(), random::generate(13, 5, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            let printed = input.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(generator(&printed.join("\n")).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("Alice would lose 2 happiness units by sitting next to Bob.\nBob would win 3 happiness units by sitting next to Alice.").err(),
+            Some(Error::parse(
+                13,
+                2,
+                11,
+                "Bob would win 3 happiness units by sitting next to Alice.",
+                "Expected a sentence like 'Alice would gain 54 happiness units by sitting next to Bob.'"
+            ))
+        );
     }
 
     #[test]
@@ -122,7 +216,7 @@ mod tests {
         assert_eq!(
             parse_instruction("Alice would lose 2 happiness units by sitting next to Bob."),
             Ok((
-                ".",
+                "",
                 Instruction {
                     first_person: "Alice".to_string(),
                     second_person: "Bob".to_string(),
@@ -134,7 +228,7 @@ mod tests {
         assert_eq!(
             parse_instruction("Carol would gain 42 happiness units by sitting next to David."),
             Ok((
-                ".",
+                "",
                 Instruction {
                     first_person: "Carol".to_string(),
                     second_person: "David".to_string(),
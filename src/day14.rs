@@ -1,29 +1,41 @@
-use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{
-    bytes::complete::tag,
-    character::complete::{alpha1, digit1},
-    combinator::map,
-    IResult,
+//! Day 14: Reindeer Olympics
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Parsed},
+    Part,
 };
-use std::collections::HashMap;
+use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{bytes::complete::tag, combinator::opt};
+use std::{collections::HashMap, fmt};
 
+/// Reindeer which alternates between flying and resting, starting with flying
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct Reindeer {
-    name: String,
-    speed: u32,
-    move_duration: u32,
-    rest_duration: u32,
+    /// Name of the reindeer, which must be unique
+    pub name: String,
+
+    /// Flying speed in km/s
+    pub speed: u32,
+
+    /// Seconds spent flying before each rest
+    pub move_duration: u32,
+
+    /// Seconds spent resting before flying again
+    pub rest_duration: u32,
 }
 
 impl Reindeer {
-    fn parse(s: &str) -> IResult<&str, Self> {
-        let (s, name) = alpha1(s)?;
+    fn parse(s: &str) -> Parsed<'_, Self> {
+        let (s, name) = parse::name(s)?;
         let (s, _) = tag(" can fly ")(s)?;
-        let (s, speed) = map(digit1, |v: &str| v.parse::<u32>().unwrap())(s)?;
+        let (s, speed) = parse::integer(s)?;
         let (s, _) = tag(" km/s for ")(s)?;
-        let (s, move_duration) = map(digit1, |v: &str| v.parse::<u32>().unwrap())(s)?;
+        let (s, move_duration) = parse::integer(s)?;
         let (s, _) = tag(" seconds, but then must rest for ")(s)?;
-        let (s, rest_duration) = map(digit1, |v: &str| v.parse::<u32>().unwrap())(s)?;
+        let (s, rest_duration) = parse::integer(s)?;
+        let (s, _) = opt(tag(" seconds."))(s)?;
 
         Ok((
             s,
@@ -37,8 +49,18 @@ impl Reindeer {
     }
 }
 
+impl fmt::Display for Reindeer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.",
+            self.name, self.speed, self.move_duration, self.rest_duration
+        )
+    }
+}
+
 #[derive(Debug)]
-pub struct ReindeerState {
+struct ReindeerState {
     distance: u32,
     moving: bool,
     duration: u32,
@@ -56,33 +78,56 @@ impl Default for ReindeerState {
     }
 }
 
+/// Parse each line as a reindeer's flying stats
 #[aoc_generator(day14)]
-pub fn generator(input: &str) -> Vec<Reindeer> {
-    input
-        .trim()
-        .lines()
-        .map(|line| Reindeer::parse(line.trim()).unwrap().1)
-        .collect::<Vec<Reindeer>>()
+pub fn generator(input: &str) -> Result<Vec<Reindeer>, Error> {
+    lines(14, input)
+        .map(|line| {
+            parse::line(
+                &line,
+                Reindeer::parse,
+                "Expected a sentence like 'Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.'",
+            )
+        })
+        .collect()
 }
 
+/// Distance travelled by the winning reindeer after 2503 seconds
 #[aoc(day14, part1)]
-pub fn part1(input: &[Reindeer]) -> u32 {
-    solve(input, true)
+pub fn part1(input: &[Reindeer]) -> Result<u32, Error> {
+    solve(input, Part::One, &Params::default())
 }
 
+/// Points of the winning reindeer after 2503 seconds, with a point per second for each leader
 #[aoc(day14, part2)]
-pub fn part2(input: &[Reindeer]) -> u32 {
-    solve(input, false)
+pub fn part2(input: &[Reindeer]) -> Result<u32, Error> {
+    solve(input, Part::Two, &Params::default())
+}
+
+/// Length of the race
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Seconds the race lasts, 2503 in the puzzle
+    pub seconds: u32,
 }
 
-fn solve(input: &[Reindeer], part1: bool) -> u32 {
+impl Default for Params {
+    fn default() -> Self {
+        Params { seconds: 2503 }
+    }
+}
+
+/// Solve either part for a race of the given length
+pub fn solve(input: &[Reindeer], part: Part, params: &Params) -> Result<u32, Error> {
+    let part1 = part == Part::One;
+
     let mut state_map: HashMap<&Reindeer, ReindeerState> = input
         .iter()
         .map(|r| (r, ReindeerState::default()))
         .collect();
 
-    for _ in 0..2504 {
-        for (&reindeer, mut state) in state_map.iter_mut() {
+    for _ in 0..params.seconds {
+        for (&reindeer, state) in state_map.iter_mut() {
             match (
                 state.moving,
                 reindeer.move_duration == state.duration,
@@ -95,13 +140,13 @@ fn solve(input: &[Reindeer], part1: bool) -> u32 {
                 }
                 (true, false, _) => {
                     // keep moving
-                    state.distance += reindeer.speed;
+                    state.distance = checked::add(14, "flying", state.distance, reindeer.speed)?;
                 }
                 (false, _, true) => {
                     // rested for full duration, start moving
                     state.moving = true;
                     state.duration = 0;
-                    state.distance += reindeer.speed;
+                    state.distance = checked::add(14, "flying", state.distance, reindeer.speed)?;
                 }
                 (false, _, false) => { /* keep resting */ }
             }
@@ -120,28 +165,69 @@ fn solve(input: &[Reindeer], part1: bool) -> u32 {
         }
     }
 
-    if part1 {
+    Ok(if part1 {
         state_map.values().map(|r| r.distance).max().unwrap()
     } else {
         state_map.values().map(|r| r.points).max().unwrap()
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, random};
 
     static INPUT: &str = include_str!("../input/2015/day14.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 2660);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(2660));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 1256);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(1256));
+    }
+
+    #[test]
+    fn test_params() {
+        let input = generator(
+            "Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
+Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.",
+        )
+        .unwrap();
+        let params = Params { seconds: 1000 };
+
+        assert_eq!(solve(&input, Part::One, &params), Ok(1120));
+        assert_eq!(solve(&input, Part::Two, &params), Ok(689));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let text = "Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.";
+        let input = generator(text).unwrap();
+        assert_eq!(input[0].to_string(), text);
+
+        for text in &[INPUT.to_string(), random::generate(14, 20, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            let printed = input.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(generator(&printed.join("\n")).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("Comet can fly 14 km/h for 10 seconds, but then must rest for 127 seconds.").err(),
+            Some(Error::parse(
+                14,
+                1,
+                17,
+                "Comet can fly 14 km/h for 10 seconds, but then must rest for 127 seconds.",
+                "Expected a sentence like 'Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.'"
+            ))
+        );
     }
 }
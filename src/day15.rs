@@ -1,150 +1,212 @@
-use crate::Part;
+//! Day 15: Science for Hungry People
+
+use crate::{
+    checked,
+    error::{lines, Error, Line},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 
+/// Properties per teaspoon of an ingredient. A cookie's score is the product of its capacity,
+/// durability, flavour and texture totals, with negative totals counting as 0.
 pub struct Ingredient {
-    capacity: i32,
-    durability: i32,
-    flavour: i32,
-    texture: i32,
-    calories: u32,
+    /// Contribution to the cookie's capacity
+    pub capacity: i32,
+
+    /// Contribution to the cookie's durability
+    pub durability: i32,
+
+    /// Contribution to the cookie's flavour
+    pub flavour: i32,
+
+    /// Contribution to the cookie's texture
+    pub texture: i32,
+
+    /// Calories, which don't count towards the score
+    pub calories: u32,
+}
+
+impl Ingredient {
+    /// Parse a line like `Sugar: capacity -1, durability 0, flavor 0, texture 2, calories 8`
+    fn parse(line: &Line) -> Result<Self, Error> {
+        let text = line.text.trim();
+        let (_, properties) = text
+            .split_once(": ")
+            .ok_or_else(|| line.error(text, "Expected an ingredient name followed by ': '"))?;
+
+        let properties = properties.split(", ").collect::<Vec<_>>();
+        let expected = ["capacity", "durability", "flavor", "texture", "calories"];
+
+        if properties.len() != expected.len() {
+            return Err(line.error(
+                properties[0],
+                format!("Expected the properties {}", expected.join(", ")),
+            ));
+        }
+
+        let mut values = [0i32; 5];
+
+        for ((&property, &name), value) in properties
+            .iter()
+            .zip(expected.iter())
+            .zip(values.iter_mut())
+        {
+            let amount = property
+                .strip_prefix(name)
+                .and_then(|amount| amount.strip_prefix(' '))
+                .ok_or_else(|| line.error(property, format!("Expected property {}", name)))?;
+
+            *value = amount
+                .parse()
+                .map_err(|e| line.error(amount, format!("Invalid {} {:?}: {}", name, amount, e)))?;
+        }
+
+        if values[4] < 0 {
+            return Err(line.error(properties[4], "Calories can't be negative"));
+        }
+
+        Ok(Ingredient {
+            capacity: values[0],
+            durability: values[1],
+            flavour: values[2],
+            texture: values[3],
+            calories: values[4] as u32,
+        })
+    }
 }
 
+/// Parse each line as an ingredient's properties
 #[aoc_generator(day15)]
-pub fn generator(_input: &str) -> Vec<Ingredient> {
-    // Hack: this isn't parsing the input, it's hard-coded from my input
-    let ingredients = vec![
-        Ingredient {
-            capacity: 5,
-            durability: -1,
-            flavour: 0,
-            texture: 0,
-            calories: 5,
-        },
-        Ingredient {
-            capacity: -1,
-            durability: 3,
-            flavour: 0,
-            texture: 0,
-            calories: 1,
-        },
-        Ingredient {
-            capacity: 0,
-            durability: -1,
-            flavour: 4,
-            texture: 0,
-            calories: 6,
-        },
-        Ingredient {
-            capacity: -1,
-            durability: 0,
-            flavour: 0,
-            texture: 2,
-            calories: 8,
-        },
-    ];
-
-    ingredients
+pub fn generator(input: &str) -> Result<Vec<Ingredient>, Error> {
+    lines(15, input)
+        .map(|line| Ingredient::parse(&line))
+        .collect()
 }
 
+/// Highest score of any cookie using 100 teaspoons of ingredients
 #[aoc(day15, part1)]
-pub fn part1(input: &[Ingredient]) -> u32 {
+pub fn part1(input: &[Ingredient]) -> Result<u32, Error> {
     solve(input, Part::One)
 }
 
+/// Highest score of any cookie using 100 teaspoons of ingredients with exactly 500 calories
 #[aoc(day15, part2)]
-pub fn part2(input: &[Ingredient]) -> u32 {
+pub fn part2(input: &[Ingredient]) -> Result<u32, Error> {
     solve(input, Part::Two)
 }
 
-fn solve(ingredients: &[Ingredient], part: Part) -> u32 {
-    // assert ingredients length to alide bounds checks in all the below arithmetic
-    assert_eq!(ingredients.len(), 4, "There must be exactly 4 ingredients");
-
+fn solve(ingredients: &[Ingredient], part: Part) -> Result<u32, Error> {
     let mut max_score = u32::MIN;
-    let permutations = create_permutations();
+    let mut quantities = vec![0; ingredients.len()];
 
-    for quantities in permutations.iter() {
-        let capacity = quantities
-            .iter()
-            .zip(ingredients.iter())
-            .map(|(&q, i)| q as i32 * i.capacity)
-            .sum();
-        let durability = quantities
-            .iter()
-            .zip(ingredients.iter())
-            .map(|(&q, i)| q as i32 * i.durability)
-            .sum();
-        let flavour = quantities
-            .iter()
-            .zip(ingredients.iter())
-            .map(|(&q, i)| q as i32 * i.flavour)
-            .sum();
-        let texture = quantities
-            .iter()
-            .zip(ingredients.iter())
-            .map(|(&q, i)| q as i32 * i.texture)
-            .sum();
-        let calories = quantities
-            .iter()
-            .zip(ingredients.iter())
-            .map(|(&q, i)| q * i.calories)
-            .sum::<u32>();
-
-        // any negative sums should latch to 0 (note: calories can't be < 0)
-        let capacity = std::cmp::max(capacity, 0) as u32;
-        let durability = std::cmp::max(durability, 0) as u32;
-        let flavour = std::cmp::max(flavour, 0) as u32;
-        let texture = std::cmp::max(texture, 0) as u32;
-
-        let score = capacity * durability * flavour * texture;
-
-        max_score = match part {
-            Part::One => std::cmp::max(score, max_score),
-            Part::Two => {
-                if calories == 500 {
-                    std::cmp::max(score, max_score)
-                } else {
-                    max_score
-                }
-            }
+    for_each_mix(&mut quantities, 100, &mut |quantities| {
+        let (score, calories) = score(ingredients, quantities)?;
+
+        if part == Part::One || calories == 500 {
+            max_score = std::cmp::max(score, max_score);
         }
-    }
 
-    max_score
+        Ok(())
+    })?;
+
+    Ok(max_score)
+}
+
+/// Score a cookie made with the given quantity of each ingredient, along with its calories
+fn score(ingredients: &[Ingredient], quantities: &[u32]) -> Result<(u32, u32), Error> {
+    let property = |f: fn(&Ingredient) -> i32| {
+        let sum = quantities
+            .iter()
+            .zip(ingredients.iter())
+            .try_fold(0, |sum, (&q, i)| {
+                let score = checked::mul(15, "scoring an ingredient", q as i32, f(i))?;
+                checked::add(15, "scoring a cookie", sum, score)
+            })?;
+
+        // any negative sums should latch to 0
+        Ok::<_, Error>(std::cmp::max(sum, 0) as u32)
+    };
+
+    let calories = quantities
+        .iter()
+        .zip(ingredients.iter())
+        .try_fold(0, |sum, (&q, i)| {
+            let calories = checked::mul(15, "counting calories", q, i.calories)?;
+            checked::add(15, "counting calories", sum, calories)
+        })?;
+
+    let score = checked::product(
+        15,
+        "scoring a cookie",
+        [
+            property(|i| i.capacity)?,
+            property(|i| i.durability)?,
+            property(|i| i.flavour)?,
+            property(|i| i.texture)?,
+        ],
+    )?;
+
+    Ok((score, calories))
 }
 
-/// Uses the "stars and bars" methodology to find the all combinations of 4 positive
-/// integers that add up to 100
-fn create_permutations() -> Vec<[u32; 4]> {
-    let mut permutations = Vec::with_capacity(156_849); // 99! / (3! * 96!)
-    let n: u32 = 100;
-
-    for a in 0..n + 1 {
-        for b in a..n + 1 {
-            for c in b..n + 1 {
-                permutations.push([a, b - a, c - b, n - c]);
-            }
+/// Call `f` with every way of splitting `teaspoons` between the ingredients, using the "stars and
+/// bars" methodology, stopping at the first error
+fn for_each_mix(quantities: &mut [u32], teaspoons: u32, f: &mut Visit<'_>) -> Result<(), Error> {
+    fn fill(
+        quantities: &mut [u32],
+        index: usize,
+        remaining: u32,
+        f: &mut Visit<'_>,
+    ) -> Result<(), Error> {
+        if index + 1 == quantities.len() {
+            quantities[index] = remaining;
+            return f(quantities);
+        }
+
+        for q in 0..=remaining {
+            quantities[index] = q;
+            fill(quantities, index + 1, remaining - q, f)?;
         }
+
+        Ok(())
+    }
+
+    if quantities.is_empty() {
+        return Ok(());
     }
 
-    permutations
+    fill(quantities, 0, teaspoons, f)
 }
 
+/// Function called with each mix of ingredients
+type Visit<'a> = dyn FnMut(&[u32]) -> Result<(), Error> + 'a;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     static INPUT: &str = include_str!("../input/2015/day15.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 13882464);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(13882464));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 11171160);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(11171160));
+    }
+
+    #[test]
+    fn test_generator_error() {
+        let line = "Sugar: capacity -1, durability 0, flavour 0, texture 2, calories 8";
+
+        assert_eq!(
+            generator(line).err(),
+            Some(Error::parse(15, 1, 35, line, "Expected property flavor"))
+        );
     }
 }
@@ -1,157 +1,296 @@
-use crate::Part;
+//! Day 16: Aunt Sue
+
+use crate::{
+    error::{lines, Error},
+    parse::{self, Failure, Parsed},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use lazy_static::lazy_static;
-use regex::Regex;
+use nom::{bytes::complete::tag, sequence::delimited};
 
+/// What is remembered about one of the aunts called Sue, where `None` means the count is unknown
 #[derive(Debug, PartialEq, Default)]
 pub struct Candidate {
-    id: u32,
-    akitas: Option<u32>,
-    goldfish: Option<u32>,
-    cars: Option<u32>,
-    cats: Option<u32>,
-    children: Option<u32>,
-    samoyeds: Option<u32>,
-    trees: Option<u32>,
-    vizslas: Option<u32>,
-    pomeranians: Option<u32>,
-    perfumes: Option<u32>,
+    /// Number of the aunt, starting at 1
+    pub id: u32,
+
+    /// Number of akitas
+    pub akitas: Option<u32>,
+
+    /// Number of goldfish
+    pub goldfish: Option<u32>,
+
+    /// Number of cars
+    pub cars: Option<u32>,
+
+    /// Number of cats
+    pub cats: Option<u32>,
+
+    /// Number of children
+    pub children: Option<u32>,
+
+    /// Number of samoyeds
+    pub samoyeds: Option<u32>,
+
+    /// Number of trees
+    pub trees: Option<u32>,
+
+    /// Number of vizslas
+    pub vizslas: Option<u32>,
+
+    /// Number of pomeranians
+    pub pomeranians: Option<u32>,
+
+    /// Number of perfumes
+    pub perfumes: Option<u32>,
 }
 
+/// Parse each line as what is remembered about an aunt, like `Sue 1: cars: 9, akitas: 3`
 #[aoc_generator(day16)]
-pub fn generator(input: &str) -> Vec<Candidate> {
-    lazy_static! {
-        static ref SUE: Regex = Regex::new(r"Sue (\d+)").unwrap();
-        static ref ITEM: Regex = Regex::new(r"(\w+): (\d+)").unwrap();
-    }
+pub fn generator(input: &str) -> Result<Vec<Candidate>, Error> {
+    lines(16, input)
+        .map(|line| {
+            parse::line(
+                &line,
+                candidate,
+                "Expected a line like 'Sue 1: cars: 9, akitas: 3, goldfish: 0'",
+            )
+        })
+        .collect()
+}
 
-    input
-        .lines()
-        .map(|l| l.trim())
-        .map(|l| {
-            let id = SUE
-                .captures(l)
-                .unwrap()
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse::<u32>()
-                .unwrap();
-
-            let mut sue = Candidate {
-                id,
-                ..Default::default()
-            };
-
-            for item in ITEM.captures_iter(l) {
-                let label = item.get(1).unwrap().as_str();
-                let quantity = item.get(2).unwrap().as_str().parse::<u32>().unwrap();
-
-                match label {
-                    "akitas" => sue.akitas = Some(quantity),
-                    "goldfish" => sue.goldfish = Some(quantity),
-                    "cars" => sue.cars = Some(quantity),
-                    "cats" => sue.cats = Some(quantity),
-                    "children" => sue.children = Some(quantity),
-                    "samoyeds" => sue.samoyeds = Some(quantity),
-                    "trees" => sue.trees = Some(quantity),
-                    "vizslas" => sue.vizslas = Some(quantity),
-                    "pomeranians" => sue.pomeranians = Some(quantity),
-                    "perfumes" => sue.perfumes = Some(quantity),
-                    _ => panic!("Unrecognised label: {}", label),
-                }
+fn candidate(s: &str) -> Parsed<'_, Candidate> {
+    let (mut s, id) = delimited(tag("Sue "), parse::integer, tag(": "))(s)?;
+
+    let mut sue = Candidate {
+        id,
+        ..Default::default()
+    };
+
+    loop {
+        let (rest, (label, quantity)) = parse::key_value(parse::integer)(s)?;
+
+        let count = match label {
+            "akitas" => &mut sue.akitas,
+            "goldfish" => &mut sue.goldfish,
+            "cars" => &mut sue.cars,
+            "cats" => &mut sue.cats,
+            "children" => &mut sue.children,
+            "samoyeds" => &mut sue.samoyeds,
+            "trees" => &mut sue.trees,
+            "vizslas" => &mut sue.vizslas,
+            "pomeranians" => &mut sue.pomeranians,
+            "perfumes" => &mut sue.perfumes,
+            _ => {
+                return Err(nom::Err::Failure(Failure {
+                    input: s,
+                    message: Some(format!("Unrecognised label: {}", label)),
+                }))
             }
+        };
+        *count = Some(quantity);
 
-            sue
-        })
-        .collect()
+        match tag::<_, _, Failure>(", ")(rest) {
+            Ok((rest, _)) => s = rest,
+            Err(_) => return Ok((rest, sue)),
+        }
+    }
 }
 
+/// Number of the aunt whose remembered things all match the ticker tape exactly
 #[aoc(day16, part1)]
-pub fn part1(input: &[Candidate]) -> u32 {
+pub fn part1(input: &[Candidate]) -> Result<u32, Error> {
     solve(input, Part::One)
 }
 
+/// Number of the aunt who matches the ticker tape, treating cats and trees as lower bounds and pomeranians and goldfish as upper bounds
 #[aoc(day16, part2)]
-pub fn part2(input: &[Candidate]) -> u32 {
+pub fn part2(input: &[Candidate]) -> Result<u32, Error> {
     solve(input, Part::Two)
 }
 
-fn solve(input: &[Candidate], part: Part) -> u32 {
-    for sue in input {
-        let mut matches = 0;
-
-        if sue.children.unwrap_or_default() == 3 {
-            matches += 1;
-        }
+fn solve(input: &[Candidate], part: Part) -> Result<u32, Error> {
+    input
+        .iter()
+        .find(|sue| matches(sue, part))
+        .map(|sue| sue.id)
+        .ok_or_else(|| Error::unsolvable(16, "No Sue matches the MFCSAM readings"))
+}
 
-        if (part == Part::One && sue.cats.unwrap_or_default() == 7)
-            || (part == Part::Two && sue.cats.unwrap_or_default() > 7)
-        {
-            matches += 1;
-        }
+/// Check that everything we remember about this Sue agrees with the MFCSAM readings. In part 2 the
+/// cat and tree readings are lower bounds and the pomeranian and goldfish readings upper bounds.
+fn matches(sue: &Candidate, part: Part) -> bool {
+    let exact = |value: Option<u32>, reading: u32| value.is_none_or(|v| v == reading);
+    let more = |value: Option<u32>, reading: u32| match part {
+        Part::One => exact(value, reading),
+        Part::Two => value.is_none_or(|v| v > reading),
+    };
+    let fewer = |value: Option<u32>, reading: u32| match part {
+        Part::One => exact(value, reading),
+        Part::Two => value.is_none_or(|v| v < reading),
+    };
 
-        if sue.samoyeds.unwrap_or_default() == 2 {
-            matches += 1;
-        }
+    exact(sue.children, 3)
+        && more(sue.cats, 7)
+        && exact(sue.samoyeds, 2)
+        && fewer(sue.pomeranians, 3)
+        && exact(sue.akitas, 0)
+        && exact(sue.vizslas, 0)
+        && fewer(sue.goldfish, 5)
+        && more(sue.trees, 3)
+        && exact(sue.cars, 2)
+        && exact(sue.perfumes, 1)
+}
 
-        if (part == Part::One && sue.pomeranians.unwrap_or_default() == 3)
-            || (part == Part::Two && sue.pomeranians.unwrap_or(4) < 3)
-        {
-            matches += 1;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, random};
+    use itertools::Itertools;
+    use proptest::{collection::vec, prelude::*, sample::subsequence};
 
-        if sue.akitas.unwrap_or(1) == 0 {
-            matches += 1;
-        }
+    static INPUT: &str = include_str!("../input/2015/day16.txt");
 
-        if sue.vizslas.unwrap_or(1) == 0 {
-            matches += 1;
-        }
+    /// Slow but simple solution working directly on the input text, which finds the first Sue
+    /// who has no things contradicting the MFCSAM readings
+    fn reference(input: &str, part: Part) -> Option<u32> {
+        let readings = [
+            ("children", 3, "="),
+            ("cats", 7, ">"),
+            ("samoyeds", 2, "="),
+            ("pomeranians", 3, "<"),
+            ("akitas", 0, "="),
+            ("vizslas", 0, "="),
+            ("goldfish", 5, "<"),
+            ("trees", 3, ">"),
+            ("cars", 2, "="),
+            ("perfumes", 1, "="),
+        ];
 
-        if (part == Part::One && sue.goldfish.unwrap_or(6) == 5)
-            || (part == Part::Two && sue.goldfish.unwrap_or(6) < 5)
-        {
-            matches += 1;
-        }
+        for line in input.lines() {
+            let (sue, things) = line.split_once(": ").unwrap();
+            let mut possible = true;
 
-        if (part == Part::One && sue.trees.unwrap_or_default() == 3)
-            || (part == Part::Two && sue.trees.unwrap_or_default() > 3)
-        {
-            matches += 1;
-        }
+            for thing in things.split(", ") {
+                let (name, amount) = thing.split_once(": ").unwrap();
+                let amount = amount.parse::<u32>().unwrap();
+                let &(_, reading, comparison) = readings.iter().find(|r| r.0 == name).unwrap();
 
-        if sue.cars.unwrap_or_default() == 2 {
-            matches += 1;
-        }
+                possible &= match (part, comparison) {
+                    (Part::Two, ">") => amount > reading,
+                    (Part::Two, "<") => amount < reading,
+                    _ => amount == reading,
+                };
+            }
 
-        if sue.perfumes.unwrap_or_default() == 1 {
-            matches += 1;
+            if possible {
+                return Some(sue.trim_start_matches("Sue ").parse().unwrap());
+            }
         }
 
-        if matches >= 3 {
-            return sue.id;
-        }
+        None
     }
 
-    panic!("Didn't find a matching Sue!")
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Aunts who each remember a few different things, with amounts close to the readings so that
+    /// some of them match
+    fn sues() -> impl Strategy<Value = String> {
+        let things = [
+            "children",
+            "cats",
+            "samoyeds",
+            "pomeranians",
+            "akitas",
+            "vizslas",
+            "goldfish",
+            "trees",
+            "cars",
+            "perfumes",
+        ];
+        let sue = subsequence(things.to_vec(), 1..=4)
+            .prop_flat_map(|names| {
+                names
+                    .into_iter()
+                    .map(|name| (Just(name), prop_oneof![0..=3u32, 0..=10u32]))
+                    .collect::<Vec<_>>()
+            })
+            .prop_shuffle();
 
-    static INPUT: &str = include_str!("../input/2015/day16.txt");
+        vec(sue, 0..=20).prop_map(|sues| {
+            sues.iter()
+                .enumerate()
+                .map(|(i, things)| {
+                    let things = things
+                        .iter()
+                        .map(|(name, amount)| format!("{}: {}", name, amount))
+                        .join(", ");
+                    format!("Sue {}: {}", i + 1, things)
+                })
+                .join("\n")
+        })
+    }
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 373);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(373));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 260);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(260));
+    }
+
+    #[test]
+    fn test_reference() {
+        assert_eq!(reference(INPUT, Part::One), Some(373));
+        assert_eq!(reference(INPUT, Part::Two), Some(260));
+
+        let text = random::generate(16, 20, 1).unwrap();
+        let input = generator(&text).unwrap();
+        assert_eq!(part1(&input).ok(), reference(&text, Part::One));
+        assert_eq!(part2(&input).ok(), reference(&text, Part::Two));
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(
+            part1(&generator("Sue 1: cats: 8").unwrap()),
+            Err(Error::unsolvable(16, "No Sue matches the MFCSAM readings"))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_reference(text in sues()) {
+            let input = generator(&text).unwrap();
+
+            prop_assert_eq!(part1(&input).ok(), reference(&text, Part::One));
+            prop_assert_eq!(part2(&input).ok(), reference(&text, Part::Two));
+        }
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("Sue 1: cars: 9, dogs: 3").err(),
+            Some(Error::parse(
+                16,
+                1,
+                17,
+                "Sue 1: cars: 9, dogs: 3",
+                "Unrecognised label: dogs"
+            ))
+        );
+        assert_eq!(
+            generator("Sue 1: cars: 9\nBob 2: cars: 1").err(),
+            Some(Error::parse(
+                16,
+                2,
+                1,
+                "Bob 2: cars: 1",
+                "Expected a line like 'Sue 1: cars: 9, akitas: 3, goldfish: 0'"
+            ))
+        );
     }
 }
@@ -1,90 +1,307 @@
+//! Day 17: No Such Thing as Too Much
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
+/// Parse each line as the capacity of a container
 #[aoc_generator(day17)]
-pub fn generator(input: &str) -> Vec<u32> {
-    input
-        .lines()
-        .map(|l| l.trim().parse::<u32>().unwrap())
-        .sorted_unstable()
-        .collect()
+pub fn generator(input: &str) -> Result<Vec<u32>, Error> {
+    let containers = lines(17, input)
+        .map(|line| {
+            let text = line.text.trim();
+            text.parse::<u32>()
+                .map_err(|e| line.error(text, format!("Invalid container size {:?}: {}", text, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(containers.into_iter().sorted_unstable().collect())
 }
 
+/// Number of combinations of containers which hold exactly 150 litres
 #[aoc(day17, part1)]
-pub fn part1(input: &[u32]) -> usize {
+pub fn part1(input: &[u32]) -> Result<usize, Error> {
+    solve(input, Part::One, &Params::default())
+}
+
+/// Number of ways to hold exactly 150 litres using the fewest containers
+#[aoc(day17, part2)]
+pub fn part2(input: &[u32]) -> Result<usize, Error> {
+    solve(input, Part::Two, &Params::default())
+}
+
+/// Amount of eggnog to store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Litres which the containers must hold exactly, 150 in the puzzle
+    pub litres: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { litres: 150 }
+    }
+}
+
+/// Solve either part for the given amount of eggnog
+pub fn solve(input: &[u32], part: Part, params: &Params) -> Result<usize, Error> {
+    match part {
+        Part::One => combinations_of(input, params.litres),
+        Part::Two => fewest_combinations(input, params.litres),
+    }
+}
+
+/// Solve either part by trying combinations of containers, starting with the fewest which could
+/// hold the eggnog
+pub fn combinations(input: &[u32], part: Part, params: &Params) -> Result<usize, Error> {
+    solve(input, part, params)
+}
+
+/// Solve either part by counting the ways to fill every amount up to the target with each number
+/// of containers, adding one container at a time. This takes `O(n^2 litres)` time however many
+/// combinations there are.
+pub fn dynamic(input: &[u32], part: Part, params: &Params) -> Result<usize, Error> {
+    let litres = params.litres as usize;
+    let sizes = input.iter().map(|&size| size as usize);
+
+    // more eggnog than every container together holds can't be stored, and would need a huge table
+    if litres > checked::sum(17, "adding up containers", sizes)? {
+        return Ok(0);
+    }
+
+    // ways[k][amount] is the number of ways to hold exactly amount using k containers
+    let mut ways = vec![vec![0usize; litres + 1]; input.len() + 1];
+    ways[0][0] = 1;
+
+    for (i, &size) in input.iter().enumerate() {
+        let size = size as usize;
+
+        if size > litres {
+            continue;
+        }
+
+        for k in (0..=i).rev() {
+            for amount in (0..=litres - size).rev() {
+                let total = ways[k + 1][amount + size];
+                ways[k + 1][amount + size] =
+                    checked::add(17, "counting combinations", total, ways[k][amount])?;
+            }
+        }
+    }
+
+    let mut counts = ways[1..].iter().map(|ways| ways[litres]);
+
+    match part {
+        Part::One => checked::sum(17, "counting combinations", counts),
+        Part::Two => Ok(counts.find(|&count| count > 0).unwrap_or_default()),
+    }
+}
+
+/// Count the combinations of the sorted containers which add up to the given litres
+fn combinations_of(input: &[u32], litres: u32) -> Result<usize, Error> {
     let mut acc = 0;
     let mut max_sum = 0;
 
+    // containers which hold nothing fit in any combination, so keep going until there's too much
     for &x in input.iter() {
-        acc += x;
+        acc = checked::add(17, "adding up containers", acc, x)?;
         max_sum += 1;
 
-        if acc >= 150 {
+        if acc > litres {
             break;
         }
     }
 
-    acc = 0;
-    let mut min_sum = 0;
+    let min_sum = fewest_containers(input, litres)?;
 
-    for &x in input.iter().rev() {
-        acc += x;
-        min_sum += 1;
+    // a combination needs at least one container, even to hold nothing
+    (min_sum.max(1)..max_sum + 1).try_fold(0, |total, k| {
+        checked::add(17, "counting combinations", total, count(input, k, litres)?)
+    })
+}
 
-        if acc >= 150 {
-            break;
+/// Count the combinations of the sorted containers which add up to the given litres using as few
+/// containers as possible
+fn fewest_combinations(input: &[u32], litres: u32) -> Result<usize, Error> {
+    let min_sum = fewest_containers(input, litres)?;
+
+    // the fewest containers which could hold the eggnog might not add up to exactly the right
+    // amount, so keep trying more containers until some combination does
+    for k in min_sum.max(1)..=input.len() {
+        let count = count(input, k, litres)?;
+
+        if count > 0 {
+            return Ok(count);
         }
     }
 
-    (min_sum..max_sum + 1)
-        .map(|k| {
-            input
-                .iter()
-                .combinations(k)
-                .map(|c| c.into_iter().sum::<u32>())
-                .filter(|&sum| sum == 150)
-                .count()
-        })
-        .sum()
+    Ok(0)
 }
 
-#[aoc(day17, part2)]
-pub fn part2(input: &[u32]) -> usize {
+/// Fewest of the sorted containers which could hold the given litres, filling the biggest first
+fn fewest_containers(input: &[u32], litres: u32) -> Result<usize, Error> {
     let mut acc = 0;
     let mut min_sum = 0;
 
     for &x in input.iter().rev() {
-        acc += x;
+        acc = checked::add(17, "adding up containers", acc, x)?;
         min_sum += 1;
 
-        if acc >= 150 {
+        if acc >= litres {
             break;
         }
     }
 
-    input
-        .iter()
-        .combinations(min_sum)
-        .map(|c| c.into_iter().sum::<u32>())
-        .filter(|&sum| sum == 150)
-        .count()
+    Ok(min_sum)
+}
+
+/// Count the combinations of `k` containers which add up to the given litres
+fn count(input: &[u32], k: usize, litres: u32) -> Result<usize, Error> {
+    let mut count = 0;
+
+    for c in input.iter().combinations(k) {
+        if checked::sum(17, "adding up containers", c.into_iter().copied())? == litres {
+            count += 1;
+        }
+    }
+
+    Ok(count)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, random};
+    use proptest::{collection::vec, prelude::*};
 
     static INPUT: &str = include_str!("../input/2015/day17.txt");
 
+    /// Slow but simple solution which tries every subset of containers, returning the number of
+    /// ways to hold the eggnog and the number of ways using the fewest containers
+    fn reference(input: &[u32], litres: u32) -> (usize, usize) {
+        let subsets = (1..1u32 << input.len())
+            .filter(|subset| {
+                let total = (0..input.len())
+                    .filter(|i| subset & (1 << i) != 0)
+                    .map(|i| input[i])
+                    .sum::<u32>();
+
+                total == litres
+            })
+            .map(|subset| subset.count_ones())
+            .collect::<Vec<_>>();
+
+        let fewest = subsets.iter().min().copied().unwrap_or_default();
+        let with_fewest = subsets.iter().filter(|&&count| count == fewest).count();
+
+        (subsets.len(), with_fewest)
+    }
+
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 654);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(654));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 57);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(57));
+    }
+
+    #[test]
+    fn test_params() {
+        let input = generator("20\n15\n10\n5\n5").unwrap();
+        let params = Params { litres: 25 };
+
+        assert_eq!(solve(&input, Part::One, &params), Ok(4));
+        assert_eq!(solve(&input, Part::Two, &params), Ok(3));
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let input = generator(INPUT).unwrap();
+        let params = Params::default();
+
+        for part in [Part::One, Part::Two] {
+            assert_eq!(
+                dynamic(&input, part, &params),
+                combinations(&input, part, &params)
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_eggnog() {
+        let params = Params { litres: 0 };
+
+        for strategy in [combinations, dynamic] {
+            assert_eq!(strategy(&[0, 0, 5], Part::One, &params), Ok(3));
+            assert_eq!(strategy(&[0, 0, 5], Part::Two, &params), Ok(2));
+            assert_eq!(strategy(&[], Part::One, &params), Ok(0));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        let input = [u32::MAX - 1, u32::MAX - 1];
+        let params = Params { litres: u32::MAX };
+        let overflow = Err(Error::Overflow {
+            day: 17,
+            operation: "adding up containers",
+        });
+
+        for part in [Part::One, Part::Two] {
+            assert_eq!(combinations(&input, part, &params), overflow);
+        }
+    }
+
+    #[test]
+    fn test_random_input() {
+        let input = generator(&random::generate(17, 10, 1).unwrap()).unwrap();
+        let (ways, fewest) = reference(&input, 150);
+
+        assert_eq!(part1(&input), Ok(ways));
+        assert_eq!(part2(&input), Ok(fewest));
+    }
+
+    /// Containers as the generator returns them, in increasing order of size
+    fn containers() -> impl Strategy<Value = Vec<u32>> {
+        vec(0..=60u32, 0..=12).prop_map(|mut containers| {
+            containers.sort_unstable();
+            containers
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_reference(input in containers(), litres in 0..=200u32) {
+            let (ways, fewest) = reference(&input, litres);
+            let params = Params { litres };
+
+            prop_assert_eq!(combinations(&input, Part::One, &params), Ok(ways));
+            prop_assert_eq!(combinations(&input, Part::Two, &params), Ok(fewest));
+            prop_assert_eq!(dynamic(&input, Part::One, &params), Ok(ways));
+            prop_assert_eq!(dynamic(&input, Part::Two, &params), Ok(fewest));
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("20\n-15").err(),
+            Some(Error::parse(
+                17,
+                2,
+                1,
+                "-15",
+                "Invalid container size \"-15\": invalid digit found in string"
+            ))
+        );
     }
 }
@@ -1,50 +1,127 @@
-use crate::{compass::Point, Part};
+//! Day 18: Like a GIF For Your Yard
+
+use crate::{
+    compass::Point,
+    error::{lines, Error},
+    render::{Image, NoopRecorder, Recorder},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 
+/// Parse the grid of lights, returning the position of each light which is on
 #[aoc_generator(day18)]
-pub fn generator(input: &str) -> Vec<Point> {
-    let input = input.lines().map(|l| l.trim()).collect::<Vec<_>>();
+pub fn generator(input: &str) -> Result<Vec<Point>, Error> {
     let mut lights = Vec::new();
 
-    for (y, &line) in input.iter().enumerate() {
-        for (x, c) in line.bytes().enumerate() {
-            if c == b'#' {
-                lights.push(Point::new(x as i32, y as i32));
+    for (y, line) in lines(18, input).enumerate() {
+        let text = line.text.trim();
+
+        for (x, c) in text.char_indices() {
+            match c {
+                '#' => lights.push(Point::new(x as i32, y as i32)),
+                '.' => {}
+                _ => {
+                    let fragment = &text[x..x + c.len_utf8()];
+                    return Err(line.error(fragment, format!("Unexpected char {}", c)));
+                }
             }
         }
     }
 
-    lights
+    Ok(lights)
 }
 
+/// Number of lights on after 100 steps
 #[aoc(day18, part1)]
 pub fn part1(input: &[Point]) -> usize {
-    solve(input, &Part::One)
+    solve(input, Part::One, &Params::default())
 }
 
+/// Number of lights on after 100 steps, with the four corners stuck on
 #[aoc(day18, part2)]
 pub fn part2(input: &[Point]) -> usize {
-    solve(input, &Part::Two)
+    solve(input, Part::Two, &Params::default())
+}
+
+/// Size of the grid and how long to animate it for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Width and height of the grid, 100 in the puzzle. Lights outside the grid are ignored.
+    pub size: usize,
+
+    /// Number of steps to animate, 100 in the puzzle
+    pub steps: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            size: 100,
+            steps: 100,
+        }
+    }
+}
+
+/// Largest grid which can be animated, so that every position fits in a [Point]
+pub const MAX_SIZE: usize = 1 << 15;
+
+/// Number of lights on after animating the grid with the given size and steps
+pub fn solve(input: &[Point], part: Part, params: &Params) -> usize {
+    animate(input, &part, params, &mut NoopRecorder).len()
+}
+
+/// Render the lights which are on after the last step, recording the initial state and the
+/// grid after each step
+#[doc(hidden)]
+pub fn render(input: &[Point], part: Part, params: &Params, recorder: &mut dyn Recorder) -> Image {
+    image(&animate(input, &part, params, recorder), params.size)
+}
+
+fn image(lights: &HashSet<Point>, size: usize) -> Image {
+    let mut image = Image::new(size, size, 1);
+
+    for light in lights.iter().filter(|l| in_grid(l, size)) {
+        image.set(light.x as usize, light.y as usize, 1);
+    }
+
+    image
 }
 
-fn solve(input: &[Point], part: &Part) -> usize {
+fn animate(
+    input: &[Point],
+    part: &Part,
+    params: &Params,
+    recorder: &mut dyn Recorder,
+) -> HashSet<Point> {
+    let size = params.size;
     let mut current = input.iter().cloned().collect::<HashSet<Point>>();
     let mut next = HashSet::new();
 
-    for _ in 0..100 {
-        for x in 0..100 {
-            for y in 0..100 {
+    // the corners are stuck on from the start in part 2
+    if part == &Part::Two && size > 0 {
+        let last = size as i32 - 1;
+
+        for (x, y) in [(0, 0), (0, last), (last, 0), (last, last)] {
+            current.insert(Point::new(x, y));
+        }
+    }
+
+    recorder.frame(&|| image(&current, size));
+
+    for _ in 0..params.steps {
+        for x in 0..size as i32 {
+            for y in 0..size as i32 {
                 let light = Point::new(x, y);
                 let lit_neighbours = light
                     .neighbours()
                     .iter()
-                    .filter(|&n| n.x >= 0 && n.y >= 0 && n.x < 100 && n.y < 100)
+                    .filter(|&n| in_grid(n, size))
                     .filter(|&n| current.contains(n))
                     .count();
 
-                if (part == &Part::Two && is_corner(&light))
-                    || (current.contains(&light) && lit_neighbours >= 2 && lit_neighbours <= 3)
+                if (part == &Part::Two && is_corner(&light, size))
+                    || (current.contains(&light) && (2..=3).contains(&lit_neighbours))
                     || (!current.contains(&light) && lit_neighbours == 3)
                 {
                     next.insert(light);
@@ -58,31 +135,56 @@ fn solve(input: &[Point], part: &Part) -> usize {
         for p in next.drain() {
             current.insert(p);
         }
+
+        recorder.frame(&|| image(&current, size));
     }
 
-    current.len()
+    current
 }
 
-fn is_corner(point: &Point) -> bool {
-    let (x, y) = (point.x, point.y);
-    (y == 99 || y == 0) && (x == 99 || x == 0)
+fn in_grid(point: &Point, size: usize) -> bool {
+    point.x >= 0 && point.y >= 0 && (point.x as usize) < size && (point.y as usize) < size
+}
+
+fn is_corner(point: &Point, size: usize) -> bool {
+    let (x, y, last) = (point.x, point.y, size as i32 - 1);
+    (y == last || y == 0) && (x == last || x == 0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     static INPUT: &str = include_str!("../input/2015/day18.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part1(&input), 1061);
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part2(&input), 1006);
     }
+
+    #[test]
+    fn test_params() {
+        let input = generator(".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..").unwrap();
+        let params = Params { size: 6, steps: 4 };
+        assert_eq!(solve(&input, Part::One, &params), 4);
+
+        let params = Params { size: 6, steps: 5 };
+        assert_eq!(solve(&input, Part::Two, &params), 17);
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("#..#\n.#o.").err(),
+            Some(Error::parse(18, 2, 3, ".#o.", "Unexpected char o"))
+        );
+    }
 }
@@ -1,53 +1,109 @@
+//! Day 19: Medicine for Rudolph
+
+use crate::{
+    error::{Error, Line},
+    input,
+    parse::{self, Parsed},
+    progress::Progress,
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use nom::{bytes::complete::tag, combinator::all_consuming, sequence::separated_pair};
 use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
 };
 
-#[derive(Debug)]
+/// Replacement rules and the medicine molecule which they apply to
+#[derive(Debug, PartialEq, Eq)]
 pub struct Input {
-    molecule: String,
-    rules: Vec<Rule>,
+    /// Medicine molecule, a sequence of elements like `CRnCaSi`
+    pub molecule: String,
+
+    /// Every replacement rule, in the order they were written
+    pub rules: Vec<Rule>,
 }
 
-#[derive(Debug, PartialEq)]
+/// Rule allowing one element of a molecule to be replaced by several elements
+#[derive(Debug, PartialEq, Eq)]
 pub struct Rule {
-    input: String,
-    output: String,
+    /// Element which is replaced
+    pub input: String,
+
+    /// Elements which replace it
+    pub output: String,
 }
 
-impl FromStr for Rule {
-    type Err = String;
+impl Rule {
+    /// Parse a replacement rule like `H => HO`
+    fn parse(s: &str) -> Parsed<'_, Self> {
+        let (s, (input, output)) = separated_pair(parse::name, tag(" => "), parse::name)(s)?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split = s.split(" => ").collect::<Vec<_>>();
+        Ok((
+            s,
+            Rule {
+                input: input.into(),
+                output: output.into(),
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.input, self.output)
+    }
+}
 
-        match split.len() {
-            2 => Ok(Rule {
-                input: split[0].into(),
-                output: split[1].into(),
-            }),
-            _ => Err(format!("Unrecognised rule string: {}", s)),
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
         }
+
+        write!(f, "\n{}", self.molecule)
     }
 }
 
+/// Parse the replacement rules, followed by a blank line and the medicine molecule
 #[aoc_generator(day19)]
-pub fn generator(input: &str) -> Input {
-    let rules = input
-        .lines()
-        .map(|l| l.trim())
-        .take_while(|&l| !l.is_empty())
-        .map(|l| Rule::from_str(l).unwrap())
-        .collect();
-
-    Input {
-        molecule: input.lines().last().unwrap().trim().into(),
-        rules,
+pub fn generator(input: &str) -> Result<Input, Error> {
+    // blank lines are kept, as one separates the rules from the molecule
+    let mut lines = input::lines(input).enumerate().map(|(i, text)| Line {
+        day: 19,
+        number: i + 1,
+        text,
+    });
+
+    let rules = lines
+        .by_ref()
+        .take_while(|l| !l.text.trim().is_empty())
+        .map(|l| parse::line(&l, Rule::parse, "Expected a rule like 'H => HO'"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let molecule = lines.find(|l| !l.text.trim().is_empty()).ok_or_else(|| {
+        Error::end_of_input(19, input, "Expected a molecule after the replacement rules")
+    })?;
+
+    if let Some(extra) = lines.find(|l| !l.text.trim().is_empty()) {
+        return Err(extra.error(extra.text, "Unexpected line after the molecule"));
     }
+
+    // The molecule must be made only of letters to be addressed by byte index
+    let molecule = parse::line(
+        &molecule,
+        all_consuming(parse::name),
+        "Expected a molecule made only of letters",
+    )?;
+
+    Ok(Input {
+        molecule: molecule.into(),
+        rules,
+    })
 }
 
+/// Number of distinct molecules made by a single replacement
 #[aoc(day19, part1)]
 pub fn part1(input: &Input) -> usize {
     let mut generated = HashSet::new();
@@ -58,7 +114,7 @@ pub fn part1(input: &Input) -> usize {
     }
 
     // check 1-length rules
-    for (i, c) in input.molecule.chars().enumerate() {
+    for (i, c) in input.molecule.char_indices() {
         let key: &str = &format!("{}", c);
 
         if lookup.contains_key(key) {
@@ -73,11 +129,10 @@ pub fn part1(input: &Input) -> usize {
     }
 
     // check 2-length rules
-    for (i, (c1, c2)) in input
+    for ((i, c1), c2) in input
         .molecule
-        .chars()
+        .char_indices()
         .zip(input.molecule.chars().skip(1))
-        .enumerate()
     {
         let key: &str = &format!("{}{}", c1, c2);
 
@@ -95,42 +150,460 @@ pub fn part1(input: &Input) -> usize {
     generated.len()
 }
 
+/// Fewest steps needed to make the molecule from `e`
 #[aoc(day19, part2)]
-pub fn part2(input: &Input) -> usize {
-    let mut count = 0;
-    let mut molecule = input.molecule.clone();
-
-    // sort from longest to shortest output
-    let mut rules = input.rules.iter().collect::<Vec<_>>();
-    rules.sort_unstable_by(|a, b| b.output.len().cmp(&a.output.len()));
-
-    while !molecule.trim_matches('e').is_empty() {
-        for rule in input.rules.iter() {
-            if molecule.contains(&rule.output) {
-                molecule = molecule.replacen(&rule.output, &rule.input, 1);
-                count += 1;
+pub fn part2(input: &Input) -> Result<usize, Error> {
+    run(input, Part::Two, &Progress::new())
+}
+
+/// Solve either part, reporting each element of the molecule parsed or molecule searched
+pub fn run(input: &Input, part: Part, progress: &Progress) -> Result<usize, Error> {
+    match part {
+        Part::One => Ok(part1(input)),
+        Part::Two => fewest_steps(input, progress),
+    }
+}
+
+/// Count the steps needed to make the molecule from `e`. Giving each element a weight of 1, except
+/// for `Rn` and `Ar` which weigh 0 and `Y` which weighs -1, every rule in the puzzle adds exactly 1
+/// to the weight of the molecule. When the rules have that shape, every way of making the molecule
+/// takes the same number of steps, so it's enough to check that the molecule can be made at all.
+/// Other rules are solved by searching backwards from the molecule, undoing replacements.
+fn fewest_steps(input: &Input, progress: &Progress) -> Result<usize, Error> {
+    let molecule = elements(&input.molecule);
+
+    if molecule.is_empty() {
+        return Err(Error::unsolvable(19, "The molecule is empty"));
+    }
+
+    if molecule == ["e"] {
+        return Ok(0);
+    }
+
+    let rules = input
+        .rules
+        .iter()
+        .map(
+            |rule| match (&elements(&rule.input)[..], elements(&rule.output)) {
+                (&[input], output) if !output.is_empty() => Ok((input, output)),
+                _ => Err(Error::unsolvable(
+                    19,
+                    format!("Rule {} doesn't replace one element with others", rule),
+                )),
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match start_weight(&rules) {
+        Some(start) if can_make(&rules, &molecule, progress)? => {
+            Ok((1 + weight(&molecule) - start) as usize)
+        }
+        Some(_) => Err(Error::unsolvable(19, "The molecule can't be made from e")),
+        None => search(&rules, &molecule, progress),
+    }
+}
+
+/// Split a molecule into its elements, each an uppercase letter followed by any lowercase letters.
+/// Lowercase letters at the start, like the `e` which molecules are made from, are an element too.
+fn elements(molecule: &str) -> Vec<&str> {
+    let mut elements = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in molecule.char_indices().skip(1) {
+        if c.is_ascii_uppercase() {
+            elements.push(&molecule[start..i]);
+            start = i;
+        }
+    }
+
+    if !molecule.is_empty() {
+        elements.push(&molecule[start..]);
+    }
+
+    elements
+}
+
+/// Weight of a molecule, which grows by exactly 1 with every step in the puzzle
+fn weight(molecule: &[&str]) -> isize {
+    molecule
+        .iter()
+        .map(|&element| match element {
+            "Rn" | "Ar" => 0,
+            "Y" => -1,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Weight of the molecules which `e` can be replaced with, if they all weigh the same and every
+/// other rule adds exactly 1 to the weight
+fn start_weight(rules: &[(&str, Vec<&str>)]) -> Option<isize> {
+    let mut start = None;
+
+    for (input, output) in rules {
+        if *input == "e" {
+            if *start.get_or_insert(weight(output)) != weight(output) {
+                return None;
+            }
+        } else if weight(output) != weight(&[input]) + 1 {
+            return None;
+        }
+    }
+
+    start
+}
+
+/// Check whether the molecule can be made from `e` with an Earley parser, which treats the rules
+/// as a grammar. Each item is a rule, how many of its output elements have been matched so far and
+/// the position in the molecule where the rule started.
+fn can_make(
+    rules: &[(&str, Vec<&str>)],
+    molecule: &[&str],
+    progress: &Progress,
+) -> Result<bool, Error> {
+    type Item = (usize, usize, usize);
+
+    let mut sets = vec![Vec::<Item>::new(); molecule.len() + 1];
+    let mut seen = vec![HashSet::<Item>::new(); molecule.len() + 1];
+
+    let mut add = |sets: &mut Vec<Vec<Item>>, position: usize, item: Item| {
+        if seen[position].insert(item) {
+            sets[position].push(item);
+        }
+    };
+
+    for (r, (input, _)) in rules.iter().enumerate() {
+        if *input == "e" {
+            add(&mut sets, 0, (r, 0, 0));
+        }
+    }
+
+    progress.set_total(molecule.len() as u64 + 1);
+
+    for position in 0..=molecule.len() {
+        progress.tick()?;
+
+        let mut i = 0;
+        while let Some(&(r, matched, origin)) = sets[position].get(i) {
+            i += 1;
+            let (input, output) = &rules[r];
+
+            match output.get(matched) {
+                // the rule is complete, so advance every rule which was waiting for its input
+                None => {
+                    let waiting = sets[origin]
+                        .iter()
+                        .filter(|&&(r, matched, _)| rules[r].1.get(matched) == Some(input))
+                        .copied()
+                        .collect::<Vec<_>>();
+
+                    for (r, matched, start) in waiting {
+                        add(&mut sets, position, (r, matched + 1, start));
+                    }
+                }
+                Some(next) => {
+                    // the next element may be replaced by another rule
+                    for (r, _) in rules.iter().enumerate().filter(|(_, (i, _))| i == next) {
+                        add(&mut sets, position, (r, 0, position));
+                    }
+
+                    // or left as it is
+                    if molecule.get(position) == Some(next) {
+                        add(&mut sets, position + 1, (r, matched + 1, origin));
+                    }
+                }
             }
         }
     }
 
-    count
+    Ok(sets[molecule.len()].iter().any(|&(r, matched, origin)| {
+        let (input, output) = &rules[r];
+        origin == 0 && *input == "e" && matched == output.len()
+    }))
+}
+
+/// Search backwards from the molecule, undoing every possible replacement until reaching `e`. Every
+/// rule replaces an element with at least one element, so undoing them never makes the molecule
+/// longer and the search always finishes.
+fn search(
+    rules: &[(&str, Vec<&str>)],
+    molecule: &[&str],
+    progress: &Progress,
+) -> Result<usize, Error> {
+    let mut queue = VecDeque::from(vec![(molecule.to_vec(), 0)]);
+    let mut seen = HashSet::new();
+
+    while let Some((molecule, steps)) = queue.pop_front() {
+        progress.check()?;
+
+        for (input, output) in rules {
+            if *input == "e" {
+                if molecule == *output {
+                    return Ok(steps + 1);
+                }
+
+                continue;
+            }
+
+            for i in 0..molecule.len() {
+                if molecule[i..].starts_with(output) {
+                    let mut previous = molecule.clone();
+                    previous.splice(i..i + output.len(), [*input]);
+
+                    if seen.insert(previous.clone()) {
+                        queue.push_back((previous, steps + 1));
+                    }
+                }
+            }
+        }
+    }
+
+    Err(Error::unsolvable(19, "The molecule can't be made from e"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, random};
+    use proptest::{collection::vec, prelude::*, sample::Index};
 
     static INPUT: &str = include_str!("../input/2015/day19.txt");
 
+    /// Slow but simple solution which searches backwards from the molecule, undoing every possible
+    /// replacement until it reaches `e`
+    fn reference(input: &Input) -> Option<usize> {
+        let mut queue = VecDeque::from(vec![(input.molecule.clone(), 0)]);
+        let mut seen = HashSet::new();
+
+        while let Some((molecule, steps)) = queue.pop_front() {
+            for rule in &input.rules {
+                if rule.input == "e" {
+                    if molecule == rule.output {
+                        return Some(steps + 1);
+                    }
+
+                    continue;
+                }
+
+                // match_indices skips matches which overlap, like the second `HH` in `HHH`
+                let matches =
+                    (0..molecule.len()).filter(|&i| molecule[i..].starts_with(&rule.output));
+
+                for i in matches {
+                    let previous = format!(
+                        "{}{}{}",
+                        &molecule[..i],
+                        rule.input,
+                        &molecule[i + rule.output.len()..]
+                    );
+
+                    if seen.insert(previous.clone()) {
+                        queue.push_back((previous, steps + 1));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part1(&input), 535);
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 212);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(212));
+    }
+
+    #[test]
+    fn test_part2_examples() {
+        let rules = "e => H\ne => O\nH => HO\nH => OH\nO => HH\n\n";
+
+        assert_eq!(part2(&generator(&format!("{}HOH", rules)).unwrap()), Ok(3));
+        assert_eq!(
+            part2(&generator(&format!("{}HOHOHO", rules)).unwrap()),
+            Ok(6)
+        );
+        assert_eq!(part2(&generator(&format!("{}e", rules)).unwrap()), Ok(0));
+    }
+
+    #[test]
+    fn test_part2_search() {
+        // replacing H with O doesn't change the weight, so the rules have to be searched
+        let input = generator("e => H\nH => O\nO => HH\n\nHH").unwrap();
+        assert_eq!(part2(&input), Ok(3));
+
+        let input = generator("e => H\nH => O\nO => HH\n\nHCa").unwrap();
+        assert_eq!(
+            part2(&input),
+            Err(Error::unsolvable(19, "The molecule can't be made from e"))
+        );
+    }
+
+    #[test]
+    fn test_part2_unsolvable() {
+        let input = generator("e => HO\nH => HH\n\nOH").unwrap();
+        assert_eq!(
+            part2(&input),
+            Err(Error::unsolvable(19, "The molecule can't be made from e"))
+        );
+
+        let input = generator("H => HO\n\nHOH").unwrap();
+        assert_eq!(
+            part2(&input),
+            Err(Error::unsolvable(19, "The molecule can't be made from e"))
+        );
+
+        let input = generator("HO => H\n\nHOH").unwrap();
+        assert_eq!(
+            part2(&input),
+            Err(Error::unsolvable(
+                19,
+                "Rule HO => H doesn't replace one element with others"
+            ))
+        );
+
+        let input = Input {
+            molecule: String::new(),
+            rules: Vec::new(),
+        };
+        assert_eq!(
+            part2(&input),
+            Err(Error::unsolvable(19, "The molecule is empty"))
+        );
+    }
+
+    #[test]
+    fn test_progress() {
+        let input = generator(INPUT).unwrap();
+        let progress = Progress::new();
+
+        assert_eq!(run(&input, Part::Two, &progress), Ok(212));
+        assert_eq!(progress.fraction(), Some(1.0));
+
+        progress.cancel();
+        assert_eq!(run(&input, Part::Two, &progress), Err(Error::Cancelled));
+        assert_eq!(run(&input, Part::One, &progress), Ok(535));
+    }
+
+    #[test]
+    fn test_random_input() {
+        for size in 1..=5 {
+            let input = generator(&random::generate(19, size, 1).unwrap()).unwrap();
+            assert_eq!(part2(&input).ok(), reference(&input), "size {}", size);
+        }
+    }
+
+    /// Rules for a few elements, some of which don't have the shape of the puzzle's rules, and a
+    /// molecule which is either made by following them from `e` or is made of random elements, so
+    /// that it often can't be made at all
+    fn medicine() -> impl Strategy<Value = Input> {
+        let element = || prop::sample::select(vec!["H", "O", "F", "Ca", "Rn", "Ar", "Y"]);
+        let rule = (prop_oneof![Just("e"), element()], vec(element(), 1..=3));
+
+        (
+            vec(rule, 1..=8),
+            vec(any::<Index>(), 1..=5),
+            vec(element(), 1..=6),
+        )
+            .prop_map(|(rules, steps, random)| {
+                let mut molecule = vec!["e"];
+
+                for step in steps {
+                    let replaceable = (0..molecule.len())
+                        .flat_map(|i| (0..rules.len()).map(move |r| (i, r)))
+                        .filter(|&(i, r)| rules[r].0 == molecule[i])
+                        .collect::<Vec<_>>();
+
+                    if replaceable.is_empty() {
+                        break;
+                    }
+
+                    let (i, r) = *step.get(&replaceable);
+                    molecule.splice(i..=i, rules[r].1.iter().copied());
+                }
+
+                if molecule == ["e"] {
+                    molecule = random;
+                }
+
+                Input {
+                    molecule: molecule.concat(),
+                    rules: rules
+                        .iter()
+                        .map(|(input, output)| Rule {
+                            input: input.to_string(),
+                            output: output.concat(),
+                        })
+                        .collect(),
+                }
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_reference(input in medicine()) {
+            prop_assert_eq!(part2(&input).ok(), reference(&input));
+        }
+
+        #[test]
+        fn test_random_molecule_matches_reference(
+            rules in medicine(),
+            molecule in vec(prop::sample::select(vec!["H", "O", "Ca", "Y"]), 1..=6),
+        ) {
+            let input = Input { molecule: molecule.concat(), ..rules };
+            prop_assert_eq!(part2(&input).ok(), reference(&input));
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let text = "H => HO\ne => H\n\nHOH";
+        let input = generator(text).unwrap();
+        assert_eq!(input.to_string(), text);
+        assert_eq!(input.rules[0].to_string(), "H => HO");
+
+        for text in &[INPUT.to_string(), random::generate(19, 30, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            assert_eq!(generator(&input.to_string()).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("H => HO\nH -> OH\n\nHOH").err(),
+            Some(Error::parse(
+                19,
+                2,
+                2,
+                "H -> OH",
+                "Expected a rule like 'H => HO'"
+            ))
+        );
+        assert_eq!(
+            generator("H => HO\n\nHO2H").err(),
+            Some(Error::parse(
+                19,
+                3,
+                3,
+                "HO2H",
+                "Expected a molecule made only of letters"
+            ))
+        );
+        assert_eq!(
+            generator("H => HO\n").err(),
+            Some(Error::parse(
+                19,
+                2,
+                1,
+                "",
+                "Expected a molecule after the replacement rules"
+            ))
+        );
     }
 }
@@ -1,80 +1,132 @@
+//! Day 2: I Was Told There Would Be No Math
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Parsed},
+};
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::character::complete::char;
 use std::cmp::min;
 
+/// Dimensions of a present in feet, which is a perfect right rectangular prism
 pub struct Present {
-    length: usize,
-    width: usize,
-    height: usize,
+    /// Length in feet
+    pub length: usize,
+
+    /// Width in feet
+    pub width: usize,
+
+    /// Height in feet
+    pub height: usize,
 }
 
+/// Parse each line as the dimensions of a present, like `2x3x4`
 #[aoc_generator(day2)]
-pub fn generator(input: &str) -> Vec<Present> {
-    input
-        .lines()
-        .map(|line| {
-            let dimensions = line
-                .trim()
-                .split('x')
-                .map(|d| d.parse().unwrap())
-                .collect::<Vec<_>>();
-            debug_assert_eq!(dimensions.len(), 3);
-
-            Present {
-                length: dimensions[0],
-                width: dimensions[1],
-                height: dimensions[2],
-            }
-        })
+pub fn generator(input: &str) -> Result<Vec<Present>, Error> {
+    lines(2, input)
+        .map(|line| parse::line(&line, present, "Expected dimensions in the form LxWxH"))
         .collect()
 }
 
+fn present(s: &str) -> Parsed<'_, Present> {
+    let (s, length) = parse::integer(s)?;
+    let (s, _) = char('x')(s)?;
+    let (s, width) = parse::integer(s)?;
+    let (s, _) = char('x')(s)?;
+    let (s, height) = parse::integer(s)?;
+
+    Ok((
+        s,
+        Present {
+            length,
+            width,
+            height,
+        },
+    ))
+}
+
+/// Total square feet of wrapping paper needed, including slack
 #[aoc(day2, part1)]
-pub fn part1(input: &[Present]) -> usize {
-    input
-        .iter()
-        .map(|present| {
-            let front = present.width * present.height;
-            let top = present.width * present.length;
-            let side = present.length * present.height;
-            let smallest = min(min(front, top), side);
-
-            2 * front + 2 * top + 2 * side + smallest
-        })
-        .sum()
+pub fn part1(input: &[Present]) -> Result<usize, Error> {
+    input.iter().try_fold(0, |total, present| {
+        let area = |a, b| checked::mul(2, "measuring wrapping paper", a, b);
+        let front = area(present.width, present.height)?;
+        let top = area(present.width, present.length)?;
+        let side = area(present.length, present.height)?;
+        let smallest = min(min(front, top), side);
+
+        let paper = checked::sum(
+            2,
+            "measuring wrapping paper",
+            [front, front, top, top, side, side, smallest],
+        )?;
+
+        checked::add(2, "adding up wrapping paper", total, paper)
+    })
 }
 
+/// Total feet of ribbon needed, including the bows
 #[aoc(day2, part2)]
-pub fn part2(input: &[Present]) -> usize {
-    input
-        .iter()
-        .map(|present| {
-            let front = present.width + present.height;
-            let top = present.width + present.length;
-            let side = present.length + present.height;
-            let smallest = min(min(front, top), side);
-
-            let bow = present.length * present.width * present.height;
-
-            smallest * 2 + bow
-        })
-        .sum()
+pub fn part2(input: &[Present]) -> Result<usize, Error> {
+    input.iter().try_fold(0, |total, present| {
+        let around = |a, b| checked::sum(2, "measuring ribbon", [a, a, b, b]);
+        let front = around(present.width, present.height)?;
+        let top = around(present.width, present.length)?;
+        let side = around(present.length, present.height)?;
+        let smallest = min(min(front, top), side);
+
+        let bow = checked::product(
+            2,
+            "measuring a bow",
+            [present.length, present.width, present.height],
+        )?;
+        let ribbon = checked::add(2, "measuring ribbon", smallest, bow)?;
+
+        checked::add(2, "adding up ribbon", total, ribbon)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::{generator, part1, part2};
+    use crate::error::Error;
 
     static INPUT: &str = include_str!("../input/2015/day2.txt");
 
     #[test]
     fn test_part1_real() {
-        let presents = generator(INPUT);
-        assert_eq!(part1(&presents), 1586300);
+        let presents = generator(INPUT).unwrap();
+        assert_eq!(part1(&presents), Ok(1586300));
     }
 
     #[test]
     fn test_part2_real() {
-        let presents = generator(INPUT);
-        assert_eq!(part2(&presents), 3737498);
+        let presents = generator(INPUT).unwrap();
+        assert_eq!(part2(&presents), Ok(3737498));
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("1x2x3\n4xAx6").err(),
+            Some(Error::parse(
+                2,
+                2,
+                3,
+                "4xAx6",
+                "Expected dimensions in the form LxWxH"
+            ))
+        );
+        assert_eq!(
+            generator("1x2").err(),
+            Some(Error::parse(
+                2,
+                1,
+                4,
+                "1x2",
+                "Expected dimensions in the form LxWxH"
+            ))
+        );
     }
 }
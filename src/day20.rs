@@ -1,82 +1,299 @@
-use aoc_runner_derive::aoc;
+//! Day 20: Infinite Elves and Infinite Houses
 
-// I can't think of a way to estimate a sensible lower bound, so this is a hack after I
-// already found the answer by just starting at 1, just to make the tests run in reasonable
-// time. It takes ~3.3s each part starting from 1 and just brute forcing in release mode.
-//
-// It would be faster to just calculate the sum as we go instead of assigning the factors to
-// a Vec as we go then multiplying it out afterwards, but that's one for another day
-const LOWER: usize = 700_000;
+use crate::{
+    checked,
+    error::{lines, Error},
+    progress::Progress,
+    Part,
+};
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::iter;
 
+/// Parse the target number of presents
+#[aoc_generator(day20)]
+pub fn generator(input: &str) -> Result<usize, Error> {
+    let line = lines(20, input)
+        .next()
+        .ok_or_else(|| Error::end_of_input(20, input, "Expected a number of presents"))?;
+    let text = line.text.trim();
+
+    text.parse().map_err(|e| {
+        line.error(
+            text,
+            format!("Invalid number of presents {:?}: {}", text, e),
+        )
+    })
+}
+
+/// Lowest house number which gets at least as many presents as the input
 #[aoc(day20, part1)]
-pub fn part1(input: &str) -> usize {
-    let target = input.lines().next().unwrap().parse::<usize>().unwrap();
+pub fn part1(input: &usize) -> Result<usize, Error> {
+    run(input, Part::One, &Params::default(), &Progress::new())
+}
 
-    for house in LOWER.. {
-        let factors = factor(house);
+/// Lowest house number which gets at least as many presents as the input when each elf visits 50 houses
+#[aoc(day20, part2)]
+pub fn part2(input: &usize) -> Result<usize, Error> {
+    run(input, Part::Two, &Params::default(), &Progress::new())
+}
 
-        let total = factors.iter().map(|&f| f * 10).sum::<usize>();
+/// How the elves deliver presents in each part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Presents each elf delivers to each house in part 1, 10 in the puzzle
+    pub part1_presents: usize,
 
-        if total >= target {
-            return house;
+    /// Presents each elf delivers to each house in part 2, 11 in the puzzle
+    pub part2_presents: usize,
+
+    /// Houses each elf visits in part 2, 50 in the puzzle. Elves visit every house in part 1.
+    pub part2_visits: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_presents: 10,
+            part2_presents: 11,
+            part2_visits: 50,
         }
     }
-
-    unreachable!()
 }
 
-#[aoc(day20, part2)]
-pub fn part2(input: &str) -> usize {
-    let target = input.lines().next().unwrap().parse::<usize>().unwrap();
+/// Houses which presents are delivered to at once, so that the memory needed doesn't grow with the
+/// number of presents
+const SEGMENT: usize = 1 << 16;
+
+/// Number of elves to deliver presents between checks for cancellation
+const CHECK_EVERY: usize = 4096;
+
+/// Solve either part by delivering every elf's presents to the houses up to the first house
+/// which is certain to get enough, a segment of houses at a time, reporting each segment
+pub fn run(
+    input: &usize,
+    part: Part,
+    params: &Params,
+    progress: &Progress,
+) -> Result<usize, Error> {
+    let target = *input;
+    let (per_house, visits) = delivery(part, params)?;
+
+    // elf n alone delivers enough presents to house n from here, so no later house needs checking
+    let upper = target.div_ceil(per_house).max(1);
+    let mut presents = vec![0; SEGMENT.min(upper)];
+
+    progress.set_total(upper.div_ceil(SEGMENT) as u64);
 
-    for house in LOWER.. {
-        let factors = factor(house);
+    for first in (1..=upper).step_by(SEGMENT) {
+        progress.tick()?;
 
-        let total = factors
-            .iter()
-            .filter(|&&f| f * 50 >= house)
-            .map(|&f| f * 11)
-            .sum::<usize>();
+        let last = first.saturating_add(SEGMENT - 1).min(upper);
+        presents.fill(0);
 
-        if total >= target {
-            return house;
+        // elves before this one have visited all their houses before the segment starts
+        for elf in first.div_ceil(visits).max(1)..=last {
+            if elf % CHECK_EVERY == 0 {
+                progress.check()?;
+            }
+
+            // the first house in the segment is the elf's nth, which might be too big to exist
+            let nth = first.div_ceil(elf);
+            let start = match nth.checked_mul(elf) {
+                Some(start) => start,
+                None => continue,
+            };
+
+            for house in (start..=last).step_by(elf).take(visits - (nth - 1)) {
+                let delivered = checked::mul(20, "delivering presents", elf, per_house)?;
+                let total = &mut presents[house - first];
+                *total = checked::add(20, "delivering presents", *total, delivered)?;
+            }
+        }
+
+        if let Some(offset) = presents[..=last - first].iter().position(|&p| p >= target) {
+            return Ok(first + offset);
         }
     }
 
-    unreachable!()
+    Ok(upper)
 }
 
-fn factor(n: usize) -> Vec<usize> {
-    let mut factors = Vec::new();
-    let bound = (n as f64).sqrt() as usize;
+/// Presents delivered to each house by each elf in the given part, and how many houses each elf
+/// visits, checking that every elf delivers something
+fn delivery(part: Part, params: &Params) -> Result<(usize, usize), Error> {
+    let (per_house, visits) = match part {
+        Part::One => (params.part1_presents, usize::MAX),
+        Part::Two => (params.part2_presents, params.part2_visits),
+    };
+
+    if per_house == 0 {
+        return Err(Error::unsolvable(
+            20,
+            "Elves must deliver at least one present to each house",
+        ));
+    }
 
-    for i in 1..bound + 1 {
-        if n % i == 0 {
-            factors.push(i);
+    if visits == 0 {
+        return Err(Error::unsolvable(20, "Elves must visit at least one house"));
+    }
 
-            // add the inverse end of this factor if it's not a perfect square (to avoid adding it twice)
-            if i * i != n {
-                factors.push(n / i);
-            }
+    Ok((per_house, visits))
+}
+
+/// Solve either part by delivering every elf's presents, like [run] but without reporting progress
+pub fn sieve(input: &usize, part: Part, params: &Params) -> Result<usize, Error> {
+    run(input, part, params, &Progress::new())
+}
+
+/// Solve either part by working out the presents for each house in turn from its divisors, found by
+/// trial division. This needs no memory but takes `O(n sqrt n)` time rather than `O(n log n)`.
+pub fn trial_division(input: &usize, part: Part, params: &Params) -> Result<usize, Error> {
+    let target = *input;
+    let (per_house, visits) = delivery(part, params)?;
+
+    let upper = target.div_ceil(per_house).max(1);
+
+    // elf n visits house h if n divides h and it hasn't already visited all its houses
+    let presents = |house: usize| {
+        (1..)
+            .take_while(|&d| d * d <= house)
+            .filter(|&d| house.is_multiple_of(d))
+            .flat_map(|d| iter::once(d).chain(Some(house / d).filter(|&other| other != d)))
+            .filter(|&elf| house / elf <= visits)
+            .try_fold(0, |total, elf| {
+                let delivered = checked::mul(20, "delivering presents", elf, per_house)?;
+                checked::add(20, "delivering presents", total, delivered)
+            })
+    };
+
+    for house in 1..=upper {
+        if presents(house)? >= target {
+            return Ok(house);
         }
     }
 
-    factors
+    Ok(upper)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     static INPUT: &str = include_str!("../input/2015/day20.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 786240);
+        assert_eq!(part1(&generator(INPUT).unwrap()), Ok(786240));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 831600);
+        assert_eq!(part2(&generator(INPUT).unwrap()), Ok(831600));
+    }
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new();
+        assert_eq!(run(&150, Part::One, &Params::default(), &progress), Ok(8));
+        assert_eq!(progress.fraction(), Some(1.0));
+
+        progress.cancel();
+        assert_eq!(
+            run(&34_000_000, Part::One, &Params::default(), &progress),
+            Err(Error::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_huge_target() {
+        let progress = Progress::with_budget(Duration::from_millis(10));
+        assert_eq!(
+            run(
+                &1_000_000_000_000_000_000,
+                Part::One,
+                &Params::default(),
+                &progress
+            ),
+            Err(Error::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let params = Params {
+            part1_presents: 1,
+            part2_presents: 11,
+            part2_visits: 1,
+        };
+
+        assert_eq!(run(&15, Part::One, &params, &Progress::new()), Ok(8));
+        assert_eq!(run(&150, Part::Two, &params, &Progress::new()), Ok(14));
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let nothing = Params {
+            part1_presents: 0,
+            ..Params::default()
+        };
+        let nowhere = Params {
+            part2_visits: 0,
+            ..Params::default()
+        };
+
+        for solve in [sieve, trial_division] {
+            assert_eq!(
+                solve(&150, Part::One, &nothing),
+                Err(Error::unsolvable(
+                    20,
+                    "Elves must deliver at least one present to each house"
+                ))
+            );
+            assert_eq!(
+                solve(&150, Part::Two, &nowhere),
+                Err(Error::unsolvable(20, "Elves must visit at least one house"))
+            );
+            assert_eq!(solve(&150, Part::Two, &nothing), Ok(8));
+        }
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let params = Params::default();
+        let small = Params {
+            part1_presents: 1,
+            part2_presents: 3,
+            part2_visits: 4,
+        };
+
+        for target in (1..3000).step_by(37).chain([150, 29_000, 500_000]) {
+            for part in [Part::One, Part::Two] {
+                for params in [&params, &small] {
+                    assert_eq!(
+                        trial_division(&target, part, params),
+                        sieve(&target, part, params),
+                        "target {} part {} {:?}",
+                        target,
+                        part,
+                        params
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("34 million"),
+            Err(Error::parse(
+                20,
+                1,
+                1,
+                "34 million",
+                "Invalid number of presents \"34 million\": invalid digit found in string"
+            ))
+        );
     }
 }
@@ -1,32 +1,130 @@
+//! Day 21: RPG Simulator 20XX
+
+use crate::{
+    checked,
+    error::{lines, Error, Line},
+    parse::{self, Parsed},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use nom::{character::complete::char, sequence::preceded};
 use std::{
     cmp::{max, min},
-    num::ParseIntError,
+    fmt, iter,
+    str::FromStr,
 };
 
-const STARTING_HP: usize = 100;
-
+/// The boss to be beaten, along with the hero's hit points and the equipment available in the shop
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Battle {
     boss: Player,
-    weapons: [Equipment; 5],
-    armour: [Equipment; 6],
-    rings: [Equipment; 8],
+    params: Params,
+}
+
+impl Battle {
+    /// Create a battle against the given boss, using the hero and shop from the puzzle
+    pub fn new(boss: Player) -> Self {
+        Battle::with_params(boss, Params::default())
+    }
+
+    /// Create a battle against the given boss, with a different hero or shop
+    pub fn with_params(boss: Player, params: Params) -> Self {
+        Battle { boss, params }
+    }
+
+    /// Stats of the boss
+    pub fn boss(&self) -> &Player {
+        &self.boss
+    }
+
+    /// Hero's hit points and the shop
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    /// Fight the boss with the given items, which the hero starts with 100 hit points on top of
+    /// unless the battle was created with different [Params]. Returns whether the hero won, along
+    /// with the cost of the items.
+    pub fn fight(&self, equipped: &[&Equipment]) -> Result<Outcome, Error> {
+        fight(&self.boss, self.params.hero_hp, equipped)
+    }
+}
+
+/// The hero's hit points and the items for sale in the shop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Hit points the hero starts with, 100 in the puzzle
+    pub hero_hp: usize,
+
+    /// Weapons for sale, of which the hero must buy exactly one
+    pub weapons: Vec<Equipment>,
+
+    /// Armour for sale, of which the hero may buy one
+    pub armour: Vec<Equipment>,
+
+    /// Rings for sale, of which the hero may buy up to two different ones
+    pub rings: Vec<Equipment>,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            hero_hp: 100,
+            weapons: vec![
+                Equipment::new(8, 4, 0),
+                Equipment::new(10, 5, 0),
+                Equipment::new(25, 6, 0),
+                Equipment::new(40, 7, 0),
+                Equipment::new(74, 8, 0),
+            ],
+            armour: vec![
+                Equipment::new(13, 0, 1),
+                Equipment::new(31, 0, 2),
+                Equipment::new(53, 0, 3),
+                Equipment::new(75, 0, 4),
+                Equipment::new(102, 0, 5),
+            ],
+            rings: vec![
+                Equipment::new(25, 1, 0),
+                Equipment::new(50, 2, 0),
+                Equipment::new(100, 3, 0),
+                Equipment::new(20, 0, 1),
+                Equipment::new(40, 0, 2),
+                Equipment::new(80, 0, 3),
+            ],
+        }
+    }
 }
 
+/// Stats of a fighter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Player {
-    hp: usize,
-    damage: usize,
-    armour: usize,
+    /// Hit points, the fighter loses once these reach 0
+    pub hp: usize,
+
+    /// Damage dealt by each attack, before the opponent's armour is taken off
+    pub damage: usize,
+
+    /// Reduction in damage taken from each attack, which always deals at least 1 damage
+    pub armour: usize,
 }
 
+/// Item from the shop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Equipment {
-    cost: usize,
-    damage: usize,
-    armour: usize,
+    /// Price in gold
+    pub cost: usize,
+
+    /// Damage added to the wearer's attacks
+    pub damage: usize,
+
+    /// Armour added to the wearer
+    pub armour: usize,
 }
 
 impl Equipment {
+    /// Create an item from its stats
     pub fn new(cost: usize, damage: usize, armour: usize) -> Self {
         Equipment {
             cost,
@@ -36,109 +134,158 @@ impl Equipment {
     }
 }
 
-pub enum Turn {
+/// Stands in for an empty armour or ring slot
+const NOTHING: Equipment = Equipment {
+    cost: 0,
+    damage: 0,
+    armour: 0,
+};
+
+impl FromStr for Equipment {
+    type Err = String;
+
+    /// Parse an item written as `cost/damage/armour`, like `13/0/1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected = format!(
+            "Invalid item {:?}, expected cost/damage/armour like 13/0/1",
+            s
+        );
+
+        parse::all(s, equipment, &expected)
+    }
+}
+
+fn equipment(s: &str) -> Parsed<'_, Equipment> {
+    let (s, cost) = parse::integer(s)?;
+    let (s, damage) = preceded(char('/'), parse::integer)(s)?;
+    let (s, armour) = preceded(char('/'), parse::integer)(s)?;
+
+    Ok((s, Equipment::new(cost, damage, armour)))
+}
+
+impl fmt::Display for Equipment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.cost, self.damage, self.armour)
+    }
+}
+
+enum Turn {
     Hero,
     Boss,
 }
 
+/// Result of a fight, with the gold spent on equipment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Outcome {
+    /// The hero ran out of hit points first
     Lose(usize),
+
+    /// The boss ran out of hit points first
     Win(usize),
 }
 
+/// Parse the boss's stats, one per line like `Hit Points: 104`
 #[aoc_generator(day21)]
-pub fn generator(input: &str) -> Result<Battle, ParseIntError> {
-    let mut lines = input.trim().lines();
-
-    fn parse_number(line: &str) -> Result<usize, ParseIntError> {
-        line.trim().split(": ").nth(1).unwrap().parse()
-    }
+pub fn generator(input: &str) -> Result<Battle, Error> {
+    let mut lines = lines(21, input);
+
+    let mut parse_number = |label: &str| -> Result<usize, Error> {
+        let line = lines
+            .next()
+            .ok_or_else(|| Error::end_of_input(21, input, format!("Expected {}", label)))?;
+        parse_stat(&line, label)
+    };
 
     let boss = Player {
-        hp: parse_number(lines.next().unwrap())?,
-        damage: parse_number(lines.next().unwrap())?,
-        armour: parse_number(lines.next().unwrap())?,
+        hp: parse_number("Hit Points")?,
+        damage: parse_number("Damage")?,
+        armour: parse_number("Armor")?,
     };
 
-    let weapons = [
-        Equipment::new(8, 4, 0),
-        Equipment::new(10, 5, 0),
-        Equipment::new(25, 6, 0),
-        Equipment::new(40, 7, 0),
-        Equipment::new(74, 8, 0),
-    ];
-
-    let armour = [
-        Equipment::new(0, 0, 0), // no armour
-        Equipment::new(13, 0, 1),
-        Equipment::new(31, 0, 2),
-        Equipment::new(53, 0, 3),
-        Equipment::new(75, 0, 4),
-        Equipment::new(102, 0, 5),
-    ];
-
-    let rings = [
-        Equipment::new(0, 0, 0), // no ring left hand
-        Equipment::new(0, 0, 0), // no ring right hand
-        Equipment::new(25, 1, 0),
-        Equipment::new(50, 2, 0),
-        Equipment::new(100, 3, 0),
-        Equipment::new(20, 0, 1),
-        Equipment::new(40, 0, 2),
-        Equipment::new(80, 0, 3),
-    ];
-
-    Ok(Battle {
-        boss,
-        weapons,
-        armour,
-        rings,
-    })
+    Ok(Battle::new(boss))
+}
+
+/// Parse a line like `Damage: 8`, checking it has the expected label
+fn parse_stat(line: &Line, label: &str) -> Result<usize, Error> {
+    parse::line(
+        line,
+        parse::field(label, parse::integer),
+        &format!("Expected a line like '{}: 10'", label),
+    )
 }
 
+/// Least gold that can be spent on equipment and still win
 #[aoc(day21, part1)]
-pub fn part1(input: &Battle) -> usize {
-    let mut min_cost = usize::MAX;
+pub fn part1(input: &Battle) -> Result<usize, Error> {
+    cheapest_win(input)
+}
+
+/// Most gold that can be spent on equipment and still lose
+#[aoc(day21, part2)]
+pub fn part2(input: &Battle) -> Result<usize, Error> {
+    dearest_loss(input)
+}
+
+/// Solve either part against the boss from the input, with the given hero and shop
+pub fn solve(input: &Battle, part: Part, params: &Params) -> Result<usize, Error> {
+    let battle = Battle::with_params(input.boss, params.clone());
+
+    match part {
+        Part::One => cheapest_win(&battle),
+        Part::Two => dearest_loss(&battle),
+    }
+}
 
-    let loadouts = generate_loadouts(input);
+fn cheapest_win(battle: &Battle) -> Result<usize, Error> {
+    let mut min_cost = None;
+
+    let loadouts = generate_loadouts(battle);
 
     for loadout in loadouts.iter() {
-        let outcome = fight(&input.boss, loadout);
+        let outcome = battle.fight(loadout)?;
 
         if let Outcome::Win(cost) = outcome {
-            min_cost = min(min_cost, cost);
+            min_cost = Some(min_cost.map_or(cost, |min_cost| min(min_cost, cost)));
         }
     }
 
-    min_cost
+    min_cost.ok_or_else(|| Error::unsolvable(21, "The hero can't win with anything from the shop"))
 }
 
-#[aoc(day21, part2)]
-pub fn part2(input: &Battle) -> usize {
-    let mut max_cost = usize::MIN;
+fn dearest_loss(battle: &Battle) -> Result<usize, Error> {
+    let mut max_cost = None;
 
-    let loadouts = generate_loadouts(input);
+    let loadouts = generate_loadouts(battle);
 
     for loadout in loadouts.iter() {
-        let outcome = fight(&input.boss, loadout);
+        let outcome = battle.fight(loadout)?;
 
         if let Outcome::Lose(cost) = outcome {
-            max_cost = max(max_cost, cost);
+            max_cost = Some(max_cost.map_or(cost, |max_cost| max(max_cost, cost)));
         }
     }
 
-    max_cost
+    max_cost.ok_or_else(|| Error::unsolvable(21, "The hero can't lose with anything from the shop"))
 }
 
 /// Generate all the possible different loadouts of equipment that can be
 /// equipped by the hero
 fn generate_loadouts(input: &Battle) -> Vec<[&Equipment; 4]> {
+    let shop = &input.params;
     let mut loadouts = Vec::new();
 
-    for weapon in input.weapons.iter() {
-        for armour in input.armour.iter() {
-            for rings in input.rings.iter().combinations(2) {
-                let equipment = [weapon, armour, rings[0], rings[1]];
+    // buying no armour or fewer than two rings is the same as buying items which do nothing
+    let armour = iter::once(&NOTHING)
+        .chain(shop.armour.iter())
+        .collect::<Vec<_>>();
+    let rings = iter::repeat_n(&NOTHING, 2)
+        .chain(shop.rings.iter())
+        .collect::<Vec<_>>();
+
+    for weapon in shop.weapons.iter() {
+        for &armour in armour.iter() {
+            for rings in rings.iter().combinations(2) {
+                let equipment = [weapon, armour, *rings[0], *rings[1]];
                 loadouts.push(equipment);
             }
         }
@@ -150,8 +297,8 @@ fn generate_loadouts(input: &Battle) -> Vec<[&Equipment; 4]> {
 /// Simulate the boss fight using the given equipment loadout
 ///
 /// Returns - Fight outcome with associated equipment cost
-fn fight(boss: &Player, equipped: &[&Equipment; 4]) -> Outcome {
-    let mut hero_hp = STARTING_HP;
+fn fight(boss: &Player, hero_hp: usize, equipped: &[&Equipment]) -> Result<Outcome, Error> {
+    let mut hero_hp = hero_hp;
     let mut boss_hp = boss.hp;
     let mut turn = Turn::Hero;
 
@@ -160,9 +307,9 @@ fn fight(boss: &Player, equipped: &[&Equipment; 4]) -> Outcome {
     let mut equipment_cost = 0;
 
     for &e in equipped.iter() {
-        hero_attack += e.damage;
-        hero_defence += e.armour;
-        equipment_cost += e.cost;
+        hero_attack = checked::add(21, "adding up damage", hero_attack, e.damage)?;
+        hero_defence = checked::add(21, "adding up armour", hero_defence, e.armour)?;
+        equipment_cost = checked::add(21, "adding up costs", equipment_cost, e.cost)?;
     }
 
     while boss_hp > 0 && hero_hp > 0 {
@@ -183,27 +330,136 @@ fn fight(boss: &Player, equipped: &[&Equipment; 4]) -> Outcome {
     }
 
     if boss_hp == 0 {
-        Outcome::Win(equipment_cost)
+        Ok(Outcome::Win(equipment_cost))
     } else {
-        Outcome::Lose(equipment_cost)
+        Ok(Outcome::Lose(equipment_cost))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     static INPUT: &str = include_str!("../input/2015/day21.txt");
 
     #[test]
     fn test_part1() {
         let input = generator(INPUT).unwrap();
-        assert_eq!(part1(&input), 121);
+        assert_eq!(part1(&input), Ok(121));
     }
 
     #[test]
     fn test_part2() {
         let input = generator(INPUT).unwrap();
-        assert_eq!(part2(&input), 201);
+        assert_eq!(part2(&input), Ok(201));
+    }
+
+    #[test]
+    fn test_fight() {
+        let battle = Battle::new(Player {
+            hp: 12,
+            damage: 7,
+            armour: 2,
+        });
+        let dagger = Equipment::new(8, 4, 0);
+        let ring = Equipment::new(25, 1, 0);
+
+        assert_eq!(battle.boss().hp, 12);
+        assert_eq!(battle.fight(&[&dagger]), Ok(Outcome::Win(8)));
+        assert_eq!(battle.fight(&[&dagger, &ring]), Ok(Outcome::Win(33)));
+
+        let battle = Battle::new(Player {
+            hp: 100,
+            ..*battle.boss()
+        });
+        assert_eq!(battle.fight(&[&dagger]), Ok(Outcome::Lose(8)));
+    }
+
+    #[test]
+    fn test_params() {
+        let input = generator(INPUT).unwrap();
+        let stronger = Params {
+            hero_hp: 200,
+            ..Params::default()
+        };
+
+        assert!(solve(&input, Part::One, &stronger).unwrap() < 121);
+        assert_eq!(solve(&input, Part::One, &Params::default()), Ok(121));
+
+        let dagger_only = Params {
+            hero_hp: 1,
+            weapons: vec![Equipment::new(8, 4, 0)],
+            armour: Vec::new(),
+            rings: Vec::new(),
+        };
+
+        assert_eq!(
+            solve(&input, Part::One, &dagger_only),
+            Err(Error::unsolvable(
+                21,
+                "The hero can't win with anything from the shop"
+            ))
+        );
+        assert_eq!(solve(&input, Part::Two, &dagger_only), Ok(8));
+
+        let invincible = Params {
+            hero_hp: 100_000,
+            ..Params::default()
+        };
+
+        assert_eq!(
+            solve(&input, Part::Two, &invincible),
+            Err(Error::unsolvable(
+                21,
+                "The hero can't lose with anything from the shop"
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        let battle = Battle::new(Player {
+            hp: 12,
+            damage: 7,
+            armour: 2,
+        });
+        let golden = Equipment::new(usize::MAX, 4, 0);
+        let ring = Equipment::new(25, 1, 0);
+
+        assert_eq!(
+            battle.fight(&[&golden, &ring]),
+            Err(Error::Overflow {
+                day: 21,
+                operation: "adding up costs"
+            })
+        );
+    }
+
+    #[test]
+    fn test_equipment_from_str() {
+        assert_eq!("13/0/1".parse(), Ok(Equipment::new(13, 0, 1)));
+        assert_eq!(Equipment::new(8, 4, 0).to_string(), "8/4/0");
+        assert!("13/0".parse::<Equipment>().is_err());
+        assert!("13/0/x".parse::<Equipment>().is_err());
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("Hit Points: 103\nDamage: lots\nArmor: 2").err(),
+            Some(Error::parse(
+                21,
+                2,
+                9,
+                "Damage: lots",
+                "Expected a line like 'Damage: 10'"
+            ))
+        );
+        assert_eq!(
+            generator("Hit Points: 103\nDamage: 9\n").err(),
+            Some(Error::parse(21, 3, 1, "", "Expected Armor"))
+        );
     }
 }
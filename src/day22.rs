@@ -1,27 +1,58 @@
+//! Day 22: Wizard Simulator 20XX
+
+use crate::{
+    checked,
+    error::{lines, Error, Line},
+    parse,
+    progress::Progress,
+    trace::{Event, Tracer},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::{cmp::max, num::ParseIntError};
+use std::{cmp::max, collections::HashMap};
 
-type Mana = usize;
+/// Amount of mana, which the hero spends to cast spells
+pub type Mana = usize;
 type GameResult = Result<GameState, Outcome>;
 
+/// State after part of a round, or why the round stopped early
+type Step = Result<GameState, Stop>;
+
+/// Least mana the hero can go on to spend and still win from each state already played, ignoring
+/// the mana spent to reach it, or `None` if the hero can't win from there
+type Memo = HashMap<GameState, Option<Mana>>;
+
+/// Spell which the hero can cast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Spell {
+    /// Deals 4 damage
     MagicMissile,
+
+    /// Deals 2 damage and heals the hero by 2 hit points
     Drain,
+
+    /// Adds 7 armour for 6 turns
     Shield,
+
+    /// Deals 3 damage at the start of each turn for 6 turns
     Poison,
+
+    /// Gives the hero 101 mana at the start of each turn for 5 turns
     Recharge,
 }
 
-const SPELLS: [Spell; 5] = [
-    Spell::MagicMissile,
-    Spell::Drain,
-    Spell::Shield,
-    Spell::Poison,
-    Spell::Recharge,
-];
-
 impl Spell {
-    fn cost(&self) -> Mana {
+    /// Every spell, cheapest first
+    pub const ALL: [Spell; 5] = [
+        Spell::MagicMissile,
+        Spell::Drain,
+        Spell::Shield,
+        Spell::Poison,
+        Spell::Recharge,
+    ];
+
+    /// Mana needed to cast the spell
+    pub fn cost(&self) -> Mana {
         match *self {
             Spell::MagicMissile => 53,
             Spell::Drain => 73,
@@ -30,14 +61,75 @@ impl Spell {
             Spell::Recharge => 229,
         }
     }
+
+    /// Name of the spell as written in the puzzle
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Spell::MagicMissile => "Magic Missile",
+            Spell::Drain => "Drain",
+            Spell::Shield => "Shield",
+            Spell::Poison => "Poison",
+            Spell::Recharge => "Recharge",
+        }
+    }
 }
 
+/// How a game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Outcome {
+    /// The hero ran out of hit points
     Lose,
-    Win(Mana),
+
+    /// The boss ran out of hit points, with the final state of the game
+    Win(GameState),
+}
+
+/// Why a round stopped before the next one could start
+enum Stop {
+    /// The game is over
+    Ended(Outcome),
+
+    /// The hero's stats or the mana spent grew too big
+    Failed(Error),
+}
+
+impl From<Outcome> for Stop {
+    fn from(outcome: Outcome) -> Self {
+        Stop::Ended(outcome)
+    }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl From<Error> for Stop {
+    fn from(e: Error) -> Self {
+        Stop::Failed(e)
+    }
+}
+
+/// Round being played while looking for the cheapest win, with the index of the next spell to try
+/// and the least mana any spell tried so far went on to win with
+struct Round {
+    game: GameState,
+    spell: usize,
+    best: Option<Mana>,
+}
+
+impl Round {
+    fn new(game: GameState) -> Self {
+        Round {
+            game,
+            spell: 0,
+            best: None,
+        }
+    }
+
+    /// Remember the mana spent after casting a spell if it's the least so far
+    fn consider(&mut self, spent: Option<Mana>) {
+        self.best = self.best.into_iter().chain(spent).min();
+    }
+}
+
+/// State of a game between the hero and the boss at the start of a round
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct GameState {
     hero_hp: usize,
     hero_mana: Mana,
@@ -51,31 +143,221 @@ pub struct GameState {
 }
 
 impl GameState {
-    /// Play the game to find the cheapest winning scenario
-    fn play(&self) -> Mana {
-        let results = SPELLS
-            .iter()
-            .filter(|&spell| spell.cost() <= self.hero_mana)
-            .map(|spell| match self.next_round(spell) {
-                Ok(next) => next.play(),
-                Err(Outcome::Lose) => Mana::MAX,
-                Err(Outcome::Win(cost)) => cost,
+    /// Start a game against a boss with the given stats. The hero starts with 50 hit points and
+    /// 500 mana.
+    pub fn new(boss_hp: usize, boss_attack: usize) -> Self {
+        let hero = Params::default();
+
+        GameState {
+            hero_hp: hero.hero_hp,
+            hero_mana: hero.hero_mana,
+            total_mana: 0,
+            boss_hp,
+            boss_attack,
+            poison_effect: 0,
+            shield_effect: 0,
+            recharge_effect: 0,
+            hard_mode: false,
+        }
+    }
+
+    /// Change the hero's starting hit points and mana
+    pub fn with_hero(self, hp: usize, mana: Mana) -> Self {
+        GameState {
+            hero_hp: hp,
+            hero_mana: mana,
+            ..self
+        }
+    }
+
+    /// Play on hard mode, where the hero loses 1 hit point at the start of each of their turns
+    pub fn with_hard_mode(self, hard_mode: bool) -> Self {
+        GameState { hard_mode, ..self }
+    }
+
+    /// Hit points the hero has left
+    pub fn hero_hp(&self) -> usize {
+        self.hero_hp
+    }
+
+    /// Mana which the hero has left to cast spells with
+    pub fn hero_mana(&self) -> Mana {
+        self.hero_mana
+    }
+
+    /// Hit points the boss has left
+    pub fn boss_hp(&self) -> usize {
+        self.boss_hp
+    }
+
+    /// Mana spent on spells so far
+    pub fn total_mana(&self) -> Mana {
+        self.total_mana
+    }
+
+    /// Play every possible game from this state to find the least mana the hero can spend and
+    /// still win, or an error if the hero can't win
+    pub fn play(&self) -> Result<Mana, Error> {
+        self.play_remembering(&mut Memo::new())
+    }
+
+    /// Play every possible game like [GameState::play], stopping early if cancelled
+    pub fn play_until_cancelled(&self, progress: &Progress) -> Result<Mana, Error> {
+        self.play_with(&mut Memo::new(), progress)
+    }
+
+    fn play_with(&self, memo: &mut Memo, progress: &Progress) -> Result<Mana, Error> {
+        match self.cheapest(memo, progress)? {
+            Some(best) => checked::add(22, "spending mana", self.total_mana, best),
+            None => Err(Error::unsolvable(22, "The hero can't beat the boss")),
+        }
+    }
+
+    /// Play every possible game like [GameState::play], adding to what's already remembered
+    fn play_remembering(&self, memo: &mut Memo) -> Result<Mana, Error> {
+        self.play_with(memo, &Progress::new())
+    }
+
+    /// Least mana the hero can go on to spend from this state and still win, remembering the
+    /// answer for every state played along the way
+    fn cheapest(&self, memo: &mut Memo, progress: &Progress) -> Result<Option<Mana>, Error> {
+        if let Some(&best) = memo.get(&self.key()) {
+            return Ok(best);
+        }
+
+        progress.check()?;
+
+        // games against bosses with lots of hit points can last a very long time, so the rounds
+        // being played are kept here, latest last, rather than recursing
+        let mut rounds = vec![Round::new(*self)];
+
+        while let Some(round) = rounds.last_mut() {
+            let game = round.game;
+
+            let spell = match Spell::ALL.get(round.spell) {
+                Some(spell) => spell,
+                None => {
+                    // every spell has been tried, so pass the answer back to the round before
+                    let best = round.best;
+                    rounds.pop();
+                    memo.insert(game.key(), best);
+
+                    match rounds.last_mut() {
+                        Some(previous) => {
+                            previous.consider(game.spent_since(&previous.game, best)?)
+                        }
+                        None => return Ok(best),
+                    }
+
+                    continue;
+                }
+            };
+
+            round.spell += 1;
+
+            if spell.cost() > game.hero_mana {
+                continue;
+            }
+
+            let spent = match game.next_round(spell)? {
+                Ok(next) => match memo.get(&next.key()) {
+                    Some(&rest) => next.spent_since(&game, rest)?,
+                    None => {
+                        progress.check()?;
+                        rounds.push(Round::new(next));
+                        continue;
+                    }
+                },
+                Err(Outcome::Lose) => None,
+                Err(Outcome::Win(end)) => Some(end.total_mana - game.total_mana),
+            };
+
+            round.consider(spent);
+        }
+
+        unreachable!("The first round returns its answer once every spell has been tried")
+    }
+
+    /// State to remember answers by, as the mana already spent doesn't affect how the rest of the
+    /// game can go
+    fn key(&self) -> GameState {
+        GameState {
+            total_mana: 0,
+            ..*self
+        }
+    }
+
+    /// Mana spent to reach this state from an earlier one, plus the rest needed to win from here
+    fn spent_since(&self, earlier: &GameState, rest: Option<Mana>) -> Result<Option<Mana>, Error> {
+        let spent = self.total_mana - earlier.total_mana;
+
+        rest.map(|rest| checked::add(22, "spending mana", spent, rest))
+            .transpose()
+    }
+
+    /// Replay the cheapest winning game one round at a time, emitting an event for every spell
+    /// cast, using the least mana already worked out for each state
+    fn replay(&self, best: Mana, memo: &mut Memo, tracer: &mut dyn Tracer) -> Result<(), Error> {
+        let mut game = *self;
+
+        loop {
+            let mut round = None;
+
+            // pick a spell which still leads to a game as cheap as the best one
+            for spell in Spell::ALL
+                .iter()
+                .filter(|&spell| spell.cost() <= game.hero_mana)
+            {
+                round = match game.next_round(spell)? {
+                    Ok(next) if next.play_remembering(memo)? == best => Some((spell, next, false)),
+                    Err(Outcome::Win(next)) if next.total_mana == best => Some((spell, next, true)),
+                    _ => continue,
+                };
+
+                break;
+            }
+
+            let (spell, next, won) = match round {
+                Some(round) => round,
+                None => return Ok(()),
+            };
+
+            tracer.event(Event::SpellCast {
+                spell: spell.name(),
+                hero_hp: next.hero_hp,
+                hero_mana: next.hero_mana,
+                boss_hp: next.boss_hp,
+                total_mana: next.total_mana,
             });
 
-        results.min().unwrap_or(Mana::MAX)
+            if won {
+                return Ok(());
+            }
+
+            game = next;
+        }
     }
 
-    /// Play an entire round, returning early if the game ends
-    fn next_round(&self, spell: &Spell) -> GameResult {
-        self.hard_mode_effect()?
-            .apply_effects()?
-            .cast(spell)?
-            .apply_effects()?
-            .boss_turn()
+    /// Play an entire round where the hero casts the given spell, returning the state at the
+    /// start of the next round or how the game ended. The spell must be affordable.
+    pub fn next_round(&self, spell: &Spell) -> Result<Result<GameState, Outcome>, Error> {
+        let round = || -> Step {
+            self.hard_mode_effect()?
+                .apply_effects()?
+                .cast(spell)?
+                .apply_effects()?
+                .boss_turn()
+        };
+
+        match round() {
+            Ok(next) => Ok(Ok(next)),
+            Err(Stop::Ended(outcome)) => Ok(Err(outcome)),
+            Err(Stop::Failed(e)) => Err(e),
+        }
     }
 
     /// Apply any active effects and reduce any active timers
-    fn apply_effects(&self) -> GameResult {
+    fn apply_effects(&self) -> Step {
         let game = *self;
 
         let game = if game.poison_effect > 0 {
@@ -99,7 +381,7 @@ impl GameState {
 
         let game = if game.recharge_effect > 0 {
             GameState {
-                hero_mana: game.hero_mana + 101,
+                hero_mana: checked::add(22, "recharging mana", game.hero_mana, 101)?,
                 recharge_effect: game.recharge_effect - 1,
                 ..game
             }
@@ -107,11 +389,11 @@ impl GameState {
             game
         };
 
-        game.outcome()
+        Ok(game.outcome()?)
     }
 
     /// Check if hard mode applies
-    fn hard_mode_effect(&self) -> GameResult {
+    fn hard_mode_effect(&self) -> Step {
         let next = if self.hard_mode {
             GameState {
                 hero_hp: self.hero_hp.saturating_sub(1),
@@ -121,16 +403,16 @@ impl GameState {
             *self
         };
 
-        next.outcome()
+        Ok(next.outcome()?)
     }
 
     /// Cast the given spell
-    fn cast(&self, spell: &Spell) -> GameResult {
+    fn cast(&self, spell: &Spell) -> Step {
         let cost = spell.cost();
 
         let game = GameState {
             hero_mana: self.hero_mana.saturating_sub(cost),
-            total_mana: self.total_mana + cost,
+            total_mana: checked::add(22, "spending mana", self.total_mana, cost)?,
             ..*self
         };
 
@@ -141,7 +423,7 @@ impl GameState {
             },
             Spell::Drain => GameState {
                 boss_hp: self.boss_hp.saturating_sub(2),
-                hero_mana: self.hero_mana + 2,
+                hero_hp: checked::add(22, "healing the hero", self.hero_hp, 2)?,
                 ..game
             },
             Spell::Shield => GameState {
@@ -158,11 +440,11 @@ impl GameState {
             },
         };
 
-        game.outcome()
+        Ok(game.outcome()?)
     }
 
     /// Perform the boss's turn
-    fn boss_turn(&self) -> GameResult {
+    fn boss_turn(&self) -> Step {
         let armour = if self.shield_effect > 0 { 7 } else { 0 };
         let damage = max(self.boss_attack.saturating_sub(armour), 1);
 
@@ -171,13 +453,13 @@ impl GameState {
             ..*self
         };
 
-        game.outcome()
+        Ok(game.outcome()?)
     }
 
     /// Check the outcome of the current game state
     fn outcome(self) -> GameResult {
         if self.boss_hp == 0 {
-            Err(Outcome::Win(self.total_mana))
+            Err(Outcome::Win(self))
         } else if self.hero_hp == 0 {
             Err(Outcome::Lose)
         } else {
@@ -187,57 +469,246 @@ impl GameState {
     }
 }
 
+/// Parse the boss's stats, one per line like `Hit Points: 58`
 #[aoc_generator(day22)]
-pub fn generator(input: &str) -> Result<GameState, ParseIntError> {
-    let mut lines = input.trim().lines();
+pub fn generator(input: &str) -> Result<GameState, Error> {
+    let mut lines = lines(22, input);
+
+    let mut parse_number = |label: &str| -> Result<usize, Error> {
+        let line = lines
+            .next()
+            .ok_or_else(|| Error::end_of_input(22, input, format!("Expected {}", label)))?;
+        parse_stat(&line, label)
+    };
 
-    fn parse_number(line: &str) -> Result<usize, ParseIntError> {
-        line.trim().split(": ").nth(1).unwrap().parse()
-    }
+    let boss_hp = parse_number("Hit Points")?;
+    let boss_attack = parse_number("Damage")?;
+
+    Ok(GameState::new(boss_hp, boss_attack))
+}
 
-    Ok(GameState {
-        boss_hp: parse_number(lines.next().unwrap())?,
-        boss_attack: parse_number(lines.next().unwrap())?,
-        hero_hp: 50,
-        hero_mana: 500,
-        total_mana: 0,
-        poison_effect: 0,
-        shield_effect: 0,
-        recharge_effect: 0,
-        hard_mode: false,
-    })
+/// Parse a line like `Damage: 8`, checking it has the expected label
+fn parse_stat(line: &Line, label: &str) -> Result<usize, Error> {
+    parse::line(
+        line,
+        parse::field(label, parse::integer),
+        &format!("Expected a line like '{}: 10'", label),
+    )
 }
 
+/// Least mana the hero can spend and still win
 #[aoc(day22, part1)]
-pub fn part1(input: &GameState) -> Mana {
+pub fn part1(input: &GameState) -> Result<Mana, Error> {
     input.play()
 }
 
+/// Least mana the hero can spend and still win on hard mode
 #[aoc(day22, part2)]
-pub fn part2(input: &GameState) -> Mana {
-    let game = GameState {
-        hard_mode: true,
-        ..*input
-    };
+pub fn part2(input: &GameState) -> Result<Mana, Error> {
+    input.with_hard_mode(true).play()
+}
+
+/// Hero's starting stats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Hit points the hero starts with, 50 in the puzzle
+    pub hero_hp: usize,
+
+    /// Mana the hero starts with, 500 in the puzzle
+    pub hero_mana: Mana,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            hero_hp: 50,
+            hero_mana: 500,
+        }
+    }
+}
 
-    game.play()
+/// Solve the given part with the hero starting with the given stats
+pub fn solve(input: &GameState, part: Part, params: &Params) -> Result<Mana, Error> {
+    input
+        .with_hero(params.hero_hp, params.hero_mana)
+        .with_hard_mode(part == Part::Two)
+        .play()
+}
+
+/// Solve the given part with the hero starting with the given stats, stopping if cancelled. Heroes
+/// with more hit points and mana survive longer, so there are many more games to play.
+pub fn run(
+    input: &GameState,
+    part: Part,
+    params: &Params,
+    progress: &Progress,
+) -> Result<Mana, Error> {
+    input
+        .with_hero(params.hero_hp, params.hero_mana)
+        .with_hard_mode(part == Part::Two)
+        .play_until_cancelled(progress)
+}
+
+/// Solve the given part, emitting an event for every spell cast in the cheapest winning game
+pub fn trace(input: &GameState, part: Part, tracer: &mut dyn Tracer) -> Result<Mana, Error> {
+    let game = input.with_hard_mode(part == Part::Two);
+    let mut memo = Memo::new();
+    let best = game.play_remembering(&mut memo)?;
+
+    if tracer.enabled() {
+        game.replay(best, &mut memo, tracer)?;
+    }
+
+    Ok(best)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, trace::Collector};
+    use std::time::Duration;
 
     static INPUT: &str = include_str!("../input/2015/day22.txt");
 
     #[test]
     fn test_part1() {
         let input = generator(INPUT).unwrap();
-        assert_eq!(part1(&input), 1824);
+        assert_eq!(part1(&input), Ok(1824));
     }
 
     #[test]
     fn test_part2() {
         let input = generator(INPUT).unwrap();
-        assert_eq!(part2(&input), 1937);
+        assert_eq!(part2(&input), Ok(1937));
+    }
+
+    #[test]
+    fn test_next_round() {
+        let game = GameState::new(13, 8).with_hero(10, 250);
+        let game = game.next_round(&Spell::Poison).unwrap().unwrap();
+
+        assert_eq!(
+            (game.hero_hp(), game.hero_mana(), game.boss_hp()),
+            (2, 77, 10)
+        );
+
+        match game.next_round(&Spell::MagicMissile) {
+            Ok(Err(Outcome::Win(end))) => assert_eq!((end.boss_hp(), end.total_mana()), (0, 226)),
+            other => panic!("Expected a win, got {:?}", other),
+        }
+
+        assert_eq!(GameState::new(13, 8).with_hero(10, 250).play(), Ok(226));
+    }
+
+    #[test]
+    fn test_drain_heals() {
+        let game = GameState::new(5, 5).with_hero(4, 250);
+        let drained = game.next_round(&Spell::Drain).unwrap().unwrap();
+
+        assert_eq!(
+            (drained.hero_hp(), drained.hero_mana(), drained.boss_hp()),
+            (1, 177, 3)
+        );
+
+        // refunding Drain's cost as mana instead of healing loses to the boss's first attack
+        assert_eq!(game.play(), Ok(126));
+    }
+
+    #[test]
+    fn test_params() {
+        let input = generator("Hit Points: 13\nDamage: 8").unwrap();
+        let params = Params {
+            hero_hp: 10,
+            hero_mana: 250,
+        };
+
+        assert_eq!(solve(&input, Part::One, &params), Ok(226));
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        let game = GameState::new(13, 8).with_hero(usize::MAX, 500);
+
+        assert_eq!(
+            game.next_round(&Spell::Drain),
+            Err(Error::Overflow {
+                day: 22,
+                operation: "healing the hero"
+            })
+        );
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let input = generator("Hit Points: 100\nDamage: 1000").unwrap();
+        let unsolvable = Err(Error::unsolvable(22, "The hero can't beat the boss"));
+
+        assert_eq!(part1(&input), unsolvable);
+        assert_eq!(part2(&input), unsolvable);
+        assert_eq!(
+            run(&input, Part::One, &Params::default(), &Progress::new()),
+            unsolvable
+        );
+        assert_eq!(trace(&input, Part::One, &mut Collector::new()), unsolvable);
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let input = generator("Hit Points: 13\nDamage: 8").unwrap();
+        let params = Params {
+            hero_hp: 1_000_000,
+            hero_mana: 1_000_000,
+        };
+        let progress = Progress::new();
+        progress.cancel();
+
+        assert_eq!(
+            run(&input, Part::One, &params, &progress),
+            Err(Error::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_long_game() {
+        let input = generator("Hit Points: 1000000\nDamage: 1").unwrap();
+        let progress = Progress::with_budget(Duration::from_millis(100));
+
+        assert_eq!(
+            run(&input, Part::One, &Params::default(), &progress),
+            Err(Error::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = generator("Hit Points: 13\nDamage: 10").unwrap();
+        let best = part1(&input).unwrap();
+        let mut collector = Collector::new();
+
+        assert_eq!(trace(&input, Part::One, &mut collector), Ok(best));
+
+        match collector.events.last() {
+            Some(&Event::SpellCast {
+                boss_hp,
+                total_mana,
+                ..
+            }) => assert_eq!((boss_hp, total_mana), (0, best)),
+            event => panic!("Unexpected event {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("Hit Points: 71\nAttack: 10").err(),
+            Some(Error::parse(
+                22,
+                2,
+                1,
+                "Attack: 10",
+                "Expected a line like 'Damage: 10'"
+            ))
+        );
     }
 }
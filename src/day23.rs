@@ -1,7 +1,27 @@
+//! Day 23: Opening the Turing Lock
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Failure, Parsed},
+    progress::Progress,
+    trace::{Event, NoopTracer, Tracer},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::str::FromStr;
+use nom::{
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::{map, map_res},
+    sequence::separated_pair,
+};
+use std::{fmt, str::FromStr};
+
+/// Number of instructions executed between checks for cancellation
+const CHECK_EVERY: usize = 4096;
 
-#[derive(Clone, Debug)]
+/// Computer with two registers running a program of [instructions](Instruction)
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Computer {
     a: usize,
     b: usize,
@@ -9,9 +29,13 @@ pub struct Computer {
     instructions: Vec<Instruction>,
 }
 
-#[derive(Copy, Clone, Debug)]
+/// One of the computer's two registers
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Register {
+    /// Register `a`
     A,
+
+    /// Register `b`
     B,
 }
 
@@ -20,147 +44,415 @@ impl FromStr for Register {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "a" | "a," => Ok(Register::A),
-            "b" | "b," => Ok(Register::B),
+            "a" => Ok(Register::A),
+            "b" => Ok(Register::B),
             error => Err(format!("Invalid register: {}", error)),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Register::A => write!(f, "a"),
+            Register::B => write!(f, "b"),
+        }
+    }
+}
+
+/// Instruction which the computer can execute. Jump offsets are relative to the instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Instruction {
+    /// `hlf r`: halve the register, rounding down
     Half(Register),
+
+    /// `tpl r`: triple the register
     Triple(Register),
+
+    /// `inc r`: add 1 to the register
     Inc(Register),
+
+    /// `jmp offset`: jump unconditionally
     Jump(isize),
+
+    /// `jie r, offset`: jump if the register is even
     JumpEven(Register, isize),
+
+    /// `jio r, offset`: jump if the register is exactly 1
     JumpOne(Register, isize),
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Half(r) => write!(f, "hlf {}", r),
+            Instruction::Triple(r) => write!(f, "tpl {}", r),
+            Instruction::Inc(r) => write!(f, "inc {}", r),
+            Instruction::Jump(offset) => write!(f, "jmp {:+}", offset),
+            Instruction::JumpEven(r, offset) => write!(f, "jie {}, {:+}", r, offset),
+            Instruction::JumpOne(r, offset) => write!(f, "jio {}, {:+}", r, offset),
+        }
+    }
+}
+
+/// Formats the program, one instruction per line
+impl fmt::Display for Computer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}", instruction)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Computer {
-    fn execute(&mut self) {
-        while self.pointer < self.instructions.len() {
-            match self.instructions[self.pointer] {
-                Instruction::Half(r) => match r {
-                    Register::A => self.a /= 2,
-                    Register::B => self.b /= 2,
-                },
-                Instruction::Triple(r) => match r {
-                    Register::A => self.a *= 3,
-                    Register::B => self.b *= 3,
-                },
-                Instruction::Inc(r) => match r {
-                    Register::A => self.a += 1,
-                    Register::B => self.b += 1,
-                },
-                Instruction::Jump(offset) => {
-                    self.change_pointer(offset);
-                    continue;
-                }
-                Instruction::JumpEven(r, offset) => {
-                    let value = match r {
-                        Register::A => self.a,
-                        Register::B => self.b,
-                    };
-
-                    if value % 2 == 0 {
-                        self.change_pointer(offset);
-                        continue;
-                    }
-                }
-                Instruction::JumpOne(r, offset) => {
-                    let value = match r {
-                        Register::A => self.a,
-                        Register::B => self.b,
-                    };
-
-                    if value == 1 {
-                        self.change_pointer(offset);
-                        continue;
-                    }
-                }
+    /// Load a program, with both registers set to 0 and the first instruction next
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Computer {
+            a: 0,
+            b: 0,
+            pointer: 0,
+            instructions,
+        }
+    }
+
+    /// The program being run
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Check whether the program has finished by jumping outside it
+    pub fn is_halted(&self) -> bool {
+        self.pointer >= self.instructions.len()
+    }
+
+    /// Execute the next instruction, returning its position and the instruction, or `None` if the
+    /// program has finished
+    pub fn step(&mut self) -> Result<Option<(usize, Instruction)>, Error> {
+        if self.is_halted() {
+            return Ok(None);
+        }
+
+        let pointer = self.pointer;
+        let instruction = self.instructions[pointer];
+
+        let jump = match instruction {
+            Instruction::Half(r) => {
+                *self.register(r) /= 2;
+                None
             }
+            Instruction::Triple(r) => {
+                let register = self.register(r);
+                *register = checked::mul(23, "tripling a register", *register, 3)?;
+                None
+            }
+            Instruction::Inc(r) => {
+                let register = self.register(r);
+                *register = checked::add(23, "incrementing a register", *register, 1)?;
+                None
+            }
+            Instruction::Jump(offset) => Some(offset),
+            Instruction::JumpEven(r, offset) if self.value(r).is_multiple_of(2) => Some(offset),
+            Instruction::JumpOne(r, offset) if self.value(r) == 1 => Some(offset),
+            Instruction::JumpEven(..) | Instruction::JumpOne(..) => None,
+        };
 
-            self.pointer += 1;
+        match jump {
+            Some(offset) => self.change_pointer(offset),
+            None => self.pointer += 1,
         }
+
+        Ok(Some((pointer, instruction)))
     }
 
-    fn change_pointer(&mut self, offset: isize) {
-        let p = (self.pointer as isize) + offset;
+    /// Run the program until it finishes. Programs which loop forever never return.
+    pub fn run(&mut self) -> Result<(), Error> {
+        self.execute(&mut NoopTracer, &Progress::new())
+    }
+
+    /// Run the program until it finishes, stopping early if cancelled, such as when a program
+    /// which loops forever runs out of time
+    fn execute(&mut self, tracer: &mut dyn Tracer, progress: &Progress) -> Result<(), Error> {
+        for steps in 0.. {
+            if steps % CHECK_EVERY == 0 {
+                progress.check()?;
+            }
+
+            let (pointer, instruction) = match self.step()? {
+                Some(step) => step,
+                None => break,
+            };
+
+            if tracer.enabled() {
+                tracer.event(Event::InstructionExecuted {
+                    pointer,
+                    instruction: instruction.to_string(),
+                    a: self.a,
+                    b: self.b,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Current value of a register
+    pub fn value(&self, register: Register) -> usize {
+        match register {
+            Register::A => self.a,
+            Register::B => self.b,
+        }
+    }
+
+    /// Set a register, e.g. before running the program
+    pub fn set(&mut self, register: Register, value: usize) {
+        *self.register(register) = value;
+    }
 
-        self.pointer = if p < 0 || (p as usize) >= self.instructions.len() {
-            usize::MAX // bit hacky
-        } else {
-            p as usize
+    fn register(&mut self, register: Register) -> &mut usize {
+        match register {
+            Register::A => &mut self.a,
+            Register::B => &mut self.b,
+        }
+    }
+
+    fn change_pointer(&mut self, offset: isize) {
+        self.pointer = match self.pointer.checked_add_signed(offset) {
+            Some(p) if p < self.instructions.len() => p,
+            _ => usize::MAX, // bit hacky
         };
     }
 }
 
+/// Parse the program, one instruction per line like `jio a, +19`
 #[aoc_generator(day23)]
-pub fn generator(input: &str) -> Computer {
-    let instructions = input
-        .trim()
-        .lines()
-        .map(|line| line.trim())
+pub fn generator(input: &str) -> Result<Computer, Error> {
+    let instructions = lines(23, input)
         .map(|line| {
-            let split = line.split_whitespace().collect::<Vec<_>>();
-
-            match &split[..] {
-                ["hlf", r] => Instruction::Half(Register::from_str(r).unwrap()),
-                ["tpl", r] => Instruction::Triple(Register::from_str(r).unwrap()),
-                ["inc", r] => Instruction::Inc(Register::from_str(r).unwrap()),
-                ["jmp", offset] => Instruction::Jump(offset.parse::<isize>().unwrap()),
-                ["jie", r, offset] => Instruction::JumpEven(
-                    Register::from_str(r).unwrap(),
-                    offset.parse::<isize>().unwrap(),
-                ),
-                ["jio", r, offset] => Instruction::JumpOne(
-                    Register::from_str(r).unwrap(),
-                    offset.parse::<isize>().unwrap(),
-                ),
-                _ => panic!("Invalid instruction: {}", line),
-            }
+            parse::line(
+                &line,
+                instruction,
+                "Expected an instruction like 'jio a, +19'",
+            )
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
+
+    Ok(Computer::new(instructions))
+}
 
-    Computer {
-        a: 0,
-        b: 0,
-        pointer: 0,
-        instructions,
+fn instruction(s: &str) -> Parsed<'_, Instruction> {
+    let (rest, name) = parse::name(s)?;
+    let (rest, _) = char(' ')(rest)?;
+    let register_offset = || separated_pair(register, tag(", "), parse::signed);
+
+    match name {
+        "hlf" => map(register, Instruction::Half)(rest),
+        "tpl" => map(register, Instruction::Triple)(rest),
+        "inc" => map(register, Instruction::Inc)(rest),
+        "jmp" => map(parse::signed, Instruction::Jump)(rest),
+        "jie" => map(register_offset(), |(r, offset)| {
+            Instruction::JumpEven(r, offset)
+        })(rest),
+        "jio" => map(register_offset(), |(r, offset)| {
+            Instruction::JumpOne(r, offset)
+        })(rest),
+        _ => Err(nom::Err::Failure(Failure {
+            input: s,
+            message: Some(format!("Invalid instruction: {}", name)),
+        })),
     }
 }
 
+fn register(s: &str) -> Parsed<'_, Register> {
+    map_res(parse::name, Register::from_str)(s)
+}
+
+/// Value of register `b` once the program finishes
 #[aoc(day23, part1)]
-pub fn part1(input: &Computer) -> usize {
-    let mut computer = input.clone();
-    computer.execute();
-    computer.b
+pub fn part1(input: &Computer) -> Result<usize, Error> {
+    trace(input, Part::One, &mut NoopTracer)
 }
 
+/// Value of register `b` once the program finishes, when register `a` starts at 1
 #[aoc(day23, part2)]
-pub fn part2(input: &Computer) -> usize {
+pub fn part2(input: &Computer) -> Result<usize, Error> {
+    trace(input, Part::Two, &mut NoopTracer)
+}
+
+/// Solve the given part, emitting an event for every instruction executed
+pub fn trace(input: &Computer, part: Part, tracer: &mut dyn Tracer) -> Result<usize, Error> {
+    solve(input, part, tracer, &Progress::new())
+}
+
+/// Solve the given part, stopping if cancelled
+pub fn run(input: &Computer, part: Part, progress: &Progress) -> Result<usize, Error> {
+    solve(input, part, &mut NoopTracer, progress)
+}
+
+fn solve(
+    input: &Computer,
+    part: Part,
+    tracer: &mut dyn Tracer,
+    progress: &Progress,
+) -> Result<usize, Error> {
     let mut computer = input.clone();
-    computer.a = 1;
-    computer.execute();
-    computer.b
+
+    if part == Part::Two {
+        computer.set(Register::A, 1);
+    }
+
+    computer.execute(tracer, progress)?;
+    Ok(computer.value(Register::B))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, random, trace::Collector};
+    use std::time::Duration;
 
     static INPUT: &str = include_str!("../input/2015/day23.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 170);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(170));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 247);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(247));
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = generator("inc b\njio b, +2\ntpl b\ninc a\njie a, -3").unwrap();
+        let mut collector = Collector::new();
+
+        assert_eq!(trace(&input, Part::One, &mut collector), Ok(1));
+        assert_eq!(
+            collector.to_string(),
+            "   0: inc b        a = 0, b = 1
+   1: jio b, +2    a = 0, b = 1
+   3: inc a        a = 1, b = 1
+   4: jie a, -3    a = 1, b = 1
+"
+        );
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let input = generator("inc b\njmp +0").unwrap();
+
+        assert_eq!(
+            run(
+                &input,
+                Part::One,
+                &Progress::with_budget(Duration::from_millis(10))
+            ),
+            Err(Error::Cancelled)
+        );
+        assert_eq!(
+            run(&generator(INPUT).unwrap(), Part::Two, &Progress::new()),
+            Ok(247)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        let program = format!("inc b\n{}", "tpl b\n".repeat(50));
+        let input = generator(&program).unwrap();
+
+        assert_eq!(
+            part1(&input),
+            Err(Error::Overflow {
+                day: 23,
+                operation: "tripling a register"
+            })
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let mut computer = Computer::new(vec![
+            Instruction::Inc(Register::A),
+            Instruction::JumpOne(Register::A, 2),
+            Instruction::Triple(Register::A),
+            Instruction::Inc(Register::A),
+        ]);
+
+        assert_eq!(
+            computer.step(),
+            Ok(Some((0, Instruction::Inc(Register::A))))
+        );
+        assert_eq!(computer.value(Register::A), 1);
+
+        computer.run().unwrap();
+        assert!(computer.is_halted());
+        assert_eq!(computer.step(), Ok(None));
+        assert_eq!(computer.value(Register::A), 2);
+
+        let mut computer = Computer::new(computer.instructions().to_vec());
+        computer.set(Register::A, 2);
+        computer.run().unwrap();
+        assert_eq!(computer.value(Register::A), 10);
+    }
+
+    #[test]
+    fn test_jump_outside_program() {
+        for offset in [isize::MIN, -2, 1, isize::MAX] {
+            let mut computer = Computer::new(vec![
+                Instruction::Inc(Register::A),
+                Instruction::Jump(offset),
+            ]);
+            computer.run().unwrap();
+
+            assert!(computer.is_halted(), "offset {}", offset);
+            assert_eq!(computer.value(Register::A), 1);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let text = "inc a\njio a, +2\ntpl a\njie b, -7\njmp +1\nhlf b";
+        assert_eq!(generator(text).unwrap().to_string(), text);
+
+        for text in &[INPUT.to_string(), random::generate(23, 30, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            assert_eq!(generator(&input.to_string()).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("inc a\njio c, +2").err(),
+            Some(Error::parse(23, 2, 5, "jio c, +2", "Invalid register: c"))
+        );
+        assert_eq!(
+            "a,".parse::<Register>(),
+            Err("Invalid register: a,".to_string())
+        );
+        assert_eq!(
+            generator("jmp +x").err(),
+            Some(Error::parse(
+                23,
+                1,
+                6,
+                "jmp +x",
+                "Expected an instruction like 'jio a, +19'"
+            ))
+        );
+        assert_eq!(
+            generator("mul a").err(),
+            Some(Error::parse(23, 1, 1, "mul a", "Invalid instruction: mul"))
+        );
     }
 }
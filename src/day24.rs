@@ -1,76 +1,284 @@
+//! Day 24: It Hangs in the Balance
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    progress::Progress,
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use std::iter;
 
+/// Parse each line as the weight of a package
 #[aoc_generator(day24)]
-pub fn generator(input: &str) -> Vec<usize> {
-    input
-        .trim()
-        .lines()
-        .map(|l| l.trim().parse().unwrap())
+pub fn generator(input: &str) -> Result<Vec<usize>, Error> {
+    lines(24, input)
+        .map(|line| {
+            let text = line.text.trim();
+            text.parse()
+                .map_err(|e| line.error(text, format!("Invalid package weight {:?}: {}", text, e)))
+        })
         .collect()
 }
 
+/// Lowest quantum entanglement of the passenger compartment when splitting the packages into three equal groups
 #[aoc(day24, part1)]
-pub fn part1(input: &[usize]) -> usize {
-    best(input, 3)
+pub fn part1(input: &[usize]) -> Result<usize, Error> {
+    run(input, Part::One, &Progress::new())
 }
 
+/// Lowest quantum entanglement of the passenger compartment when splitting the packages into four equal groups
 #[aoc(day24, part2)]
-pub fn part2(input: &[usize]) -> usize {
-    best(input, 4)
+pub fn part2(input: &[usize]) -> Result<usize, Error> {
+    run(input, Part::Two, &Progress::new())
+}
+
+/// Solve either part, reporting each group size tried
+pub fn run(input: &[usize], part: Part, progress: &Progress) -> Result<usize, Error> {
+    match part {
+        Part::One => best(input, 3, progress),
+        Part::Two => best(input, 4, progress),
+    }
 }
 
-fn best(input: &[usize], size: usize) -> usize {
-    let total = input.iter().sum::<usize>();
+/// Find the smallest quantum entanglement of the passenger compartment group, which must have as
+/// few packages as possible while leaving packages which can be split into equal groups
+fn best(input: &[usize], size: usize, progress: &Progress) -> Result<usize, Error> {
+    let total = checked::sum(24, "weighing the packages", input.iter().copied())?;
+    if !total.is_multiple_of(size) {
+        return Err(Error::unsolvable(
+            24,
+            format!("The packages can't be split into {} equal groups", size),
+        ));
+    }
     let target = total / size;
-    let mut best = usize::MAX;
 
-    for k in 3..input.len() {
-        for combination in input.iter().combinations(k) {
-            let mut product = 1;
-            let mut acc = 0;
+    progress.set_total(input.len() as u64);
 
-            for &x in combination {
-                acc += x;
-                product *= x;
+    for k in 1..=input.len() {
+        progress.tick()?;
 
-                if acc >= target {
-                    break;
-                }
-            }
+        let mut candidates = Vec::new();
 
-            if acc != target {
-                continue;
+        for c in (0..input.len())
+            .combinations(k)
+            .take_while(|_| !progress.is_cancelled())
+        {
+            let weights = c.iter().map(|&i| input[i]);
+
+            if checked::sum(24, "weighing the packages", weights.clone())? == target {
+                candidates.push((checked::product(24, "entangling packages", weights)?, c));
             }
+        }
+
+        progress.check()?;
+        candidates.sort_unstable();
+
+        for (product, combination) in candidates {
+            progress.check()?;
 
-            if product < best {
-                best = product;
+            let rest = (0..input.len())
+                .filter(|i| !combination.contains(i))
+                .map(|i| input[i])
+                .collect::<Vec<_>>();
+
+            if can_split(&rest, target, size - 1)? {
+                return Ok(product);
             }
         }
+    }
 
-        if best < usize::MAX {
-            return best;
+    Err(Error::unsolvable(
+        24,
+        format!("The packages can't be split into {} equal groups", size),
+    ))
+}
+
+/// Check whether the packages can be split into the given number of groups which each weigh
+/// exactly `target` and have at least one package, even when some packages weigh nothing
+fn can_split(packages: &[usize], target: usize, groups: usize) -> Result<bool, Error> {
+    if groups <= 1 {
+        let weight = checked::sum(24, "weighing the packages", packages.iter().copied())?;
+        return Ok(!packages.is_empty()
+            && weight == checked::mul(24, "weighing the packages", target, groups)?);
+    }
+
+    // the first package has to go in some group, so only try groups which contain it
+    let (&first, rest) = match packages.split_first() {
+        Some(split) => split,
+        None => return Ok(false),
+    };
+
+    for k in 0..rest.len() {
+        for c in (0..rest.len()).combinations(k) {
+            let weights = iter::once(first).chain(c.iter().map(|&i| rest[i]));
+
+            if checked::sum(24, "weighing the packages", weights)? != target {
+                continue;
+            }
+
+            let remaining = (0..rest.len())
+                .filter(|i| !c.contains(i))
+                .map(|i| rest[i])
+                .collect::<Vec<_>>();
+
+            if can_split(&remaining, target, groups - 1)? {
+                return Ok(true);
+            }
         }
     }
 
-    panic!("Tried every value and found no solution")
+    Ok(false)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::Error, random};
+    use proptest::{collection::vec, prelude::*};
 
     static INPUT: &str = include_str!("../input/2015/day24.txt");
 
+    /// Slow but simple solution which tries every subset of packages as the first group, checking
+    /// that the rest can be split by trying every subset of them in turn
+    fn reference(input: &[usize], size: usize) -> Option<usize> {
+        let total = input.iter().sum::<usize>();
+        if total % size != 0 {
+            return None;
+        }
+        let target = total / size;
+
+        let weight = |subset: u32, packages: u32| {
+            (0..input.len())
+                .filter(|i| subset & packages & (1 << i) != 0)
+                .map(|i| input[i])
+                .sum::<usize>()
+        };
+
+        fn splits(
+            packages: u32,
+            groups: usize,
+            weight: &dyn Fn(u32, u32) -> usize,
+            target: usize,
+        ) -> bool {
+            if groups == 1 {
+                return packages != 0 && weight(packages, packages) == target;
+            }
+
+            (1..=packages)
+                .filter(|&subset| subset & !packages == 0 && weight(subset, packages) == target)
+                .any(|subset| splits(packages & !subset, groups - 1, weight, target))
+        }
+
+        let all = (1u32 << input.len()) - 1;
+
+        (1..=all)
+            .filter(|&first| {
+                weight(first, all) == target && splits(all & !first, size - 1, &weight, target)
+            })
+            .map(|first| {
+                let product = (0..input.len())
+                    .filter(|i| first & (1 << i) != 0)
+                    .map(|i| input[i])
+                    .product::<usize>();
+
+                (first.count_ones(), product)
+            })
+            .min()
+            .map(|(_, product)| product)
+    }
+
+    /// Packages which can be split into the given number of groups of equal weight, each of a few
+    /// packages
+    fn balanced(groups: usize) -> impl Strategy<Value = Vec<usize>> {
+        (vec(vec(1..=30usize, 1..=3), groups), 1..=30usize)
+            .prop_map(|(groups, extra)| {
+                let partial = |group: &[usize]| group[..group.len() - 1].iter().sum::<usize>();
+                let weight = groups.iter().map(|g| partial(g)).max().unwrap() + extra;
+
+                groups
+                    .into_iter()
+                    .flat_map(|mut group| {
+                        let last = group.len() - 1;
+                        group[last] = weight - partial(&group);
+                        group
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .prop_shuffle()
+    }
+
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 10439961859);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(10439961859));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 72050269);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(72050269));
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        assert_eq!(
+            part1(&[1, 2, 3, 4]),
+            Err(Error::unsolvable(
+                24,
+                "The packages can't be split into 3 equal groups"
+            ))
+        );
+        assert_eq!(
+            part1(&[1, 1, 4]),
+            Err(Error::unsolvable(
+                24,
+                "The packages can't be split into 3 equal groups"
+            ))
+        );
+
+        // every group needs a package, even when they weigh nothing
+        assert!(part1(&[0, 0]).is_err());
+        assert_eq!(part1(&[0, 0, 0]), Ok(0));
+    }
+
+    #[test]
+    fn test_random_input() {
+        let input = generator(&random::generate(24, 12, 1).unwrap()).unwrap();
+        assert_eq!(part1(&input).ok(), reference(&input, 3));
+        assert_eq!(part2(&input).ok(), reference(&input, 4));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_matches_reference(input in vec(0..=30usize, 0..=10)) {
+            prop_assert_eq!(part1(&input).ok(), reference(&input, 3));
+            prop_assert_eq!(part2(&input).ok(), reference(&input, 4));
+        }
+
+        #[test]
+        fn test_balanced_matches_reference(three in balanced(3), four in balanced(4)) {
+            prop_assert!(reference(&three, 3).is_some());
+            prop_assert_eq!(part1(&three).ok(), reference(&three, 3));
+            prop_assert!(reference(&four, 4).is_some());
+            prop_assert_eq!(part2(&four).ok(), reference(&four, 4));
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("1\n2\n3kg").err(),
+            Some(Error::parse(
+                24,
+                3,
+                1,
+                "3kg",
+                "Invalid package weight \"3kg\": invalid digit found in string"
+            ))
+        );
     }
 }
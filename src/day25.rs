@@ -1,32 +1,70 @@
-use crate::compass::Point;
+//! Day 25: Let It Snow
+
+use crate::{
+    compass::Point,
+    error::{lines, Error},
+    parse::{self, Parsed},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use lazy_static::lazy_static;
-use regex::Regex;
+use nom::{
+    bytes::complete::{tag, take_until},
+    combinator::{map_res, rest},
+    sequence::preceded,
+};
 
+/// Parse the row and column of the code to enter
 #[aoc_generator(day25)]
-pub fn generator(input: &str) -> Point {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"row (\d+), column (\d+)").unwrap();
-    }
+pub fn generator(input: &str) -> Result<Point, Error> {
+    let line = lines(25, input)
+        .next()
+        .ok_or_else(|| Error::end_of_input(25, input, "Expected a row and column"))?;
+
+    parse::line(
+        &line,
+        position,
+        "Expected a sentence containing 'row <n>, column <n>'",
+    )
+}
 
-    let line = input.lines().next().unwrap();
-    let captures = RE.captures(line).unwrap();
-    let row = captures.get(1).unwrap().as_str().parse::<i32>().unwrap();
-    let column = captures.get(2).unwrap().as_str().parse::<i32>().unwrap();
+/// Find `row <n>, column <n>` in a sentence
+fn position(s: &str) -> Parsed<'_, Point> {
+    let start_at_one = |value: i32| match value {
+        value if value > 0 => Ok(value),
+        _ => Err("Rows and columns start at 1"),
+    };
 
-    Point::new(column, row)
+    let (s, _) = take_until("row ")(s)?;
+    let (s, row) = preceded(tag("row "), map_res(parse::integer, start_at_one))(s)?;
+    let (s, column) = preceded(tag(", column "), map_res(parse::integer, start_at_one))(s)?;
+    let (s, _) = rest(s)?;
+
+    Ok((s, Point::new(column, row)))
 }
 
+/// First code in the top left corner
+const FIRST: u64 = 20151125;
+
+/// Each code is the previous one multiplied by this...
+const MUL: u64 = 252533;
+
+/// ...modulo this
+const DIV: u64 = 33554393;
+
+/// Code at the given row and column
 #[aoc(day25, part1)]
 pub fn part1(input: &Point) -> u64 {
+    iteration(input, Part::One)
+}
+
+/// Find the code by generating every code before it, diagonal by diagonal
+pub fn iteration(input: &Point, _part: Part) -> u64 {
     let mut row = 1;
     let mut col = 1;
-    let mut code: u64 = 20151125;
-    let mul: u64 = 252533;
-    let div: u64 = 33554393;
+    let mut code = FIRST;
 
     // move through the diagonals until you get to the right column/row
-    loop {
+    while row != input.y || col != input.x {
         col += 1;
         row -= 1;
 
@@ -35,23 +73,85 @@ pub fn part1(input: &Point) -> u64 {
             col = 1;
         }
 
-        code = (code * mul) % div;
+        code = (code * MUL) % DIV;
+    }
+
+    code
+}
+
+/// Find the code directly from its position in the sequence, as the first code multiplied by
+/// `MUL` to the power of the number of codes before it, using exponentiation by squaring
+pub fn modular_exponentiation(input: &Point, _part: Part) -> u64 {
+    let (row, col) = (input.y as u64, input.x as u64);
+
+    // the code is on diagonal row + col - 1, after every code on the diagonals before it
+    let diagonal = row + col - 1;
+    let before = diagonal * (diagonal - 1) / 2 + col - 1;
 
-        if row == input.y && col == input.x {
-            return code;
+    let mut code = FIRST;
+    let mut base = MUL;
+    let mut exponent = before;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            code = code * base % DIV;
         }
+
+        base = base * base % DIV;
+        exponent >>= 1;
     }
+
+    code
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     static INPUT: &str = include_str!("../input/2015/day25.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part1(&input), 19980801);
     }
+
+    #[test]
+    fn test_part1_examples() {
+        assert_eq!(part1(&Point::new(1, 1)), 20151125);
+        assert_eq!(part1(&Point::new(1, 2)), 31916031);
+        assert_eq!(part1(&Point::new(2, 1)), 18749137);
+        assert_eq!(part1(&Point::new(6, 6)), 27995004);
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let input = generator(INPUT).unwrap();
+        assert_eq!(modular_exponentiation(&input, Part::One), 19980801);
+
+        for row in 1..30 {
+            for col in 1..30 {
+                let point = Point::new(col, row);
+                assert_eq!(
+                    modular_exponentiation(&point, Part::One),
+                    iteration(&point, Part::One)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("Enter the code at row 0, column 3029.").err(),
+            Some(Error::parse(
+                25,
+                1,
+                23,
+                "Enter the code at row 0, column 3029.",
+                "Rows and columns start at 1"
+            ))
+        );
+    }
 }
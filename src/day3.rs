@@ -1,12 +1,37 @@
-use crate::compass::{Direction, Point};
+//! Day 3: Perfectly Spherical Houses in a Vacuum
+
+use crate::{
+    compass::{Direction, Point},
+    error::{lines, Error},
+    render::{Image, Recorder},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+};
 
+/// Parse the input as a list of moves, written as arrows
 #[aoc_generator(day3)]
-pub fn generator(input: &str) -> Vec<Direction> {
-    input.chars().map(|c| Direction::from_char(&c)).collect()
+pub fn generator(input: &str) -> Result<Vec<Direction>, Error> {
+    let mut directions = Vec::with_capacity(input.len());
+
+    for line in lines(3, input) {
+        for (i, c) in line.text.char_indices() {
+            let direction = Direction::from_char(&c).ok_or_else(|| {
+                let fragment = &line.text[i..i + c.len_utf8()];
+                line.error(fragment, format!("Unrecognised direction {}", c))
+            })?;
+
+            directions.push(direction);
+        }
+    }
+
+    Ok(directions)
 }
 
+/// Number of houses which get at least one present
 #[aoc(day3, part1)]
 pub fn part1(input: &[Direction]) -> usize {
     let locations = input
@@ -16,11 +41,13 @@ pub fn part1(input: &[Direction]) -> usize {
             *current = next;
             Some(next)
         })
+        .chain(iter::once(Point::default())) // plus initial position
         .collect::<HashSet<_>>();
 
-    locations.len() + 1 // plus initial position
+    locations.len()
 }
 
+/// Number of houses which get at least one present when Santa and Robo-Santa take turns
 #[aoc(day3, part2)]
 pub fn part2(input: &[Direction]) -> usize {
     let santa_locations = input
@@ -45,20 +72,83 @@ pub fn part2(input: &[Direction]) -> usize {
 
     let unique_locations = santa_locations
         .chain(robot_locations)
+        .chain(iter::once(Point::default())) // plus initial position
         .collect::<HashSet<_>>();
 
     unique_locations.len()
 }
 
+/// Render the number of presents delivered to each house, with north at the top, recording the
+/// grid after each move
+#[doc(hidden)]
+pub fn render(input: &[Direction], part: Part, recorder: &mut dyn Recorder) -> Image {
+    let santas = match part {
+        Part::One => 1,
+        Part::Two => 2,
+    };
+
+    let mut positions = vec![Point::default(); santas];
+    let route = input
+        .iter()
+        .enumerate()
+        .map(|(i, direction)| {
+            let position = &mut positions[i % santas];
+            *position = position.move_direction(direction);
+            *position
+        })
+        .collect::<Vec<_>>();
+
+    let mut visits = HashMap::new();
+    visits.insert(Point::default(), santas);
+
+    for &house in &route {
+        *visits.entry(house).or_insert(0) += 1;
+    }
+
+    let min_x = visits.keys().map(|p| p.x).min().unwrap_or(0);
+    let max_x = visits.keys().map(|p| p.x).max().unwrap_or(0);
+    let min_y = visits.keys().map(|p| p.y).min().unwrap_or(0);
+    let max_y = visits.keys().map(|p| p.y).max().unwrap_or(0);
+    let max = visits
+        .values()
+        .copied()
+        .max()
+        .unwrap_or(1)
+        .min(u8::MAX as usize) as u8;
+
+    let mut image = Image::new(
+        (max_x - min_x + 1) as usize,
+        (max_y - min_y + 1) as usize,
+        max,
+    );
+
+    let deliver = |image: &mut Image, house: Point| {
+        let (x, y) = ((house.x - min_x) as usize, (max_y - house.y) as usize);
+        image.set(x, y, image.get(x, y).saturating_add(1));
+    };
+
+    for _ in 0..santas {
+        deliver(&mut image, Point::default());
+    }
+
+    for house in route {
+        deliver(&mut image, house);
+        recorder.frame(&|| image.clone());
+    }
+
+    image
+}
+
 #[cfg(test)]
 mod tests {
     use super::{generator, part1, part2, Direction};
+    use crate::error::Error;
 
     static INPUT: &str = include_str!("../input/2015/day3.txt");
 
     #[test]
     fn test_generator() {
-        let actual = generator("^v><");
+        let actual = generator("^v><").unwrap();
         let expected = &[
             Direction::North,
             Direction::South,
@@ -68,15 +158,23 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("^v\n>x<"),
+            Err(Error::parse(3, 2, 2, ">x<", "Unrecognised direction x"))
+        );
+    }
+
     #[test]
     fn test_part1_real() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part1(&input), 2081);
     }
 
     #[test]
     fn test_part2_real() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part2(&input), 2341);
     }
 }
@@ -1,42 +1,106 @@
+//! Day 4: The Ideal Stocking Stuffer
+
+use crate::{
+    progress::{Cancelled, Progress},
+    Part,
+};
 use aoc_runner_derive::aoc;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+/// Lowest number which gives an MD5 hash starting with five zeroes when appended to the key
 #[aoc(day4, part1)]
 pub fn part1(input: &str) -> usize {
-    check_hash(input, 5, 300_000) // 254575
+    run(input, Part::One, &Params::default(), &Progress::new()).unwrap()
 }
 
+/// Lowest number which gives an MD5 hash starting with six zeroes when appended to the key
 #[aoc(day4, part2)]
 pub fn part2(input: &str) -> usize {
-    check_hash(input, 6, 1_100_100) // 1038736
+    run(input, Part::Two, &Params::default(), &Progress::new()).unwrap()
+}
+
+/// Number of leading zeroes the hash must start with in each part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Leading zeroes for part 1, 5 in the puzzle
+    pub part1_zeroes: usize,
+
+    /// Leading zeroes for part 2, 6 in the puzzle
+    pub part2_zeroes: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_zeroes: 5,
+            part2_zeroes: 6,
+        }
+    }
+}
+
+/// Most leading zeroes which can be searched for, as an MD5 hash has 32 hex digits
+pub const MAX_ZEROES: usize = 32;
+
+/// Number of suffixes checked in parallel at a time, so that the search can stop soon after the
+/// lowest match without a fixed upper limit
+const CHUNK_SIZE: usize = 100_000;
+
+/// Solve either part, reporting each chunk of hashes checked
+pub fn run(
+    input: &str,
+    part: Part,
+    params: &Params,
+    progress: &Progress,
+) -> Result<usize, Cancelled> {
+    match part {
+        Part::One => check_hash(input, params.part1_zeroes, progress),
+        Part::Two => check_hash(input, params.part2_zeroes, progress),
+    }
 }
 
-fn check_hash(input: &str, leading_zeroes: usize, search_size: usize) -> usize {
+fn check_hash(input: &str, leading_zeroes: usize, progress: &Progress) -> Result<usize, Cancelled> {
     let input = input.trim();
-    let range = (1..search_size).collect::<Vec<_>>();
-    let expected = std::iter::repeat('0')
-        .take(leading_zeroes)
-        .collect::<String>();
-
-    *range
-        .par_iter()
-        .find_any(|&i| {
+    let expected = "0".repeat(leading_zeroes);
+
+    // each hash has a 1 in 16^n chance of starting with n zeroes, so expect to check about that many
+    let hashes = 16u64.saturating_pow(leading_zeroes as u32);
+    progress.set_total(hashes.div_ceil(CHUNK_SIZE as u64));
+
+    for start in (1..).step_by(CHUNK_SIZE) {
+        progress.tick()?;
+
+        let check = |i| {
             let check = format!("{}{}", input, i);
             let hash = md5::compute(check);
             let formatted_hash = format!("{:x}", hash);
-            formatted_hash[0..leading_zeroes] == expected
-        })
-        .unwrap_or_else(|| {
-            panic!(
-                "Couldn't find a hash starting with {} or more zeroes",
-                leading_zeroes
-            )
-        })
+
+            if formatted_hash[0..leading_zeroes] == expected {
+                Some(i)
+            } else {
+                None
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        let found = (start..start + CHUNK_SIZE)
+            .into_par_iter()
+            .find_map_first(check);
+
+        #[cfg(not(feature = "parallel"))]
+        let found = (start..start + CHUNK_SIZE).find_map(check);
+
+        if let Some(i) = found {
+            return Ok(i);
+        }
+    }
+
+    unreachable!()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::*;
 
     static INPUT: &str = include_str!("../input/2015/day4.txt");
 
@@ -49,4 +113,40 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(INPUT), 1038736);
     }
+
+    #[test]
+    fn test_cancelled() {
+        let progress = Progress::new();
+        progress.cancel();
+        assert_eq!(
+            run(INPUT, Part::Two, &Params::default(), &progress),
+            Err(Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new();
+
+        // about 16^5 hashes are expected, in 11 chunks, and the answer is in the seventh
+        assert_eq!(
+            run("abcdef", Part::One, &Params::default(), &progress),
+            Ok(609043)
+        );
+        assert_eq!(progress.fraction(), Some(7.0 / 11.0));
+    }
+
+    #[test]
+    fn test_params() {
+        let params = Params {
+            part1_zeroes: 2,
+            part2_zeroes: 3,
+        };
+
+        assert_eq!(run("abcdef", Part::One, &params, &Progress::new()), Ok(298));
+        assert_eq!(
+            run("abcdef", Part::Two, &params, &Progress::new()),
+            Ok(3337)
+        );
+    }
 }
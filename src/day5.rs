@@ -1,6 +1,9 @@
+//! Day 5: Doesn't He Have Intern-Elves For This?
+
 use aoc_runner_derive::aoc;
 
 // deliberately not done with regex to make it fast
+/// Number of nice strings under the original rules
 #[aoc(day5, part1)]
 pub fn part1(input: &str) -> usize {
     input
@@ -14,6 +17,7 @@ pub fn part1(input: &str) -> usize {
 }
 
 // ok fine, we'll use regex then
+/// Number of nice strings under the new rules
 #[aoc(day5, part2)]
 pub fn part2(input: &str) -> usize {
     input
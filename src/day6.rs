@@ -1,10 +1,32 @@
+//! Day 6: Probably a Fire Hazard
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Failure, Parsed},
+    render::{Image, NoopRecorder, Recorder},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::str::FromStr;
+use nom::{
+    bytes::complete::tag,
+    combinator::{map_res, opt},
+};
+use std::{fmt, str::FromStr};
+
+/// Lights are arranged in a square grid of this size
+const GRID_SIZE: usize = 1000;
 
+/// What an [Instruction] does to each light in its rectangle
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
-enum Operation {
+pub enum Operation {
+    /// `turn on`, or increase the brightness by 1
     On,
+
+    /// `turn off`, or decrease the brightness by 1 down to 0
     Off,
+
+    /// `toggle`, or increase the brightness by 2
     Toggle,
 }
 
@@ -21,100 +43,234 @@ impl FromStr for Operation {
     }
 }
 
-#[derive(Debug)]
-pub struct Instruction {
-    operation: Operation,
-    start: (usize, usize),
-    stop: (usize, usize),
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::On => write!(f, "turn on"),
+            Operation::Off => write!(f, "turn off"),
+            Operation::Toggle => write!(f, "toggle"),
+        }
+    }
 }
 
-impl FromStr for Instruction {
-    type Err = ();
+/// Instruction applying an operation to a rectangle of lights, inclusive of both corners
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Instruction {
+    /// What to do to each light
+    pub operation: Operation,
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(' ').collect::<Vec<_>>();
+    /// Top left corner as `(x, y)`
+    pub start: (usize, usize),
+
+    /// Bottom right corner as `(x, y)`
+    pub stop: (usize, usize),
+}
 
-        let operation = Operation::from_str(parts[parts.len() - 4]).unwrap();
+impl Instruction {
+    fn parse(s: &str) -> Parsed<'_, Self> {
+        let (rest, turn) = opt(tag("turn "))(s)?;
+        let (rest, operation) = map_res(parse::name, Operation::from_str)(rest)?;
 
-        let start = parts[parts.len() - 3].split(',').collect::<Vec<_>>();
-        let start = (start[0].parse().unwrap(), start[1].parse().unwrap());
+        // lights are turned on and off, but toggled without turning
+        if turn.is_some() == (operation == Operation::Toggle) {
+            return Err(nom::Err::Failure(Failure {
+                input: s,
+                message: Some(format!("Expected '{}'", operation)),
+            }));
+        }
 
-        let stop = parts[parts.len() - 1].split(',').collect::<Vec<_>>();
-        let stop = (stop[0].parse().unwrap(), stop[1].parse().unwrap());
+        let (s, _) = tag(" ")(rest)?;
+        let (s, start) = coordinate(s)?;
+        let (s, _) = tag(" through ")(s)?;
+        let (s, stop) = coordinate(s)?;
 
-        Ok(Instruction {
-            operation,
-            start,
-            stop,
-        })
+        Ok((
+            s,
+            Instruction {
+                operation,
+                start,
+                stop,
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {},{} through {},{}",
+            self.operation, self.start.0, self.start.1, self.stop.0, self.stop.1
+        )
     }
 }
 
+/// Parse an `x,y` coordinate which must be within the grid
+fn coordinate(s: &str) -> Parsed<'_, (usize, usize)> {
+    let within_grid = |value: usize| match value {
+        value if value < GRID_SIZE => Ok(value),
+        _ => Err(format!("Coordinate {} is outside the grid", value)),
+    };
+
+    parse::comma_pair(map_res(parse::integer, within_grid))(s)
+}
+
+/// Parse each line as an instruction like `turn on 0,0 through 999,999`
 #[aoc_generator(day6)]
-pub fn generator(input: &str) -> Vec<Instruction> {
-    input
-        .trim()
-        .lines()
-        .map(|line| line.trim())
-        .map(|line| Instruction::from_str(line).unwrap())
+pub fn generator(input: &str) -> Result<Vec<Instruction>, Error> {
+    lines(6, input)
+        .map(|line| {
+            parse::line(
+                &line,
+                Instruction::parse,
+                "Expected an instruction like 'turn on 0,0 through 999,999'",
+            )
+        })
         .collect()
 }
 
+/// Number of lights lit after following the instructions
 #[aoc(day6, part1)]
-pub fn part1(input: &[Instruction]) -> usize {
-    let mut grid = [[false; 1000]; 1000];
+pub fn part1(input: &[Instruction]) -> Result<usize, Error> {
+    let grid = lights(input, Part::One, &mut NoopRecorder)?;
+    Ok(grid.iter().filter(|&&v| v > 0).count())
+}
 
-    for instruction in input {
-        #[allow(clippy::needless_range_loop)]
-        for x in instruction.start.0..instruction.stop.0 + 1 {
-            for y in instruction.start.1..instruction.stop.1 + 1 {
-                match instruction.operation {
-                    Operation::On => grid[x][y] = true,
-                    Operation::Off => grid[x][y] = false,
-                    Operation::Toggle => grid[x][y] = !grid[x][y],
-                };
-            }
-        }
-    }
+/// Total brightness after following the instructions with brightness controls
+#[aoc(day6, part2)]
+pub fn part2(input: &[Instruction]) -> Result<usize, Error> {
+    let grid = lights(input, Part::Two, &mut NoopRecorder)?;
+    Ok(grid.iter().map(|&v| v as usize).sum())
+}
 
-    grid.iter().flatten().filter(|&&v| v).count()
+/// Render the brightness of each light, recording the grid after each instruction
+#[doc(hidden)]
+pub fn render(
+    input: &[Instruction],
+    part: Part,
+    recorder: &mut dyn Recorder,
+) -> Result<Image, Error> {
+    let grid = lights(input, part, recorder)?;
+    Ok(Image::from_pixels(GRID_SIZE, GRID_SIZE, grid))
 }
 
-#[aoc(day6, part2)]
-pub fn part2(input: &[Instruction]) -> usize {
-    let mut grid = [[0u8; 1000]; 1000];
+/// Follow the instructions, returning the brightness of each light in rows. In part one lights
+/// are either off (0) or on (1).
+fn lights(
+    input: &[Instruction],
+    part: Part,
+    recorder: &mut dyn Recorder,
+) -> Result<Vec<u8>, Error> {
+    let mut grid = vec![0u8; GRID_SIZE * GRID_SIZE];
 
     for instruction in input {
-        #[allow(clippy::needless_range_loop)]
-        for x in instruction.start.0..instruction.stop.0 + 1 {
-            for y in instruction.start.1..instruction.stop.1 + 1 {
-                match instruction.operation {
-                    Operation::On => grid[x][y] += 1,
-                    Operation::Off => grid[x][y] = grid[x][y].saturating_sub(1),
-                    Operation::Toggle => grid[x][y] += 2,
+        for y in instruction.start.1..instruction.stop.1 + 1 {
+            for x in instruction.start.0..instruction.stop.0 + 1 {
+                let light = &mut grid[y * GRID_SIZE + x];
+
+                *light = match (part, instruction.operation) {
+                    (Part::One, Operation::On) => 1,
+                    (Part::One, Operation::Off) => 0,
+                    (Part::One, Operation::Toggle) => 1 - *light,
+                    (Part::Two, Operation::On) => checked::add(6, "turning up a light", *light, 1)?,
+                    (Part::Two, Operation::Off) => light.saturating_sub(1),
+                    (Part::Two, Operation::Toggle) => {
+                        checked::add(6, "turning up a light", *light, 2)?
+                    }
                 };
             }
         }
+
+        recorder.frame(&|| Image::from_pixels(GRID_SIZE, GRID_SIZE, grid.clone()));
     }
 
-    grid.iter().flatten().map(|&v| v as usize).sum()
+    Ok(grid)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{generator, part1, part2};
+    use crate::error::Error;
+    use crate::random;
 
     static INPUT: &str = include_str!("../input/2015/day6.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 377891);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(377891));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 14110788);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(14110788));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let input = generator("turn off 499,0 through 500,999\ntoggle 1,2 through 3,4").unwrap();
+        assert_eq!(input[0].to_string(), "turn off 499,0 through 500,999");
+        assert_eq!(input[1].to_string(), "toggle 1,2 through 3,4");
+
+        for text in &[INPUT.to_string(), random::generate(6, 50, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            let printed = input.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(generator(&printed.join("\n")).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("turn of 0,0 through 1,1").err(),
+            Some(Error::parse(
+                6,
+                1,
+                6,
+                "turn of 0,0 through 1,1",
+                "Unrecognised operation: of"
+            ))
+        );
+        assert_eq!(
+            generator("toggle 0,0 through 1000,1").err(),
+            Some(Error::parse(
+                6,
+                1,
+                20,
+                "toggle 0,0 through 1000,1",
+                "Coordinate 1000 is outside the grid"
+            ))
+        );
+        assert_eq!(
+            generator("on 0,0 through 1,1").err(),
+            Some(Error::parse(
+                6,
+                1,
+                1,
+                "on 0,0 through 1,1",
+                "Expected 'turn on'"
+            ))
+        );
+        assert_eq!(
+            generator("turn toggle 0,0 through 1,1").err(),
+            Some(Error::parse(
+                6,
+                1,
+                1,
+                "turn toggle 0,0 through 1,1",
+                "Expected 'toggle'"
+            ))
+        );
+        assert_eq!(
+            generator("toggle 0,0 to 1,1").err(),
+            Some(Error::parse(
+                6,
+                1,
+                11,
+                "toggle 0,0 to 1,1",
+                "Expected an instruction like 'turn on 0,0 through 999,999'"
+            ))
+        );
     }
 }
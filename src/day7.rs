@@ -1,8 +1,25 @@
+//! Day 7: Some Assembly Required
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Failure, Parsed},
+    trace::{Event, NoopTracer, Tracer},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::{collections::HashMap, str::FromStr};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::char,
+    combinator::map,
+    error::context,
+    sequence::preceded,
+};
+use std::{collections::HashMap, fmt, str::FromStr};
 
 /// Source for a wire value
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Source {
     /// Source is the value of another wire
     Wire(String),
@@ -15,22 +32,21 @@ impl FromStr for Source {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let c = s
-            .chars()
-            .next()
-            .ok_or("Empty input supplied to Source::for_str")?;
-
-        Ok(if c.is_ascii_digit() {
-            let value = s.parse::<u16>().map_err(|e| e.to_string())?;
-            Source::Raw(value)
-        } else {
-            Source::Wire(s.to_owned())
-        })
+        parse::all(s, source, &format!("Invalid source: {:?}", s))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Wire(wire) => write!(f, "{}", wire),
+            Source::Raw(value) => write!(f, "{}", value),
+        }
     }
 }
 
 /// Operation to perform on a wire as part of an [Instruction]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Operation {
     /// Set the destination wire to the source value
     Set(Source),
@@ -57,86 +73,186 @@ impl FromStr for Operation {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(' ').collect::<Vec<_>>();
-
-        Ok(match parts.len() {
-            1 => {
-                let a = Source::from_str(parts[0])?;
-                Operation::Set(a)
-            }
-            2 => {
-                let a = Source::from_str(parts[1])?;
-                Operation::Not(a)
-            }
-            3 => {
-                let a = Source::from_str(parts[0])?;
-                let b = Source::from_str(parts[2])?;
-
-                match parts[1] {
-                    "AND" => Operation::And(a, b),
-                    "OR" => Operation::Or(a, b),
-                    "LSHIFT" => Operation::LShift(a, b),
-                    "RSHIFT" => Operation::RShift(a, b),
-                    _ => return Err(format!("Unsupported operation: {}", s)),
-                }
-            }
-            _ => return Err(format!("Unsupported operation: {}", s)),
-        })
+        parse::all(s, operation, &format!("Unsupported operation: {}", s))
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Set(a) => write!(f, "{}", a),
+            Operation::Not(a) => write!(f, "NOT {}", a),
+            Operation::And(a, b) => write!(f, "{} AND {}", a, b),
+            Operation::Or(a, b) => write!(f, "{} OR {}", a, b),
+            Operation::LShift(a, b) => write!(f, "{} LSHIFT {}", a, b),
+            Operation::RShift(a, b) => write!(f, "{} RSHIFT {}", a, b),
+        }
     }
 }
 
 /// Instruction to perform which generates the value of the destination wire
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Instruction {
     /// Operation to perform
-    operation: Operation,
+    pub operation: Operation,
 
     /// Destination wire ID
-    dest: String,
+    pub dest: String,
 }
 
-impl FromStr for Instruction {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(" -> ").collect::<Vec<_>>();
-
-        let operation = Operation::from_str(parts[0])?;
-        let dest = parts[1].to_owned();
+impl Instruction {
+    /// Parse an instruction such as `x AND y -> z`
+    fn parse(s: &str) -> Parsed<'_, Self> {
+        let (s, operation) = operation(s)?;
+        let (s, _) = tag(" -> ")(s)?;
+        let (s, dest) = context(
+            "Expected a destination wire made of lowercase letters",
+            wire,
+        )(s)?;
+
+        Ok((
+            s,
+            Instruction {
+                operation,
+                dest: dest.to_owned(),
+            },
+        ))
+    }
+}
 
-        Ok(Instruction { operation, dest })
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.operation, self.dest)
     }
 }
 
 /// Parse the input file to a set of [instructions](Instruction) for generating wire values
 #[aoc_generator(day7)]
-pub fn generator(input: &str) -> Vec<Instruction> {
-    input
-        .trim()
-        .lines()
-        .map(|line| line.trim())
-        .map(|line| Instruction::from_str(line).unwrap())
+pub fn generator(input: &str) -> Result<Vec<Instruction>, Error> {
+    lines(7, input)
+        .map(|line| {
+            parse::line(
+                &line,
+                Instruction::parse,
+                "Expected an instruction like 'x AND y -> z'",
+            )
+        })
         .collect()
 }
 
+/// Parse a wire name, which is made of lowercase letters
+fn wire(s: &str) -> Parsed<'_, &str> {
+    take_while1(|c: char| c.is_ascii_lowercase())(s)
+}
+
+fn source(s: &str) -> Parsed<'_, Source> {
+    alt((
+        map(parse::integer, Source::Raw),
+        map(wire, |w| Source::Wire(w.to_owned())),
+    ))(s)
+}
+
+fn operation(s: &str) -> Parsed<'_, Operation> {
+    alt((
+        map(preceded(tag("NOT "), source), Operation::Not),
+        gate,
+        map(source, Operation::Set),
+    ))(s)
+}
+
+/// Parse an operation with two sources, like `x AND y`, where gates are named in capitals
+fn gate(s: &str) -> Parsed<'_, Operation> {
+    let (s, a) = source(s)?;
+    let (s, _) = char(' ')(s)?;
+    let (rest, name) = take_while1(|c: char| c.is_ascii_uppercase())(s)?;
+
+    let gate: fn(Source, Source) -> Operation = match name {
+        "AND" => Operation::And,
+        "OR" => Operation::Or,
+        "LSHIFT" => Operation::LShift,
+        "RSHIFT" => Operation::RShift,
+        _ => {
+            return Err(nom::Err::Failure(Failure {
+                input: s,
+                message: Some(format!("Unsupported gate: {}", name)),
+            }))
+        }
+    };
+
+    let (s, _) = char(' ')(rest)?;
+    let (s, b) = source(s)?;
+
+    Ok((s, gate(a, b)))
+}
+
 /// Get the value of wire `a` which is generated by the given [instructions](Instruction)
 #[aoc(day7, part1)]
-pub fn part1(input: &[Instruction]) -> u16 {
-    execute(input, false)
+pub fn part1(input: &[Instruction]) -> Result<u16, Error> {
+    trace(input, Part::One, &mut NoopTracer)
 }
 
 /// Get the value of wire `a` which is generated by the given [instructions](Instruction)
 /// after replacing the input for wire `b` with the value returned from part 1
 #[aoc(day7, part2)]
-pub fn part2(input: &[Instruction]) -> u16 {
-    execute(input, true)
+pub fn part2(input: &[Instruction]) -> Result<u16, Error> {
+    trace(input, Part::Two, &mut NoopTracer)
+}
+
+/// Wires which the puzzle asks about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Wire whose value is the answer, `a` in the puzzle
+    pub wire: String,
+
+    /// Wire which is overridden with the answer to part 1 in part 2, `b` in the puzzle
+    pub override_wire: String,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            wire: "a".to_string(),
+            override_wire: "b".to_string(),
+        }
+    }
+}
+
+/// Solve the given part, emitting an event as the value of each wire is resolved
+pub fn trace(input: &[Instruction], part: Part, tracer: &mut dyn Tracer) -> Result<u16, Error> {
+    execute_part(input, part, &Params::default(), tracer)
+}
+
+/// Solve the given part, asking about the given wires
+pub fn solve(input: &[Instruction], part: Part, params: &Params) -> Result<u16, Error> {
+    execute_part(input, part, params, &mut NoopTracer)
+}
+
+fn execute_part(
+    input: &[Instruction],
+    part: Part,
+    params: &Params,
+    tracer: &mut dyn Tracer,
+) -> Result<u16, Error> {
+    let wire_override = match part {
+        Part::One => None,
+        Part::Two => Some(execute(input, params, None, &mut NoopTracer)?),
+    };
+
+    execute(input, params, wire_override, tracer)
 }
 
 /// Execute the given instructions
 ///
 /// - *input* - Input instructions
-/// - *part_2* - indicates whether part 2 is running or not
-fn execute(input: &[Instruction], part_2: bool) -> u16 {
+/// - *params* - Wire to get the value of, and wire to override
+/// - *wire_override* - Value to use for the override wire instead of its instruction, for part 2
+/// - *tracer* - Receives an event for every wire resolved
+fn execute(
+    input: &[Instruction],
+    params: &Params,
+    wire_override: Option<u16>,
+    tracer: &mut dyn Tracer,
+) -> Result<u16, Error> {
     let mut wires = HashMap::with_capacity(input.len());
     let mut instructions = HashMap::with_capacity(input.len());
 
@@ -144,100 +260,227 @@ fn execute(input: &[Instruction], part_2: bool) -> u16 {
         instructions.insert(i.dest.as_ref(), i);
     }
 
-    let b_override = Instruction {
-        operation: Operation::Set(Source::Raw(16076u16)),
-        dest: instructions["b"].dest.clone(),
-    };
+    let wire_override = wire_override.map(|value| Instruction {
+        operation: Operation::Set(Source::Raw(value)),
+        dest: params.override_wire.clone(),
+    });
 
-    if part_2 {
-        // override b with the value from part 1
-        instructions.insert("b", &b_override);
+    if let Some(wire_override) = &wire_override {
+        instructions.insert(wire_override.dest.as_str(), wire_override);
     }
 
-    get_value_by_wire("a", &mut wires, &instructions)
+    get_value_by_wire(&params.wire, &mut wires, &instructions, tracer)
 }
 
 /// Get the value of the given wire. This will run recursively backwards from the desired wire until
 /// all input wire values are known.
 ///
 /// - *id* - ID of the wire to get
-/// - *wires* - Lookup of currently known wire values by ID
+/// - *wires* - Lookup of wire values by ID, which are `None` while the wire is being resolved
 /// - *instructions* - Lookup of instructions for generating a wire value by destination ID
+/// - *tracer* - Receives an event for every wire resolved
 fn get_value_by_wire(
     id: &str,
-    wires: &mut HashMap<String, u16>,
+    wires: &mut HashMap<String, Option<u16>>,
     instructions: &HashMap<&str, &Instruction>,
-) -> u16 {
-    if wires.contains_key(id) {
-        return wires[id];
+    tracer: &mut dyn Tracer,
+) -> Result<u16, Error> {
+    match wires.get(id) {
+        Some(Some(value)) => return Ok(*value),
+        Some(None) => {
+            return Err(Error::unsolvable(
+                7,
+                format!("Wire {} depends on its own signal", id),
+            ))
+        }
+        None => {}
     }
 
-    let instruction = instructions[id];
+    let instruction = instructions
+        .get(id)
+        .ok_or_else(|| Error::unsolvable(7, format!("Wire {} has no signal", id)))?;
+
+    wires.insert(id.to_string(), None);
+
+    let mut get = |source: &Source| get_value_by_source(source, wires, instructions, tracer);
 
     let value = match &instruction.operation {
-        Operation::Set(a) => get_value_by_source(a, wires, instructions),
-        Operation::And(a, b) => {
-            let a = get_value_by_source(a, wires, instructions);
-            let b = get_value_by_source(b, wires, instructions);
-            a & b
-        }
-        Operation::Or(a, b) => {
-            let a = get_value_by_source(a, wires, instructions);
-            let b = get_value_by_source(b, wires, instructions);
-            a | b
-        }
-        Operation::Not(a) => {
-            let a = get_value_by_source(a, wires, instructions);
-            !a
-        }
+        Operation::Set(a) => get(a)?,
+        Operation::And(a, b) => get(a)? & get(b)?,
+        Operation::Or(a, b) => get(a)? | get(b)?,
+        Operation::Not(a) => !get(a)?,
         Operation::LShift(a, b) => {
-            let a = get_value_by_source(a, wires, instructions);
-            let b = get_value_by_source(b, wires, instructions);
-            a << b
+            let (a, b) = (get(a)?, get(b)?);
+            checked::shl(7, "shifting a signal left", a, b.into())?
         }
         Operation::RShift(a, b) => {
-            let a = get_value_by_source(a, wires, instructions);
-            let b = get_value_by_source(b, wires, instructions);
-            a >> b
+            let (a, b) = (get(a)?, get(b)?);
+            checked::shr(7, "shifting a signal right", a, b.into())?
         }
     };
 
-    wires.insert(id.to_string(), value);
-    value
+    if tracer.enabled() {
+        tracer.event(Event::WireResolved {
+            wire: id.to_string(),
+            value,
+        });
+    }
+
+    wires.insert(id.to_string(), Some(value));
+    Ok(value)
 }
 
 /// Dereference a source to get a concrete value. Raw sources will yield the value immediately
 /// whereas Wire source will lookup the value of the referenced input wire.
 ///
 /// - *source* - Source to dereference
-/// - *wires* - Lookup of currently known wire values by ID
+/// - *wires* - Lookup of wire values by ID, which are `None` while the wire is being resolved
 /// - *instructions* - Lookup of instructions for generating a wire value by destination ID
+/// - *tracer* - Receives an event for every wire resolved
 fn get_value_by_source(
     source: &Source,
-    wires: &mut HashMap<String, u16>,
+    wires: &mut HashMap<String, Option<u16>>,
     instructions: &HashMap<&str, &Instruction>,
-) -> u16 {
+    tracer: &mut dyn Tracer,
+) -> Result<u16, Error> {
     match source {
-        Source::Wire(id) => get_value_by_wire(id, wires, instructions),
-        Source::Raw(value) => *value,
+        Source::Wire(id) => get_value_by_wire(id, wires, instructions, tracer),
+        Source::Raw(value) => Ok(*value),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
+    use super::{generator, part1, part2, solve, trace, Params};
+    use crate::{
+        error::Error,
+        random,
+        trace::{Collector, Event},
+        Part,
+    };
 
     static INPUT: &str = include_str!("../input/2015/day7.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 16076);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(16076));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 2797);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(2797));
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = generator("123 -> x\n456 -> y\nx AND y -> a\n1 -> b").unwrap();
+        let mut collector = Collector::new();
+
+        assert_eq!(trace(&input, Part::One, &mut collector), Ok(72));
+        assert_eq!(
+            collector.events,
+            vec![
+                Event::WireResolved {
+                    wire: "x".to_string(),
+                    value: 123
+                },
+                Event::WireResolved {
+                    wire: "y".to_string(),
+                    value: 456
+                },
+                Event::WireResolved {
+                    wire: "a".to_string(),
+                    value: 72
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let input = generator("123 -> x\n456 -> y\nx AND y -> d\nx LSHIFT 2 -> f").unwrap();
+        let params = Params {
+            wire: "f".to_string(),
+            override_wire: "x".to_string(),
+        };
+
+        assert_eq!(solve(&input, Part::One, &params), Ok(492));
+        assert_eq!(solve(&input, Part::Two, &params), Ok(1968));
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        assert_eq!(
+            part1(&generator("x AND y -> a\n1 -> x").unwrap()),
+            Err(Error::unsolvable(7, "Wire y has no signal"))
+        );
+        assert_eq!(
+            part1(&generator("b -> a\nNOT a -> c\nc OR 1 -> b").unwrap()),
+            Err(Error::unsolvable(7, "Wire a depends on its own signal"))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        assert_eq!(
+            part1(&generator("1 LSHIFT 16 -> a").unwrap()),
+            Err(Error::Overflow {
+                day: 7,
+                operation: "shifting a signal left"
+            })
+        );
+        assert_eq!(part1(&generator("1 LSHIFT 15 -> a").unwrap()), Ok(32768));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let text =
+            "123 -> x\nNOT x -> h\nx AND y -> d\n1 OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g";
+        let input = generator(text).unwrap();
+        let printed = input.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(printed.join("\n"), text);
+
+        for text in &[INPUT.to_string(), random::generate(7, 50, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            let printed = input.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(generator(&printed.join("\n")).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("123 -> x\nx XOR y -> z").err(),
+            Some(Error::parse(
+                7,
+                2,
+                3,
+                "x XOR y -> z",
+                "Unsupported gate: XOR"
+            ))
+        );
+        assert_eq!(
+            generator("123 -> X").err(),
+            Some(Error::parse(
+                7,
+                1,
+                8,
+                "123 -> X",
+                "Expected a destination wire made of lowercase letters"
+            ))
+        );
+        assert_eq!(
+            generator("123 x").err(),
+            Some(Error::parse(
+                7,
+                1,
+                4,
+                "123 x",
+                "Expected an instruction like 'x AND y -> z'"
+            ))
+        );
     }
 }
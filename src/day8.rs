@@ -1,4 +1,10 @@
-use aoc_runner_derive::aoc;
+//! Day 8: Matchsticks
+
+use crate::{
+    error::{lines, Error},
+    parse::{self, Parsed},
+};
+use aoc_runner_derive::{aoc, aoc_generator};
 
 // let's learn some nom!
 use nom::{
@@ -6,37 +12,42 @@ use nom::{
     character::complete::{char, none_of, one_of},
     multi::many0,
     sequence::{delimited, preceded, tuple},
-    IResult,
 };
 
+/// Character in a string literal, as it's written in the code
 #[derive(Debug, PartialEq)]
-enum Character {
+pub enum Character {
+    /// Unescaped character
     Raw(char),
+
+    /// `\\` or `\"`, holding the escaped character
     SimpleEscape(char),
+
+    /// `\x` followed by two hexadecimal digits
     HexEscape(char, char),
 }
 
-fn unescape(input: &str) -> IResult<&str, Vec<Character>> {
+fn unescape(input: &str) -> Parsed<'_, Vec<Character>> {
     delimited(char('"'), many0(parse_character), char('"'))(input)
 }
 
-fn parse_character(input: &str) -> IResult<&str, Character> {
+fn parse_character(input: &str) -> Parsed<'_, Character> {
     alt((is_raw, is_simple_escape, is_hex_escape))(input)
 }
 
-fn is_raw(input: &str) -> IResult<&str, Character> {
+fn is_raw(input: &str) -> Parsed<'_, Character> {
     let (input, c) = regular_char(input)?;
 
     Ok((input, Character::Raw(c)))
 }
 
-fn is_simple_escape(input: &str) -> IResult<&str, Character> {
+fn is_simple_escape(input: &str) -> Parsed<'_, Character> {
     let (input, c) = preceded(char('\\'), one_of("\"\\"))(input)?;
 
     Ok((input, Character::SimpleEscape(c)))
 }
 
-fn is_hex_escape(input: &str) -> IResult<&str, Character> {
+fn is_hex_escape(input: &str) -> Parsed<'_, Character> {
     let escape = tuple((char('\\'), char('x')));
     let digits = tuple((regular_char, regular_char));
 
@@ -45,28 +56,46 @@ fn is_hex_escape(input: &str) -> IResult<&str, Character> {
     Ok((input, Character::HexEscape(first, second)))
 }
 
-fn regular_char(input: &str) -> IResult<&str, char> {
+fn regular_char(input: &str) -> Parsed<'_, char> {
     none_of("\"\\")(input)
 }
 
+impl Character {
+    /// Number of characters used to represent this character in the escaped string literal
+    fn escaped_len(&self) -> usize {
+        match self {
+            Character::Raw(_) => 1,
+            Character::SimpleEscape(_) => 2,
+            Character::HexEscape(_, _) => 4,
+        }
+    }
+}
+
+/// Parse each line of the input as an escaped string literal
+#[aoc_generator(day8)]
+pub fn generator(input: &str) -> Result<Vec<Vec<Character>>, Error> {
+    lines(8, input)
+        .map(|line| parse::line(&line, unescape, "Expected a quoted string literal"))
+        .collect()
+}
+
+/// Characters of code minus characters in memory, summed over every string
 #[aoc(day8, part1)]
-pub fn part1(input: &str) -> usize {
-    let total: usize = input.lines().map(|l| l.trim().chars().count()).sum();
-    let unescaped: usize = input
-        .lines()
-        .map(|l| unescape(l.trim()).unwrap().1.len())
-        .sum();
+pub fn part1(input: &[Vec<Character>]) -> usize {
+    let total = literal_len(input);
+    let unescaped: usize = input.iter().map(|l| l.len()).sum();
 
     total - unescaped
 }
 
+/// Characters of code after escaping each string again, minus the original characters of code
 #[aoc(day8, part2)]
-pub fn part2(input: &str) -> usize {
-    let total: usize = input.lines().map(|l| l.trim().chars().count()).sum();
+pub fn part2(input: &[Vec<Character>]) -> usize {
+    let total = literal_len(input);
 
     let double_escaped: usize = input
-        .lines()
-        .flat_map(|l| unescape(l.trim()).unwrap().1)
+        .iter()
+        .flatten()
         .map(|c| match c {
             Character::Raw(_) => 1,
             Character::SimpleEscape(_) => 4, // transform \" -> \\\"
@@ -74,21 +103,30 @@ pub fn part2(input: &str) -> usize {
         })
         .sum();
 
-    let quotes = input.lines().count() * 2 * 3; // each line starts and ends with the 3 chars "\"
+    let quotes = input.len() * 2 * 3; // each line starts and ends with the 3 chars "\"
 
     quotes + double_escaped - total
 }
 
+/// Total number of characters in the string literals, including the surrounding quotes
+fn literal_len(input: &[Vec<Character>]) -> usize {
+    input
+        .iter()
+        .map(|l| l.iter().map(Character::escaped_len).sum::<usize>() + 2)
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
     use test_case::test_case;
 
     static INPUT: &str = include_str!("../input/2015/day8.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 1333);
+        assert_eq!(part1(&generator(INPUT).unwrap()), 1333);
     }
 
     #[test_case(r#""""# => 4; "empty string")]
@@ -102,7 +140,31 @@ mod tests {
     #[test_case(INPUT => 2046; "real input")]
 
     fn test_part2(input: &str) -> usize {
-        part2(input)
+        part2(&generator(input).unwrap())
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(
+            generator("\"abc\"\n\"abc").err(),
+            Some(Error::parse(
+                8,
+                2,
+                5,
+                "\"abc",
+                "Expected a quoted string literal"
+            ))
+        );
+        assert_eq!(
+            generator("\"abc\"def").err(),
+            Some(Error::parse(
+                8,
+                1,
+                6,
+                "\"abc\"def",
+                "Unexpected \"def\" at the end of the line"
+            ))
+        );
     }
 
     #[test]
@@ -1,59 +1,156 @@
+//! Day 9: All in a Single Night
+
+use crate::{
+    checked,
+    error::{lines, Error},
+    parse::{self, Parsed},
+    progress::{self, Progress},
+    Part,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::{alpha1, digit1},
     sequence::{preceded, separated_pair},
-    IResult,
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
 
+/// Route between two locations, which can be travelled in either direction
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Vertex {
-    source: String,
-    dest: String,
-    weight: usize,
+    /// Location at one end of the route
+    pub source: String,
+
+    /// Location at the other end of the route
+    pub dest: String,
+
+    /// Distance between the two locations
+    pub weight: usize,
+}
+
+impl fmt::Display for Vertex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {} = {}", self.source, self.dest, self.weight)
+    }
 }
 
+/// Parse each line as a route like `London to Dublin = 464`
 #[aoc_generator(day9)]
-pub fn generator(input: &str) -> Vec<Vertex> {
-    input
-        .lines()
-        .map(|line| parse_vertex(line.trim()).unwrap().1)
-        .collect::<Vec<_>>()
+pub fn generator(input: &str) -> Result<Vec<Vertex>, Error> {
+    lines(9, input)
+        .map(|line| {
+            parse::line(
+                &line,
+                parse_vertex,
+                "Expected a route like 'London to Dublin = 464'",
+            )
+        })
+        .collect()
 }
 
-fn parse_vertex(s: &str) -> IResult<&str, Vertex> {
-    let (s, (source, dest)) = separated_pair(alpha1, tag(" to "), alpha1)(s)?;
-    let (s, weight) = preceded(tag(" = "), digit1)(s)?;
+fn parse_vertex(s: &str) -> Parsed<'_, Vertex> {
+    let (s, (source, dest)) = separated_pair(parse::name, tag(" to "), parse::name)(s)?;
+    let (s, weight) = preceded(tag(" = "), parse::integer)(s)?;
 
     Ok((
         s,
         Vertex {
             source: source.to_string(),
             dest: dest.to_string(),
-            weight: weight.parse().unwrap(), // safe because nom ensured it was only digits
+            weight,
         },
     ))
 }
 
 /// What if we just brute force it instead of implenting a proper TSP algorithm?
 #[aoc(day9, part1)]
-pub fn part1(input: &[Vertex]) -> usize {
-    search(input).0
+pub fn part1(input: &[Vertex]) -> Result<usize, Error> {
+    run(input, Part::One, &Progress::new())
 }
 
 /// I can't believe that worked... The input is very kind because not every city goes to every other
 /// Saves implementing Held-Karp though!
 #[aoc(day9, part2)]
-pub fn part2(input: &[Vertex]) -> usize {
-    search(input).1
+pub fn part2(input: &[Vertex]) -> Result<usize, Error> {
+    run(input, Part::Two, &Progress::new())
 }
 
-/// Find the min and max path between the given vertices
-fn search(input: &[Vertex]) -> (usize, usize) {
+/// Solve either part, reporting each route tried
+pub fn run(input: &[Vertex], part: Part, progress: &Progress) -> Result<usize, Error> {
+    let (min, max) = search(input, progress)?;
+
+    match part {
+        Part::One => Ok(min),
+        Part::Two => Ok(max),
+    }
+}
+
+/// Distance used for a pair of locations without a route between them
+const NO_ROUTE: usize = 99999;
+
+/// Shortest or longest route, found by trying every ordering of the locations
+pub fn permutations(input: &[Vertex], part: Part) -> Result<usize, Error> {
+    run(input, part, &Progress::new())
+}
+
+/// Shortest or longest route, found with the Held-Karp dynamic programming algorithm. This finds
+/// the best route ending at each location for each subset of locations, which takes `O(2^n n^2)`
+/// time rather than `O(n!)`.
+pub fn held_karp(input: &[Vertex], part: Part) -> Result<usize, Error> {
+    let (cities, edges) = graph(input);
+    let n = cities.len();
+
+    if n == 0 {
+        return Ok(0);
+    }
+
+    let distance = |a: usize, b: usize| *edges.get(&(cities[a], cities[b])).unwrap_or(&NO_ROUTE);
+    let better = |a: usize, b: usize| match part {
+        Part::One => a.min(b),
+        Part::Two => a.max(b),
+    };
+
+    // best[visited][last] is the best distance visiting the set of cities ending at last
+    let mut best = vec![vec![None; n]; 1 << n];
+
+    for city in 0..n {
+        best[1 << city][city] = Some(0);
+    }
+
+    for visited in 1..1usize << n {
+        for last in 0..n {
+            let Some(so_far) = best[visited][last] else {
+                continue;
+            };
+
+            for next in (0..n).filter(|next| visited & (1 << next) == 0) {
+                let distance =
+                    checked::add(9, "adding up distances", so_far, distance(last, next))?;
+                let entry = &mut best[visited | (1 << next)][next];
+                *entry = Some(entry.map_or(distance, |d| better(d, distance)));
+            }
+        }
+    }
+
+    Ok(best[(1 << n) - 1]
+        .iter()
+        .flatten()
+        .copied()
+        .reduce(better)
+        .unwrap_or_default())
+}
+
+type Edges<'a> = HashMap<(&'a str, &'a str), usize>;
+
+/// Find every location, sorted so that they are always visited in the same order, and the
+/// distance between each pair in both directions
+fn graph(input: &[Vertex]) -> (Vec<&str>, Edges<'_>) {
     let sources = input.iter().map(|v| v.source.as_ref());
     let dests = input.iter().map(|v| v.dest.as_ref());
-    let cities = sources.chain(dests).collect::<HashSet<&str>>();
+    let cities = sources.chain(dests).collect::<BTreeSet<&str>>();
 
     let forward = input
         .iter()
@@ -61,19 +158,29 @@ fn search(input: &[Vertex]) -> (usize, usize) {
     let backward = input
         .iter()
         .map(|v| ((v.dest.as_ref(), v.source.as_ref()), v.weight));
-    let edges = forward
-        .chain(backward)
-        .collect::<HashMap<(&str, &str), usize>>();
+    let edges = forward.chain(backward).collect::<Edges>();
+
+    (cities.into_iter().collect(), edges)
+}
+
+/// Find the min and max path between the given vertices
+fn search(input: &[Vertex], progress: &Progress) -> Result<(usize, usize), Error> {
+    let (cities, edges) = graph(input);
 
     let mut min_weight = usize::MAX;
     let mut max_weight = usize::MIN;
 
+    progress.set_total(progress::factorial(cities.len()));
+
     for p in cities.iter().permutations(cities.len()) {
+        progress.tick()?;
+
         let mut weight = 0usize;
 
         for (&&src, &&dest) in p.iter().zip(p.iter().skip(1)) {
             let key = (src, dest);
-            weight += edges.get(&key).unwrap_or(&99999usize);
+            let distance = *edges.get(&key).unwrap_or(&NO_ROUTE);
+            weight = checked::add(9, "adding up distances", weight, distance)?;
         }
 
         if weight < min_weight {
@@ -85,24 +192,89 @@ fn search(input: &[Vertex]) -> (usize, usize) {
         }
     }
 
-    (min_weight, max_weight)
+    Ok((min_weight, max_weight))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
+    use super::{generator, held_karp, part1, part2, permutations};
+    use crate::{error::Error, random, Part};
 
     static INPUT: &str = include_str!("../input/2015/day9.txt");
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 117);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(117));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 909);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(909));
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let mut inputs = vec![
+            INPUT.to_string(),
+            "London to Dublin = 464".to_string(),
+            "London to Dublin = 464\nLondon to Belfast = 518".to_string(),
+        ];
+        inputs.extend((0..20).map(|seed| random::generate(9, 1 + seed % 8, seed as u64).unwrap()));
+
+        for text in &inputs {
+            let input = generator(text).unwrap();
+
+            for part in [Part::One, Part::Two] {
+                assert_eq!(
+                    held_karp(&input, part),
+                    permutations(&input, part),
+                    "{:?}",
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    fn test_overflow() {
+        let input = generator("A to B = 18446744073709551615\nB to C = 5").unwrap();
+        let overflow = Error::Overflow {
+            day: 9,
+            operation: "adding up distances",
+        };
+
+        for part in [Part::One, Part::Two] {
+            assert_eq!(permutations(&input, part), Err(overflow.clone()));
+            assert_eq!(held_karp(&input, part), Err(overflow.clone()));
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let input = generator("London to Dublin = 464").unwrap();
+        assert_eq!(input[0].to_string(), "London to Dublin = 464");
+
+        for text in &[INPUT.to_string(), random::generate(9, 6, 1).unwrap()] {
+            let input = generator(text).unwrap();
+            let printed = input.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(generator(&printed.join("\n")).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_generator_error() {
+        assert_eq!(
+            generator("London to Dublin = far").err(),
+            Some(Error::parse(
+                9,
+                1,
+                20,
+                "London to Dublin = far",
+                "Expected a route like 'London to Dublin = 464'"
+            ))
+        );
     }
 }
@@ -0,0 +1,291 @@
+//! Errors reported while parsing puzzle input or solving a puzzle
+
+use crate::{input, progress::Cancelled};
+use std::fmt;
+
+/// Errors produced while parsing puzzle input or solving a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The puzzle input was malformed
+    Parse {
+        /// Day of the puzzle whose input was being parsed
+        day: u8,
+
+        /// Line number of the offending line, starting at 1
+        line: usize,
+
+        /// Column number at which parsing failed, starting at 1
+        column: usize,
+
+        /// The offending line
+        text: String,
+
+        /// Description of the problem
+        message: String,
+    },
+
+    /// Solving the puzzle overflowed an integer, which is only detected with the `checked` feature
+    Overflow {
+        /// Day of the puzzle being solved
+        day: u8,
+
+        /// Description of the operation which overflowed
+        operation: &'static str,
+    },
+
+    /// The puzzle has no answer for this input, e.g. because it refers to something which doesn't
+    /// exist
+    Unsolvable {
+        /// Day of the puzzle being solved
+        day: u8,
+
+        /// Description of the problem
+        message: String,
+    },
+
+    /// The solver was [cancelled](crate::progress::Progress::cancel) or ran out of time before it
+    /// found an answer
+    Cancelled,
+}
+
+impl Error {
+    /// Create a parse error at the given position
+    pub fn parse(
+        day: u8,
+        line: usize,
+        column: usize,
+        text: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Error::Parse {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Create an error for input which has no answer
+    pub fn unsolvable(day: u8, message: impl Into<String>) -> Self {
+        Error::Unsolvable {
+            day,
+            message: message.into(),
+        }
+    }
+
+    /// Create a parse error for input which ended before everything expected had been parsed
+    pub fn end_of_input(day: u8, input: &str, message: impl Into<String>) -> Self {
+        Error::parse(day, input.lines().count() + 1, 1, "", message)
+    }
+}
+
+/// The alternate form, `{:#}`, shows the offending line with a caret under the column where
+/// parsing failed instead of quoting the line
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                message,
+            } => {
+                write!(
+                    f,
+                    "Day {} input, line {}, column {}: {}",
+                    day, line, column, message
+                )?;
+
+                if !f.alternate() {
+                    return write!(f, " (in {:?})", text);
+                }
+
+                // Keep tabs so that the caret lines up however wide they are displayed
+                let gutter = " ".repeat(line.to_string().len());
+                let indent = text
+                    .get(..column.saturating_sub(1))
+                    .unwrap_or_default()
+                    .chars()
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect::<String>();
+
+                write!(
+                    f,
+                    "\n{} |\n{} | {}\n{} | {}^",
+                    gutter, line, text, gutter, indent
+                )
+            }
+            Error::Overflow { day, operation } => {
+                write!(f, "Day {}: Overflow while {}", day, operation)
+            }
+            Error::Unsolvable { day, message } => write!(f, "Day {}: {}", day, message),
+            Error::Cancelled => Cancelled.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Cancelled> for Error {
+    fn from(_: Cancelled) -> Self {
+        Error::Cancelled
+    }
+}
+
+/// A single line of puzzle input, used to report the position of parse errors
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Line number, starting at 1
+    pub number: usize,
+
+    /// Line content, without the line ending
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Create a parse error at the start of `fragment`, which should be a slice of this line. Any
+    /// other fragment is reported at the start of the line.
+    pub fn error(&self, fragment: &str, message: impl Into<String>) -> Error {
+        let offset = (fragment.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+        let column = if offset.saturating_add(fragment.len()) <= self.text.len() {
+            offset + 1
+        } else {
+            1
+        };
+
+        Error::parse(self.day, self.number, column, self.text, message)
+    }
+
+    /// Create a parse error from a nom failure, positioned where nom stopped parsing
+    #[cfg(feature = "nom")]
+    pub fn nom_error(
+        &self,
+        e: nom::Err<nom::error::Error<&str>>,
+        message: impl Into<String>,
+    ) -> Error {
+        match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => self.error(e.input, message),
+            nom::Err::Incomplete(_) => self.error(&self.text[self.text.len()..], message),
+        }
+    }
+}
+
+/// Split the input into numbered lines for the given day, [normalising](input::lines) each one and
+/// skipping any blank lines
+pub fn lines(day: u8, input: &str) -> impl Iterator<Item = Line<'_>> {
+    input::lines(input)
+        .enumerate()
+        .map(move |(i, text)| Line {
+            day,
+            number: i + 1,
+            text,
+        })
+        .filter(|line| !line.text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_normalised() {
+        let lines = lines(2, "\u{feff}1x2x3\r\n\r\n4x5x6 \t\r\n\r\n")
+            .map(|line| (line.number, line.text))
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, vec![(1, "1x2x3"), (3, "4x5x6")]);
+    }
+
+    #[test]
+    fn test_line_error() {
+        let input = "1x2x3\n\n  4xfivex6\n";
+        let line = lines(2, input).nth(1).unwrap();
+        let error = line.error(&line.text[4..8], "Invalid dimension");
+
+        assert_eq!(
+            error,
+            Error::Parse {
+                day: 2,
+                line: 3,
+                column: 5,
+                text: "  4xfivex6".to_string(),
+                message: "Invalid dimension".to_string(),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            r#"Day 2 input, line 3, column 5: Invalid dimension (in "  4xfivex6")"#
+        );
+        assert_eq!(
+            format!("{:#}", error),
+            "Day 2 input, line 3, column 5: Invalid dimension\n  |\n3 |   4xfivex6\n  |     ^"
+        );
+    }
+
+    #[test]
+    fn test_line_error_unrelated_fragment() {
+        let line = lines(1, "abc").next().unwrap();
+
+        assert!(matches!(
+            line.error(&String::from("xyz"), "Oops"),
+            Error::Parse { column: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_caret_after_tabs() {
+        let error = Error::parse(6, 12, 3, "\tx?", "Oops");
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Day 6 input, line 12, column 3: Oops\n   |\n12 | \tx?\n   | \t ^"
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let error = Error::Overflow {
+            day: 23,
+            operation: "tripling a register",
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Day 23: Overflow while tripling a register"
+        );
+        assert_eq!(format!("{:#}", error), error.to_string());
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        assert_eq!(
+            Error::unsolvable(7, "Wire a has no signal").to_string(),
+            "Day 7: Wire a has no signal"
+        );
+    }
+
+    #[test]
+    fn test_cancelled() {
+        assert_eq!(Error::from(Cancelled), Error::Cancelled);
+        assert_eq!(Error::Cancelled.to_string(), "Cancelled");
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let error = Error::end_of_input(21, "Hit Points: 103\nDamage: 9\n", "Missing armor");
+
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 3,
+                column: 1,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,313 @@
+//! Worked examples for every day, read from a directory of input and answer files
+
+use crate::{
+    solver,
+    verify::{Outcome, Status},
+    Part,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Directory holding the example corpus, relative to the root of the crate
+pub const EXAMPLES_DIR: &str = "examples";
+
+/// Worked example for a single day, read from `<dir>/day<N>/<name>.txt` with its expected answers
+/// in `<dir>/day<N>/<name>.answers`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Name of the example, taken from its file name
+    pub name: String,
+
+    /// Raw puzzle input
+    pub input: String,
+
+    /// Expected answer for each part which the example covers
+    pub expected: BTreeMap<Part, String>,
+}
+
+impl Example {
+    /// Read an example and its answers file
+    pub fn read(day: u8, path: &Path) -> Result<Self, String> {
+        let read = |path: &Path| {
+            fs::read_to_string(path)
+                .map_err(|e| format!("Unable to read {}: {}", path.display(), e))
+        };
+
+        let answers = path.with_extension("answers");
+        let expected = parse_expected(&read(&answers)?)
+            .map_err(|e| format!("{}: {}", answers.display(), e))?;
+
+        Ok(Example {
+            day,
+            name: path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            input: read(path)?,
+            expected,
+        })
+    }
+
+    /// Run the example through its day's solver, checking each part it has an answer for
+    pub fn run(&self) -> Vec<Outcome> {
+        let outcome = |part, answer, status| Outcome {
+            day: self.day,
+            part,
+            answer,
+            status,
+        };
+
+        let solver = match solver::solver(self.day) {
+            Some(solver) => solver,
+            None => {
+                let e = format!("No solver for day {}", self.day);
+                return self
+                    .expected
+                    .keys()
+                    .map(|&part| outcome(part, None, Status::Error(e.clone())))
+                    .collect();
+            }
+        };
+
+        let parsed = match solver::try_parse(solver, &self.input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return self
+                    .expected
+                    .keys()
+                    .map(|&part| outcome(part, None, Status::Error(e.clone())))
+                    .collect()
+            }
+        };
+
+        self.expected
+            .iter()
+            .map(
+                |(&part, expected)| match solver::try_solve(solver, part, parsed.as_ref()) {
+                    Ok(answer) if answer.to_string() == *expected => {
+                        outcome(part, Some(answer.to_string()), Status::Pass)
+                    }
+                    Ok(answer) => outcome(
+                        part,
+                        Some(answer.to_string()),
+                        Status::Fail(expected.clone()),
+                    ),
+                    Err(e) => outcome(part, None, Status::Error(e)),
+                },
+            )
+            .collect()
+    }
+}
+
+/// Parse an answers file. Each line is `part1: <answer>` or `part2: <answer>`, blank lines and
+/// lines starting with `#` are ignored. Parts without a line aren't checked.
+pub fn parse_expected(s: &str) -> Result<BTreeMap<Part, String>, String> {
+    let mut expected = BTreeMap::new();
+
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = || format!("Invalid answer on line {}: {:?}", i + 1, line);
+
+        let (part, answer) = line.split_once(':').ok_or_else(invalid)?;
+        let part = match part.trim() {
+            "part1" => Part::One,
+            "part2" => Part::Two,
+            _ => return Err(invalid()),
+        };
+
+        if expected.insert(part, answer.trim().to_string()).is_some() {
+            return Err(format!(
+                "Duplicate answer for part {} on line {}",
+                part,
+                i + 1
+            ));
+        }
+    }
+
+    Ok(expected)
+}
+
+/// Find every example in the corpus, ordered by day and then name. Each `day<N>` directory holds
+/// any number of `<name>.txt` inputs, each of which must have a `<name>.answers` file.
+pub fn discover(dir: &Path) -> Result<Vec<Example>, String> {
+    let mut examples = Vec::new();
+
+    for day_dir in list(dir)? {
+        let day = match day_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day"))
+            .and_then(|day| day.parse::<u8>().ok())
+        {
+            Some(day) if day_dir.is_dir() => day,
+            _ => continue,
+        };
+
+        for path in list(&day_dir)? {
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                examples.push(Example::read(day, &path)?);
+            }
+        }
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+fn list(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| format!("Unable to read {}: {}", dir.display(), e))?;
+
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    // Some helpers are only used by tests of days which may not be compiled in
+    #![cfg_attr(not(feature = "all-days"), allow(unused_imports, dead_code))]
+
+    use super::*;
+    use std::{collections::BTreeSet, env};
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_corpus() {
+        let examples = discover(Path::new(EXAMPLES_DIR)).unwrap();
+
+        let failures = examples
+            .iter()
+            .flat_map(|example| {
+                example
+                    .run()
+                    .into_iter()
+                    .filter(|o| o.status != Status::Pass)
+                    .map(move |o| {
+                        format!(
+                            "day {} {} part {}: {:?}",
+                            o.day, example.name, o.part, o.status
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+
+        let days = examples.iter().map(|e| e.day).collect::<BTreeSet<_>>();
+        let missing = solver::solvers()
+            .map(|solver| solver.day())
+            .filter(|day| !days.contains(day))
+            .collect::<Vec<_>>();
+
+        assert!(missing.is_empty(), "No examples for days {:?}", missing);
+    }
+
+    /// Every day should accept its examples however an editor saved them
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_mangled_corpus() {
+        type Mangle = fn(&str) -> String;
+
+        let manglings: [(&str, Mangle); 4] = [
+            ("CRLF", |s| s.trim_end().replace('\n', "\r\n") + "\r\n"),
+            ("BOM", |s| format!("\u{feff}{}", s)),
+            ("trailing whitespace", |s| {
+                s.lines()
+                    .map(|line| format!("{} \t\n", line))
+                    .collect::<String>()
+                    + "\n \n\n"
+            }),
+            ("no final newline", |s| s.trim_end().to_string()),
+        ];
+
+        let failures = discover(Path::new(EXAMPLES_DIR))
+            .unwrap()
+            .into_iter()
+            .flat_map(|example| {
+                manglings.iter().flat_map(move |(name, mangle)| {
+                    let mangled = Example {
+                        input: mangle(&example.input),
+                        ..example.clone()
+                    };
+
+                    mangled
+                        .run()
+                        .into_iter()
+                        .filter(|o| o.status != Status::Pass)
+                        .map(|o| {
+                            format!(
+                                "day {} {} with {}: {:?}",
+                                o.day, example.name, name, o.status
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_parse_expected() {
+        let expected = parse_expected("# comment\npart1: 12\n\npart2:  abc \n").unwrap();
+
+        assert_eq!(expected.get(&Part::One).map(String::as_str), Some("12"));
+        assert_eq!(expected.get(&Part::Two).map(String::as_str), Some("abc"));
+        assert_eq!(
+            parse_expected("part3: 1"),
+            Err("Invalid answer on line 1: \"part3: 1\"".to_string())
+        );
+        assert_eq!(
+            parse_expected("part1: 1\npart1: 2"),
+            Err("Duplicate answer for part 1 on line 2".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day2")]
+    fn test_run() {
+        let dir = env::temp_dir().join("advent-2015-examples");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("day2")).unwrap();
+        fs::create_dir_all(dir.join("notes")).unwrap();
+
+        fs::write(dir.join("day2/box.txt"), "2x3x4").unwrap();
+        fs::write(dir.join("day2/box.answers"), "part1: 58\npart2: 1").unwrap();
+        fs::write(dir.join("day2/bad.txt"), "2x3").unwrap();
+        fs::write(dir.join("day2/bad.answers"), "part1: 0").unwrap();
+
+        let examples = discover(&dir).unwrap();
+        let names = examples.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["bad", "box"]);
+
+        let statuses = examples[1]
+            .run()
+            .into_iter()
+            .map(|o| o.status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, vec![Status::Pass, Status::Fail("1".to_string())]);
+
+        assert!(matches!(examples[0].run()[0].status, Status::Error(_)));
+
+        fs::write(dir.join("day2/orphan.txt"), "1x1x1").unwrap();
+        assert!(discover(&dir).unwrap_err().contains("orphan.answers"));
+    }
+}
@@ -0,0 +1,186 @@
+//! C-compatible functions for calling the solvers from other languages, exported by the `cdylib`
+//! build of the crate and declared for C and C++ in `include/advent_2015.h`
+
+use crate::{params::Params, progress::Progress, solver, Part};
+use std::{ffi::CString, os::raw::c_char, slice, str};
+
+/// Outcome of [advent_solve]. Every status except `Ok` comes with a message describing the problem.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdventStatus {
+    /// The part was solved and the answer written
+    Ok = 0,
+
+    /// A pointer was null, the part wasn't 1 or 2, or the input wasn't UTF-8
+    InvalidArgument = 1,
+
+    /// The day has no solver, either because it wasn't compiled in or because it has no such part
+    NoSolver = 2,
+
+    /// The input couldn't be parsed
+    ParseError = 3,
+
+    /// The solver failed, e.g. by overflowing
+    SolveError = 4,
+}
+
+/// Solve one part of a day, writing the answer, or a message if it couldn't be solved, to
+/// `*answer` as a NUL-terminated string which must be released with [advent_free]
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, or may be null if `len` is 0. `answer` must be null
+/// or point to writable memory for a pointer. Nothing is written if it is null.
+#[no_mangle]
+pub unsafe extern "C" fn advent_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    len: usize,
+    answer: *mut *mut c_char,
+) -> AdventStatus {
+    if answer.is_null() {
+        return AdventStatus::InvalidArgument;
+    }
+
+    let (status, message) = solve(day, part, input, len);
+
+    // C strings end at the first NUL, so escape any which made it into the message
+    let message = CString::new(message.replace('\0', "\\0")).unwrap_or_default();
+    *answer = message.into_raw();
+
+    status
+}
+
+/// Release a string written by [advent_solve]. Does nothing if `s` is null.
+///
+/// # Safety
+///
+/// `s` must be null or a string written by [advent_solve] which hasn't already been released.
+#[no_mangle]
+pub unsafe extern "C" fn advent_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Solve the part, returning the status and the answer or message
+unsafe fn solve(day: u8, part: u8, input: *const u8, len: usize) -> (AdventStatus, String) {
+    let input = match (input.is_null(), len) {
+        (true, 0) => &[],
+        (true, _) => return (AdventStatus::InvalidArgument, "Input is null".to_string()),
+        (false, _) => slice::from_raw_parts(input, len),
+    };
+    let input = match str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => {
+            return (
+                AdventStatus::InvalidArgument,
+                "Input must be UTF-8".to_string(),
+            )
+        }
+    };
+
+    let part = match part {
+        1 => Part::One,
+        2 => Part::Two,
+        _ => {
+            return (
+                AdventStatus::InvalidArgument,
+                format!("Invalid part: {}", part),
+            )
+        }
+    };
+
+    let solver = match solver::solver(day) {
+        Some(solver) if solver.has_part(part) => solver,
+        Some(_) => {
+            return (
+                AdventStatus::NoSolver,
+                format!("Day {} has no part {}", day, part),
+            )
+        }
+        None => return (AdventStatus::NoSolver, format!("No solver for day {}", day)),
+    };
+
+    let parsed = match solver::try_parse(solver, input) {
+        Ok(parsed) => parsed,
+        Err(e) => return (AdventStatus::ParseError, e),
+    };
+
+    match solver::try_run_with(
+        solver,
+        part,
+        parsed.as_ref(),
+        &Params::default(),
+        &Progress::new(),
+    ) {
+        Ok(answer) => (AdventStatus::Ok, answer.to_string()),
+        Err(e) => (AdventStatus::SolveError, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{ffi::CStr, ptr};
+
+    fn call(day: u8, part: u8, input: &str) -> (AdventStatus, String) {
+        let mut answer = ptr::null_mut();
+
+        unsafe {
+            let status = advent_solve(day, part, input.as_ptr(), input.len(), &mut answer);
+            let text = CStr::from_ptr(answer).to_string_lossy().into_owned();
+            advent_free(answer);
+
+            (status, text)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_solve() {
+        assert_eq!(call(1, 2, "()())"), (AdventStatus::Ok, "5".to_string()));
+        assert_eq!(
+            call(1, 3, "()())"),
+            (AdventStatus::InvalidArgument, "Invalid part: 3".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day2")]
+    fn test_parse_error() {
+        assert_eq!(
+            call(2, 1, "1x2"),
+            (
+                AdventStatus::ParseError,
+                "Day 2 input, line 1, column 4: Expected dimensions in the form LxWxH (in \"1x2\")"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut answer = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(
+                advent_solve(1, 1, ptr::null(), 3, &mut answer),
+                AdventStatus::InvalidArgument
+            );
+            advent_free(answer);
+
+            assert_eq!(
+                advent_solve(1, 1, b"(".as_ptr(), 1, ptr::null_mut()),
+                AdventStatus::InvalidArgument
+            );
+            advent_free(ptr::null_mut());
+        }
+
+        assert_eq!(
+            call(0, 1, ""),
+            (AdventStatus::NoSolver, "No solver for day 0".to_string())
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Entry points for fuzzing the input parsers
+
+use crate::{input, random::Rng};
+
+/// Fuzz entry point: parse arbitrary bytes with the given day's generator, which is called
+/// directly rather than through its [Solver](crate::solver::Solver). Returning at all means the
+/// parser coped with the input, whether or not it was valid, so any panic is a bug. Invalid UTF-8
+/// is replaced rather than rejected so that the parser still sees the rest of the input. Days which
+/// solve the raw input only normalise it.
+pub fn parse(day: u8, data: &[u8]) {
+    let input = &*String::from_utf8_lossy(data);
+
+    match day {
+        #[cfg(feature = "day1")]
+        1 => drop(crate::day1::generator(input)),
+        #[cfg(feature = "day2")]
+        2 => drop(crate::day2::generator(input)),
+        #[cfg(feature = "day3")]
+        3 => drop(crate::day3::generator(input)),
+        #[cfg(feature = "day6")]
+        6 => drop(crate::day6::generator(input)),
+        #[cfg(feature = "day7")]
+        7 => drop(crate::day7::generator(input)),
+        #[cfg(feature = "day8")]
+        8 => drop(crate::day8::generator(input)),
+        #[cfg(feature = "day9")]
+        9 => drop(crate::day9::generator(input)),
+        #[cfg(feature = "day10")]
+        10 => drop(crate::day10::generator(input)),
+        #[cfg(feature = "day11")]
+        11 => drop(crate::day11::generator(input)),
+        #[cfg(feature = "day12")]
+        12 => drop(crate::day12::generator(input)),
+        #[cfg(feature = "day13")]
+        13 => drop(crate::day13::generator(input)),
+        #[cfg(feature = "day14")]
+        14 => drop(crate::day14::generator(input)),
+        #[cfg(feature = "day15")]
+        15 => drop(crate::day15::generator(input)),
+        #[cfg(feature = "day16")]
+        16 => drop(crate::day16::generator(input)),
+        #[cfg(feature = "day17")]
+        17 => drop(crate::day17::generator(input)),
+        #[cfg(feature = "day18")]
+        18 => drop(crate::day18::generator(input)),
+        #[cfg(feature = "day19")]
+        19 => drop(crate::day19::generator(input)),
+        #[cfg(feature = "day20")]
+        20 => drop(crate::day20::generator(input)),
+        #[cfg(feature = "day21")]
+        21 => drop(crate::day21::generator(input)),
+        #[cfg(feature = "day22")]
+        22 => drop(crate::day22::generator(input)),
+        #[cfg(feature = "day23")]
+        23 => drop(crate::day23::generator(input)),
+        #[cfg(feature = "day24")]
+        24 => drop(crate::day24::generator(input)),
+        #[cfg(feature = "day25")]
+        25 => drop(crate::day25::generator(input)),
+        4 | 5 => drop(input::normalise(input)),
+        _ => {}
+    }
+}
+
+/// Characters which are significant to at least one day's parser, used to mutate valid inputs into
+/// nearly valid ones
+const INTERESTING: &[char] = &[
+    ' ', '\n', '\r', ',', ':', '-', '+', '=', '>', '"', '\\', 'x', '[', ']', '{', '}', '(', ')',
+    '0', '1', '9', 'a', 'z', 'A', 'Z', '#', '.', 'é',
+];
+
+/// Apply a handful of random edits to an input, such as deleting, duplicating or replacing
+/// characters, or truncating it
+pub fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut chars = input.chars().collect::<Vec<_>>();
+
+    for _ in 0..rng.range(1..=4) {
+        let len = chars.len();
+        let at = rng.range(0..=len);
+
+        match rng.range(0..=5) {
+            0 if at < len => {
+                chars.remove(at);
+            }
+            1 if at < len => chars[at] = *rng.choose(INTERESTING),
+            2 => chars.insert(at, *rng.choose(INTERESTING)),
+            3 => chars.truncate(at),
+            4 if at < len => {
+                let end = rng.range(at..=len);
+                let copy = chars[at..end].to_vec();
+                chars.splice(at..at, copy);
+            }
+            _ => {
+                let digits = rng.next_u64().to_string();
+                chars.splice(at..at, digits.chars());
+            }
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random;
+    use std::{fs, panic};
+
+    /// Run the entry point on mutations of each day's real and generated inputs, collecting the
+    /// inputs which panic. This is much less thorough than a real fuzzer, but catches regressions.
+    #[test]
+    fn test_mutations() {
+        let mut rng = Rng::new(2015);
+        let mut panics = Vec::new();
+
+        for day in 1..=25 {
+            let mut seeds = vec![fs::read_to_string(format!("input/2015/day{}.txt", day)).unwrap()];
+            seeds.extend(random::generate(day, 20, day as u64));
+
+            for seed in &seeds {
+                for _ in 0..200 {
+                    let input = mutate(&mut rng, seed);
+
+                    if panic::catch_unwind(|| parse(day, input.as_bytes())).is_err() {
+                        panics.push(format!("day {}: {:?}", day, input));
+                    }
+                }
+            }
+        }
+
+        assert!(panics.is_empty(), "{}", panics.join("\n"));
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        for day in 1..=25 {
+            for input in ["", "\n", " ", "\r\n", "-", "0", "\u{feff}", "\0"] {
+                parse(day, input.as_bytes());
+            }
+
+            parse(day, &[0xff, 0xfe, b'\n']);
+        }
+    }
+}
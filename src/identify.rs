@@ -0,0 +1,208 @@
+//! Working out which day a puzzle input belongs to
+
+use crate::{input, solver};
+use std::fmt;
+
+/// A day which an input might belong to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Share of the total score of every candidate day, between 0 and 1
+    pub confidence: f64,
+}
+
+impl fmt::Display for Guess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} ({:.0}%)", self.day, self.confidence * 100.0)
+    }
+}
+
+/// Work out which day an input most likely belongs to
+pub fn identify(input: &str) -> Option<Guess> {
+    candidates(input).into_iter().next()
+}
+
+/// Score the input against every day, returning the days whose parser accepts it and whose
+/// input looks like it, most likely first
+pub fn candidates(input: &str) -> Vec<Guess> {
+    let input = &*input::normalise(input);
+    let scores = solver::solvers()
+        .map(|solver| (solver.day(), score(solver.day(), input)))
+        .filter(|&(_, score)| score > 0.0)
+        .filter(|&(day, _)| {
+            let solver = solver::solver(day).unwrap();
+            solver::try_parse(solver, input).is_ok()
+        })
+        .collect::<Vec<_>>();
+
+    let total = scores.iter().map(|&(_, score)| score).sum::<f64>();
+
+    let mut guesses = scores
+        .into_iter()
+        .map(|(day, score)| Guess {
+            day,
+            confidence: score / total,
+        })
+        .collect::<Vec<_>>();
+
+    guesses.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(a.day.cmp(&b.day))
+    });
+    guesses
+}
+
+/// How much the input looks like the given day's input, between 0 and 1, based on the syntax
+/// which is distinctive to that day
+fn score(day: u8, input: &str) -> f64 {
+    let lines = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    let single = match lines[..] {
+        [line] => Some(line),
+        _ => None,
+    };
+
+    // share of lines matching a predicate
+    let share = |f: &dyn Fn(&str) -> bool| {
+        if lines.is_empty() {
+            0.0
+        } else {
+            lines.iter().filter(|line| f(line)).count() as f64 / lines.len() as f64
+        }
+    };
+
+    let only = |s: &str, allowed: &dyn Fn(char) -> bool| !s.is_empty() && s.chars().all(allowed);
+    let lowercase = |s: &str| only(s, &|c| c.is_ascii_lowercase());
+
+    let numbers = lines
+        .iter()
+        .map(|line| line.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()
+        .filter(|numbers| numbers.len() > 1);
+
+    // distinct ascending weights which split into both 3 and 4 groups look like day 24
+    let weights = numbers.as_ref().is_some_and(|numbers| {
+        numbers.windows(2).all(|w| w[0] < w[1]) && numbers.iter().sum::<u64>().is_multiple_of(12)
+    });
+
+    let yes = |b: bool| if b { 1.0 } else { 0.0 };
+
+    match day {
+        1 => yes(single.is_some_and(|s| only(s, &|c| c == '(' || c == ')'))),
+        2 => share(&|line| {
+            let parts = line.split('x').collect::<Vec<_>>();
+            parts.len() == 3 && parts.iter().all(|p| p.parse::<u32>().is_ok())
+        }),
+        3 => yes(single.is_some_and(|s| only(s, &|c| "^v<>".contains(c)))),
+        4 => yes(single.is_some_and(|s| lowercase(s) && s.len() < 16)),
+        5 => yes(lines.len() > 1) * share(&|line| lowercase(line) && line.len() == 16),
+        6 => share(&|line| {
+            ["turn on ", "turn off ", "toggle "]
+                .iter()
+                .any(|op| line.starts_with(op))
+                && line.contains(" through ")
+        }),
+        7 => share(&|line| line.contains(" -> ")),
+        8 => share(&|line| line.len() >= 2 && line.starts_with('"') && line.ends_with('"')),
+        9 => share(&|line| line.contains(" to ") && line.contains(" = ")),
+        10 => match single {
+            Some(s) if only(s, &|c| ('1'..='3').contains(&c)) => 1.0,
+            Some(s) if only(s, &|c| c.is_ascii_digit()) => 0.5,
+            _ => 0.0,
+        },
+        11 => yes(single.is_some_and(|s| lowercase(s) && s.len() == 8)),
+        12 => yes(single.is_some_and(|s| s.starts_with('[') || s.starts_with('{'))),
+        13 => share(&|line| {
+            (line.contains(" would gain ") || line.contains(" would lose "))
+                && line.contains("happiness units")
+        }),
+        14 => share(&|line| line.contains(" can fly ") && line.contains(" km/s ")),
+        15 => share(&|line| line.contains("capacity") && line.contains("calories")),
+        16 => share(&|line| line.starts_with("Sue ")),
+        17 => match numbers {
+            Some(_) if weights => 0.5,
+            Some(_) => 1.0,
+            None => 0.0,
+        },
+        18 => yes(lines.len() > 1) * share(&|line| only(line, &|c| c == '#' || c == '.')),
+        19 => match lines.split_last() {
+            Some((molecule, replacements))
+                if !replacements.is_empty() && only(molecule, &|c| c.is_ascii_alphabetic()) =>
+            {
+                let rules = replacements.iter().filter(|l| l.contains(" => ")).count();
+                rules as f64 / replacements.len() as f64
+            }
+            _ => 0.0,
+        },
+        20 => match single {
+            Some(s) if only(s, &|c| ('1'..='3').contains(&c)) => 0.5,
+            Some(s) if s.parse::<u64>().is_ok() => 1.0,
+            _ => 0.0,
+        },
+        21 => yes(input.contains("Hit Points:") && input.contains("Armor:")),
+        22 => yes(input.contains("Hit Points:") && !input.contains("Armor:")),
+        23 => share(&|line| {
+            ["hlf ", "tpl ", "inc ", "jmp ", "jie ", "jio "]
+                .iter()
+                .any(|op| line.starts_with(op))
+        }),
+        24 => match numbers {
+            Some(_) if weights => 1.0,
+            Some(_) => 0.25,
+            None => 0.0,
+        },
+        25 => yes(input.contains("Enter the code at row")),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Some helpers are only used by tests of days which may not be compiled in
+    #![cfg_attr(not(feature = "all-days"), allow(unused_imports, dead_code))]
+
+    use super::*;
+    use std::fs;
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_real_inputs() {
+        for day in 1..=25 {
+            let input = fs::read_to_string(format!("input/2015/day{}.txt", day)).unwrap();
+            let guess = identify(&input).unwrap();
+
+            // days 4 and 11 both take a short string of lowercase letters
+            if day == 4 || day == 11 {
+                assert!(guess.day == 4 || guess.day == 11);
+                assert_eq!(guess.confidence, 0.5);
+            } else {
+                assert_eq!(guess.day, day, "{}", input);
+                assert!(guess.confidence > 0.6, "day {}: {}", day, guess);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_candidates() {
+        let guesses = candidates("1113222113");
+        let days = guesses.iter().map(|g| g.day).collect::<Vec<_>>();
+
+        assert_eq!(days, vec![10, 20]);
+        assert!((guesses[0].confidence - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(guesses[0].to_string(), "day 10 (67%)");
+    }
+
+    #[test]
+    fn test_unrecognised() {
+        assert_eq!(identify("Hello, world!\nGoodbye"), None);
+        assert_eq!(identify(""), None);
+    }
+}
@@ -0,0 +1,65 @@
+//! Normalising raw puzzle input before it is parsed, so that every day accepts files however they
+//! were saved. Every day's parser normalises its own input, either with [normalise] or by reading
+//! it with [lines].
+
+use std::borrow::Cow;
+
+/// Byte order mark which some editors write at the start of UTF-8 files
+const BOM: char = '\u{feff}';
+
+/// Normalise the input as saved by any editor: strip a leading byte order mark, convert Windows
+/// (CRLF) line endings to LF, strip whitespace from the end of every line and drop any trailing
+/// blank lines, including the final newline. Line numbers are unchanged, so parse errors still
+/// point at the right line of the original file. Input which is already normal is borrowed.
+pub fn normalise(input: &str) -> Cow<'_, str> {
+    let mut normal = lines(input).collect::<Vec<_>>().join("\n");
+    normal.truncate(normal.trim_end().len());
+
+    if normal == input {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(normal)
+    }
+}
+
+/// Split the input into lines as [normalise] would, without copying it: any leading byte order mark
+/// and the whitespace at the end of each line, including a Windows line ending, are left out.
+/// Trailing blank lines are kept, so that lines can still be numbered.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    let body = input.strip_prefix(BOM).unwrap_or(input);
+    body.split('\n').map(str::trim_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(normalise("\u{feff}a b\r\n\r\nc  \r\n\r\n \n"), "a b\n\nc");
+        assert_eq!(normalise("  (()\t\n"), "  (()");
+        assert_eq!(normalise("\n\n"), "");
+        assert_eq!(normalise(""), "");
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            lines("\u{feff}a b\r\n\r\nc  \r\n").collect::<Vec<_>>(),
+            vec!["a b", "", "c", ""]
+        );
+        assert_eq!(lines("").collect::<Vec<_>>(), vec![""]);
+    }
+
+    #[test]
+    fn test_normalise_borrows() {
+        assert!(matches!(normalise("a\n\nb"), Cow::Borrowed("a\n\nb")));
+        assert!(matches!(normalise("a\n"), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_bom_only_at_start() {
+        assert_eq!(normalise("a\u{feff}"), "a\u{feff}");
+        assert_eq!(normalise("\u{feff}\u{feff}a"), "\u{feff}a");
+    }
+}
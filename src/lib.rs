@@ -1,35 +1,144 @@
+//! Solutions for [Advent of Code 2015](https://adventofcode.com/2015).
+//!
+//! Each day has a public module with a `generator` which parses the puzzle input into that day's
+//! domain model, and `part1` and `part2` functions which solve it. Days can also be looked up and
+//! run dynamically through the [solver] registry.
+//!
+//! ```
+//! # #[cfg(feature = "day23")] {
+//! use advent_2015::day23::{self, Computer, Instruction, Register};
+//!
+//! let computer = day23::generator("inc b\ntpl b").unwrap();
+//! assert_eq!(day23::part1(&computer), Ok(3));
+//!
+//! let mut computer = Computer::new(vec![Instruction::Inc(Register::A)]);
+//! computer.run().unwrap();
+//! assert_eq!(computer.value(Register::A), 1);
+//! # }
+//! ```
+//!
+//! Every day is behind a cargo feature of the same name, like `day23`, and all of them are enabled
+//! by default. Depending on the crate with `default-features = false` and only the days you need
+//! leaves out the other days and any dependencies which only they use. The `parallel` feature, also
+//! on by default, spreads day 4's search and batch runs across every core. The `checked` feature
+//! reports solvers which overflow on large inputs as an [error::Error::Overflow], see [checked].
+//! The `server` feature, on by default, adds an HTTP server which solves puzzles posted to it. The
+//! `allocations` feature counts the memory each phase allocates when benchmarking.
+//!
+//! The crate also builds as a `cdylib` exporting the C functions in [ffi], which are declared in
+//! `include/advent_2015.h`.
+//!
+//! Documented public items follow semantic versioning, which `cargo semver-checks` checks on every
+//! pull request. The modules behind the command line tool, such as `batch`, `bench` and `render`,
+//! are only public so that the binary and the fuzzer can use them. They are hidden from the
+//! documentation and, like the way each solver works internally, may change at any time.
+
+#![warn(missing_docs)]
+
 use aoc_runner_derive::aoc_lib;
+use std::fmt;
 
-mod compass;
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
+#[doc(hidden)]
+pub mod batch;
+#[doc(hidden)]
+pub mod bench;
+pub mod checked;
+#[doc(hidden)]
+pub mod compare;
+pub mod compass;
+#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day10")]
+pub mod day10;
+#[cfg(feature = "day11")]
+pub mod day11;
+#[cfg(feature = "day12")]
+pub mod day12;
+#[cfg(feature = "day13")]
+pub mod day13;
+#[cfg(feature = "day14")]
+pub mod day14;
+#[cfg(feature = "day15")]
+pub mod day15;
+#[cfg(feature = "day16")]
+pub mod day16;
+#[cfg(feature = "day17")]
+pub mod day17;
+#[cfg(feature = "day18")]
+pub mod day18;
+#[cfg(feature = "day19")]
+pub mod day19;
+#[cfg(feature = "day2")]
+pub mod day2;
+#[cfg(feature = "day20")]
+pub mod day20;
+#[cfg(feature = "day21")]
+pub mod day21;
+#[cfg(feature = "day22")]
+pub mod day22;
+#[cfg(feature = "day23")]
+pub mod day23;
+#[cfg(feature = "day24")]
+pub mod day24;
+#[cfg(feature = "day25")]
+pub mod day25;
+#[cfg(feature = "day3")]
+pub mod day3;
+#[cfg(feature = "day4")]
+pub mod day4;
+#[cfg(feature = "day5")]
+pub mod day5;
+#[cfg(feature = "day6")]
+pub mod day6;
+#[cfg(feature = "day7")]
 pub mod day7;
-mod day8;
-mod day9;
+#[cfg(feature = "day8")]
+pub mod day8;
+#[cfg(feature = "day9")]
+pub mod day9;
+pub mod error;
+#[doc(hidden)]
+pub mod examples;
+pub mod ffi;
+#[doc(hidden)]
+pub mod fuzz;
+#[doc(hidden)]
+pub mod identify;
+pub mod input;
+#[doc(hidden)]
+pub mod memory;
+pub mod params;
+#[cfg(feature = "nom")]
+pub mod parse;
+pub mod progress;
+#[doc(hidden)]
+pub mod random;
+#[doc(hidden)]
+pub mod render;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod solver;
+pub mod trace;
+#[doc(hidden)]
+pub mod verify;
+
 aoc_lib! { year = 2015 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Part of a day's puzzle
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 pub enum Part {
+    /// The first part
     One,
+
+    /// The second part, which is revealed after solving the first
     Two,
 }
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
@@ -0,0 +1,749 @@
+use advent_2015::{
+    batch, bench, compare, identify,
+    params::Params,
+    progress::Progress,
+    random,
+    render::{self, FrameWriter, NoopRecorder},
+    solver,
+    trace::Collector,
+    verify, Part,
+};
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, IsTerminal, Read},
+    path::Path,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+#[cfg(feature = "server")]
+use {advent_2015::server, std::net::TcpListener};
+
+/// Counts what every phase allocates when benchmarking
+#[cfg(feature = "allocations")]
+#[global_allocator]
+static ALLOCATOR: advent_2015::memory::Counting = advent_2015::memory::Counting;
+
+const USAGE: &str = "Usage:
+    advent-2015 run --day <DAY> [--part <1|2>] [--input <FILE|->] [--trace <FILE|->]
+                    [--timeout <SECS>] [--config <FILE>] [--params <KEY=VALUE,...>]
+                    [--strategy <NAME>]
+    advent-2015 run-all [--inputs <DIR>] [--config <FILE>] [--params <KEY=VALUE,...>]
+    advent-2015 verify [--inputs <DIR>] [--answers <FILE>]
+    advent-2015 batch --inputs <DIR> [--csv <FILE>]
+    advent-2015 bench [--day <DAY>] [--iterations <N>] [--inputs <DIR>] [--output <FILE>]
+                      [--baseline <FILE>] [--threshold <PERCENT>]
+    advent-2015 compare [--day <DAY>] [--input <FILE|->] [--inputs <DIR>] [--config <FILE>]
+                        [--params <KEY=VALUE,...>]
+    advent-2015 generate --day <DAY> [--size <N>] [--seed <N>]
+    advent-2015 render --day <3|6|18> --output <FILE> [--part <1|2>] [--input <FILE|->]
+                       [--frames <DIR>] [--every <N>] [--scale <N>] [--config <FILE>]
+                       [--params <KEY=VALUE,...>]
+    advent-2015 identify [--input <FILE|->] [--inputs <DIR>]
+    advent-2015 serve [--address <ADDR>] [--connections <N>] [--timeout <SECS>]
+                      [--config <FILE>] [--params <KEY=VALUE,...>]
+
+Options:
+    --day <DAY>       Day to run (1-25)
+    --part <PART>     Part to run (1 or 2). Runs both parts if omitted
+    --input <FILE>    Input file, or - to read from stdin. Defaults to input/2015/day<DAY>.txt
+    --trace <FILE>    Print the events traced while solving, or write them to a JSON file
+    --timeout <SECS>  Cancel days 4, 9, 13, 19, 20, 22, 23 and 24 if a part takes longer than
+                      this. Defaults to 60 when serving, where every day responds at the deadline
+    --config <FILE>   Read puzzle parameters, like day14.seconds = 1000, from a file
+    --params <K=V,..> Override puzzle parameters, after reading any config file
+    --strategy <NAME> Solve days 9, 17, 20 and 25 with a different strategy, e.g. held_karp
+    --inputs <DIR>    Directory containing day<DAY>.txt input files. Defaults to input/2015. When
+                      identifying, every file in the directory is checked. When batch running,
+                      contains a directory of inputs and an optional answers.txt per account
+    --csv <FILE>      Write the answers and timings of every account, day and part to a CSV file
+    --answers <FILE>  Recorded answers to verify against. Defaults to <DIR>/answers.txt
+    --iterations <N>  Number of times to run each phase when benchmarking. Defaults to 10
+    --output <FILE>   Write the benchmark report to a JSON file, or the rendered image to a .pgm
+                      (grayscale) or .ppm (colour) file
+    --baseline <FILE> Compare against a previous benchmark report
+    --threshold <PCT> Slowdown compared to the baseline which counts as a regression. Defaults to 10
+    --size <N>        Number of lines, or length of single-line inputs, to generate. Defaults to 100.
+                      Days 11, 20, 21 and 22 have a fixed size
+    --seed <N>        Seed for generating input. Defaults to a random seed, which is printed
+    --frames <DIR>    Also write an image of each step to numbered files in a directory
+    --every <N>       Only write a frame every N steps. Defaults to 1
+    --scale <N>       Size in pixels of each cell of the grid. Defaults to 1
+    --address <ADDR>  Address to listen for HTTP requests on. Defaults to 127.0.0.1:2015
+    --connections <N> Most requests to handle at once when serving. Defaults to 16";
+
+const DEFAULT_INPUTS: &str = "input/2015";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("run-all") => run_all(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("batch") => batch(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("compare") => compare(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("render") => render(&args[1..]),
+        Some("identify") => identify(&args[1..]),
+        Some("serve") => serve(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("Unrecognised command: {}\n\n{}", command, USAGE)),
+        None => Err(USAGE.to_string()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+/// Run a single day, either one part or both
+fn run(args: &[String]) -> Result<(), String> {
+    let options = parse_options(
+        args,
+        &[
+            "day", "part", "input", "trace", "timeout", "config", "params", "strategy",
+        ],
+    )?;
+
+    let day = parse_day(options.get("day").ok_or("Missing required option --day")?)?;
+    let solver = solver::solver(day).ok_or_else(|| format!("No solver for day {}", day))?;
+
+    let parts = match options.get("part") {
+        Some(part) => vec![parse_part(part)?],
+        None if solver.has_part(Part::Two) => vec![Part::One, Part::Two],
+        None => vec![Part::One],
+    };
+
+    let timeout = options
+        .get("timeout")
+        .map(|s| parse_timeout(s))
+        .transpose()?;
+
+    let params = puzzle_params(&options)?;
+
+    if options.contains_key("trace") && params != Params::default() {
+        return Err("--trace always uses the puzzle's parameters".to_string());
+    }
+
+    let strategy = options.get("strategy").copied();
+
+    if strategy.is_some() && (options.contains_key("trace") || timeout.is_some()) {
+        return Err("--strategy can't be combined with --trace or --timeout".to_string());
+    }
+
+    let input = day_input(day, options.get("input").copied())?;
+    let parsed = solver::try_parse_with_source(solver, &input)?;
+    let mut collector = Collector::new();
+
+    for part in parts {
+        let progress = timeout.map_or_else(Progress::new, Progress::with_budget);
+
+        let answer = match (strategy, options.get("trace")) {
+            (Some(strategy), _) => {
+                solver::try_run_strategy(solver, strategy, part, parsed.as_ref(), &params)?
+            }
+            (None, Some(_)) => solver::try_trace(solver, part, parsed.as_ref(), &mut collector)?,
+            (None, None) => show_progress(&progress, || {
+                solver::try_run_with(solver, part, parsed.as_ref(), &params, &progress)
+            })
+            .map_err(|e| match timeout {
+                Some(timeout) if progress.is_cancelled() => {
+                    format!("Day {} part {} cancelled after {:?}", day, part, timeout)
+                }
+                _ => e,
+            })?,
+        };
+
+        if options.get("trace") == Some(&"-") {
+            print!("{}", collector);
+            collector.events.clear();
+        }
+
+        println!("{}", answer);
+    }
+
+    match options.get("trace") {
+        Some(&"-") | None => Ok(()),
+        Some(path) => fs::write(path, collector.to_json())
+            .map_err(|e| format!("Unable to write {}: {}", path, e)),
+    }
+}
+
+/// Run a solver, showing the percentage of its progress on stderr if that's a terminal and the
+/// solver reports its progress
+fn show_progress<T>(progress: &Progress, f: impl FnOnce() -> T) -> T {
+    if !io::stderr().is_terminal() {
+        return f();
+    }
+
+    let finished = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let reporter = scope.spawn(|| {
+            while !finished.load(Ordering::Relaxed) {
+                if let Some(fraction) = progress.fraction() {
+                    eprint!("\r{:>5.1}%", fraction * 100.0);
+                }
+
+                thread::park_timeout(Duration::from_millis(100));
+            }
+
+            if progress.fraction().is_some() {
+                eprint!("\r      \r");
+            }
+        });
+
+        let result = f();
+        finished.store(true, Ordering::Relaxed);
+        reporter.thread().unpark();
+
+        result
+    })
+}
+
+/// Run every day which has an input file available, reporting failures at the end
+fn run_all(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["inputs", "config", "params"])?;
+    let dir = Path::new(options.get("inputs").copied().unwrap_or(DEFAULT_INPUTS));
+    let params = puzzle_params(&options)?;
+
+    let mut failures = 0;
+
+    for solver in solver::solvers() {
+        let day = solver.day();
+        let path = dir.join(format!("day{}.txt", day));
+
+        if !path.exists() {
+            println!("Day {:>2}: skipped, no input at {}", day, path.display());
+            continue;
+        }
+
+        let input = read_input(&path)?;
+
+        let parsed = match solver::try_parse(solver, &input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                println!("Day {:>2}: FAILED - {}", day, e);
+                failures += 1;
+                continue;
+            }
+        };
+
+        for part in [Part::One, Part::Two].iter().copied() {
+            if !solver.has_part(part) {
+                continue;
+            }
+
+            match solver::try_run_with(solver, part, parsed.as_ref(), &params, &Progress::new()) {
+                Ok(answer) => println!("Day {:>2} part {}: {}", day, part, answer),
+                Err(e) => {
+                    println!("Day {:>2} part {}: FAILED - {}", day, part, e);
+                    failures += 1;
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        Err(format!("{} solver(s) failed", failures))
+    } else {
+        Ok(())
+    }
+}
+
+/// Verify every day against the recorded answers, printing a table of the results
+fn verify(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["inputs", "answers"])?;
+    let dir = Path::new(options.get("inputs").copied().unwrap_or(DEFAULT_INPUTS));
+
+    let answers = match options.get("answers") {
+        Some(path) => verify::Answers::read(Path::new(path))?,
+        None => verify::Answers::read(&dir.join(verify::ANSWERS_FILE))?,
+    };
+
+    let outcomes = verify::verify(dir, &answers);
+    println!("{}", verify::Table(&outcomes));
+
+    let failures = outcomes
+        .iter()
+        .filter(|o| matches!(o.status, verify::Status::Fail(_) | verify::Status::Error(_)))
+        .count();
+
+    if failures > 0 {
+        Err(format!("{} answer(s) failed verification", failures))
+    } else {
+        Ok(())
+    }
+}
+
+/// Run every account's inputs in parallel, printing a matrix of answers and timings
+fn batch(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["inputs", "csv"])?;
+    let dir = Path::new(
+        options
+            .get("inputs")
+            .ok_or("Missing required option --inputs")?,
+    );
+
+    let outcomes = batch::batch(dir)?;
+    println!("{}", batch::Matrix(&outcomes));
+
+    if let Some(path) = options.get("csv") {
+        fs::write(path, batch::to_csv(&outcomes))
+            .map_err(|e| format!("Unable to write {}: {}", path, e))?;
+    }
+
+    let failures = outcomes
+        .iter()
+        .filter(|o| matches!(o.status, verify::Status::Fail(_) | verify::Status::Error(_)))
+        .count();
+
+    if failures > 0 {
+        Err(format!("{} answer(s) failed", failures))
+    } else {
+        Ok(())
+    }
+}
+
+/// Benchmark each phase of one or every day, optionally saving the report or comparing it to a
+/// baseline
+fn bench(args: &[String]) -> Result<(), String> {
+    let options = parse_options(
+        args,
+        &[
+            "day",
+            "iterations",
+            "inputs",
+            "output",
+            "baseline",
+            "threshold",
+        ],
+    )?;
+    let dir = Path::new(options.get("inputs").copied().unwrap_or(DEFAULT_INPUTS));
+
+    let iterations = match options.get("iterations") {
+        Some(s) => match s.parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(format!("Invalid iterations: {}", s)),
+        },
+        None => 10,
+    };
+
+    let threshold = match options.get("threshold") {
+        Some(s) => s
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid threshold: {}", s))?,
+        None => 10.0,
+    };
+
+    let solvers = match options.get("day") {
+        Some(day) => {
+            let day = parse_day(day)?;
+            vec![solver::solver(day).ok_or_else(|| format!("No solver for day {}", day))?]
+        }
+        None => solver::solvers()
+            .filter(|solver| dir.join(format!("day{}.txt", solver.day())).exists())
+            .collect(),
+    };
+
+    let mut timings = Vec::new();
+
+    for solver in solvers {
+        let input = read_input(&dir.join(format!("day{}.txt", solver.day())))?;
+        timings.extend(bench::bench(solver, &input, iterations)?);
+    }
+
+    let report = bench::Report {
+        iterations,
+        timings,
+    };
+
+    println!("{}", bench::Table(&report.timings));
+
+    if let Some(path) = options.get("output") {
+        fs::write(path, report.to_json())
+            .map_err(|e| format!("Unable to write {}: {}", path, e))?;
+    }
+
+    if let Some(path) = options.get("baseline") {
+        let baseline = bench::Report::read(Path::new(path))?;
+        let comparisons = bench::compare(&report, &baseline);
+
+        println!(
+            "\n{}",
+            bench::ComparisonTable {
+                comparisons: &comparisons,
+                threshold,
+            }
+        );
+
+        let regressions = comparisons
+            .iter()
+            .filter(|c| c.is_regression(threshold))
+            .count();
+
+        if regressions > 0 {
+            return Err(format!(
+                "{} phase(s) regressed by more than {}%",
+                regressions, threshold
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Solve one or every day with each of its strategies, reporting any which disagree and how long
+/// each took
+fn compare(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["day", "input", "inputs", "config", "params"])?;
+    let dir = Path::new(options.get("inputs").copied().unwrap_or(DEFAULT_INPUTS));
+    let params = puzzle_params(&options)?;
+
+    let comparisons = match options.get("day") {
+        Some(day) => {
+            let day = parse_day(day)?;
+            let solver = solver::solver(day).ok_or_else(|| format!("No solver for day {}", day))?;
+            let input = match options.get("input") {
+                Some(&path) => read_path(path)?,
+                None => read_input(&dir.join(format!("day{}.txt", day)))?,
+            };
+
+            compare::compare(solver, &input, &params)?
+        }
+        None if options.contains_key("input") => {
+            return Err("--input needs --day to say which day it is for".to_string())
+        }
+        None => {
+            let mut comparisons = Vec::new();
+
+            for solver in solver::solvers().filter(|s| !s.strategies().is_empty()) {
+                let path = dir.join(format!("day{}.txt", solver.day()));
+
+                if path.exists() {
+                    comparisons.extend(compare::compare(solver, &read_input(&path)?, &params)?);
+                }
+            }
+
+            comparisons
+        }
+    };
+
+    println!("{}", compare::Table(&comparisons));
+
+    for c in &comparisons {
+        for run in &c.runs {
+            if let Err(e) = &run.answer {
+                println!("Day {} part {} {}: {}", c.day, c.part, run.strategy, e);
+            }
+        }
+    }
+
+    let disagreements = comparisons.iter().filter(|c| !c.agrees()).count();
+
+    if disagreements > 0 {
+        Err(format!("Strategies disagree on {} part(s)", disagreements))
+    } else {
+        Ok(())
+    }
+}
+
+/// Generate a random input for a day, printing the seed to stderr so that it can be reproduced
+fn generate(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["day", "size", "seed"])?;
+
+    let day = parse_day(options.get("day").ok_or("Missing required option --day")?)?;
+
+    let size = match options.get("size") {
+        Some(_) if random::FIXED_SIZE.contains(&day) => {
+            return Err(format!("Day {} inputs have a fixed size", day))
+        }
+        Some(s) => s.parse().map_err(|_| format!("Invalid size: {}", s))?,
+        None => 100,
+    };
+
+    let seed = match options.get("seed") {
+        Some(s) => s.parse().map_err(|_| format!("Invalid seed: {}", s))?,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default(),
+    };
+
+    let input = random::generate(day, size, seed)
+        .ok_or_else(|| format!("No input generator for day {}", day))?;
+
+    eprintln!("Generated day {} input with seed {}", day, seed);
+    println!("{}", input);
+
+    Ok(())
+}
+
+/// Render the final state of a grid-based day to an image, optionally writing each step as a
+/// numbered frame
+fn render(args: &[String]) -> Result<(), String> {
+    let options = parse_options(
+        args,
+        &[
+            "day", "part", "input", "output", "frames", "every", "scale", "config", "params",
+        ],
+    )?;
+
+    let day = parse_day(options.get("day").ok_or("Missing required option --day")?)?;
+    let part = options
+        .get("part")
+        .map_or(Ok(Part::One), |s| parse_part(s))?;
+    let output = Path::new(
+        options
+            .get("output")
+            .ok_or("Missing required option --output")?,
+    );
+
+    let format = render::Format::from_path(output).ok_or_else(|| {
+        format!(
+            "Unsupported image format {}, use .pgm or .ppm",
+            output.display()
+        )
+    })?;
+
+    let number = |key: &str| match options.get(key) {
+        Some(s) => match s.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("Invalid {}: {}", key, s)),
+        },
+        None => Ok(1),
+    };
+
+    let every = number("every")?;
+    let scale = number("scale")?;
+
+    let params = puzzle_params(&options)?;
+    let input = day_input(day, options.get("input").copied())?;
+
+    let image = match options.get("frames") {
+        Some(dir) => {
+            fs::create_dir_all(dir).map_err(|e| format!("Unable to create {}: {}", dir, e))?;
+
+            let mut frames = FrameWriter::new(Path::new(dir), format)
+                .every(every)
+                .scale(scale);
+            let image = render::render(day, part, &input, &params, &mut frames)?;
+            let count = frames
+                .finish()
+                .map_err(|e| format!("Unable to write frames to {}: {}", dir, e))?;
+
+            eprintln!("Wrote {} frames to {}", count, dir);
+            image
+        }
+        None => render::render(day, part, &input, &params, &mut NoopRecorder)?,
+    };
+
+    image
+        .scaled(scale)
+        .write(output)
+        .map_err(|e| format!("Unable to write {}: {}", output.display(), e))
+}
+
+/// Work out which day an input file belongs to, or check every file in a directory and flag
+/// any named after a different day
+fn identify(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["input", "inputs"])?;
+
+    let dir = match (options.get("input"), options.get("inputs")) {
+        (Some(_), Some(_)) => return Err("Use either --input or --inputs, not both".to_string()),
+        (Some(&path), None) => {
+            let input = read_path(path)?;
+            let candidates = identify::candidates(&input);
+
+            if candidates.is_empty() {
+                return Err("Unable to identify input".to_string());
+            }
+
+            for guess in candidates {
+                println!("{}", guess);
+            }
+
+            return Ok(());
+        }
+        (None, dir) => Path::new(dir.copied().unwrap_or(DEFAULT_INPUTS)),
+    };
+
+    let mut paths = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| format!("Unable to read {}: {}", dir.display(), e))?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    let mut mislabelled = 0;
+
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let input = read_input(&path)?;
+
+        let candidates = identify::candidates(&input);
+
+        let best = match candidates.first() {
+            Some(best) => best,
+            None => {
+                println!("{}: unrecognised", name);
+                continue;
+            }
+        };
+
+        let label = name
+            .strip_prefix("day")
+            .and_then(|s| s.strip_suffix(".txt"))
+            .and_then(|s| s.parse::<u8>().ok());
+
+        // some days have inputs which look identical, so only flag files whose label is less
+        // likely than the best guess
+        let labelled = candidates
+            .iter()
+            .find(|guess| Some(guess.day) == label && guess.confidence == best.confidence);
+
+        match (label, labelled) {
+            (Some(_), None) => {
+                println!("{}: {}, MISLABELLED", name, best);
+                mislabelled += 1;
+            }
+            _ => println!("{}: {}", name, labelled.unwrap_or(best)),
+        }
+    }
+
+    if mislabelled > 0 {
+        Err(format!("{} file(s) look like a different day", mislabelled))
+    } else {
+        Ok(())
+    }
+}
+
+/// Answer puzzles posted over HTTP until the server fails
+#[cfg(feature = "server")]
+fn serve(args: &[String]) -> Result<(), String> {
+    let options = parse_options(
+        args,
+        &["address", "connections", "timeout", "config", "params"],
+    )?;
+    let address = options.get("address").copied().unwrap_or("127.0.0.1:2015");
+    let params = puzzle_params(&options)?;
+    let mut limits = server::Limits::default();
+
+    if let Some(s) = options.get("connections") {
+        limits.connections = match s.parse() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(format!("Invalid number of connections: {}", s)),
+        };
+    }
+
+    if let Some(s) = options.get("timeout") {
+        limits.timeout = parse_timeout(s)?;
+    }
+
+    let listener = TcpListener::bind(address)
+        .map_err(|e| format!("Unable to listen on {}: {}", address, e))?;
+    eprintln!("Listening on http://{}", address);
+
+    server::serve(listener, &params, &limits).map_err(|e| format!("Server failed: {}", e))
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_args: &[String]) -> Result<(), String> {
+    Err("serve needs advent-2015 to be built with the server feature".to_string())
+}
+
+/// Parse `--key value` pairs, rejecting any keys which aren't allowed
+fn parse_options<'a>(
+    args: &'a [String],
+    allowed: &[&str],
+) -> Result<HashMap<&'a str, &'a str>, String> {
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let key = arg
+            .strip_prefix("--")
+            .filter(|key| allowed.contains(key))
+            .ok_or_else(|| format!("Unrecognised option: {}\n\n{}", arg, USAGE))?;
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for option: {}", arg))?;
+
+        options.insert(key, value.as_str());
+    }
+
+    Ok(options)
+}
+
+/// Read the puzzle parameters from the config file, if one was given, then apply any overrides
+fn puzzle_params(options: &HashMap<&str, &str>) -> Result<Params, String> {
+    let mut params = match options.get("config") {
+        Some(path) => Params::read(Path::new(path))?,
+        None => Params::default(),
+    };
+
+    if let Some(overrides) = options.get("params") {
+        params.set_all(overrides)?;
+    }
+
+    Ok(params)
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    match s.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid day: {}", s)),
+    }
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    match s.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!("Invalid timeout: {}", s)),
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("Invalid part: {}", s)),
+    }
+}
+
+/// Read the input for a day from the given path, or the default inputs directory
+fn day_input(day: u8, path: Option<&str>) -> Result<String, String> {
+    match path {
+        Some(path) => read_path(path),
+        None => read_input(&Path::new(DEFAULT_INPUTS).join(format!("day{}.txt", day))),
+    }
+}
+
+/// Read a file, or stdin if the path is `-`
+fn read_path(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Unable to read stdin: {}", e))?;
+        Ok(input)
+    } else {
+        read_input(Path::new(path))
+    }
+}
+
+fn read_input(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))
+}
@@ -0,0 +1,235 @@
+//! Counting the memory allocated while running a solver, using the `Counting` allocator which is
+//! only available with the `allocations` feature. The library doesn't install it itself: a program
+//! which wants the counts installs it as its global allocator, as the `advent-2015` binary does:
+//!
+//! ```
+//! # #[cfg(feature = "allocations")]
+//! #[global_allocator]
+//! static ALLOCATOR: advent_2015::memory::Counting = advent_2015::memory::Counting;
+//! ```
+//!
+//! The counts are shared by every thread, so they include allocations made by other threads while
+//! measuring, such as day 4's search with the `parallel` feature.
+
+use std::fmt;
+#[cfg(feature = "allocations")]
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// Number of allocations, including reallocations, since the program started
+#[cfg(feature = "allocations")]
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes requested by every allocation since the program started
+#[cfg(feature = "allocations")]
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes currently allocated
+#[cfg(feature = "allocations")]
+static LIVE: AtomicU64 = AtomicU64::new(0);
+
+/// Most bytes allocated at once since the current measurement started
+#[cfg(feature = "allocations")]
+static PEAK: AtomicU64 = AtomicU64::new(0);
+
+/// Whether [Counting] has allocated anything, which means it's the global allocator
+#[cfg(feature = "allocations")]
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// The system allocator, counting what is allocated through it when installed as the global
+/// allocator
+#[cfg(feature = "allocations")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counting;
+
+#[cfg(feature = "allocations")]
+impl Counting {
+    /// Record an allocation of `size` bytes which replaced `freed` bytes
+    fn record(size: usize, freed: usize) {
+        INSTALLED.store(true, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+
+        // a reallocation may copy into a new block before freeing the old one, so both count
+        // towards the peak
+        let live = LIVE.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+        LIVE.fetch_sub(freed as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "allocations")]
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Counting::record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Counting::record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            Counting::record(new_size, layout.size());
+        }
+        new
+    }
+}
+
+/// Memory allocated while running something
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    /// Number of allocations, including reallocations
+    pub allocations: u64,
+
+    /// Total bytes requested by every allocation
+    pub allocated: u64,
+
+    /// Most bytes allocated at once, on top of what was already allocated beforehand
+    pub peak: u64,
+}
+
+impl Usage {
+    /// The most of each count between the two, such as the most allocated by any iteration
+    pub fn most(self, other: Usage) -> Usage {
+        Usage {
+            allocations: self.allocations.max(other.allocations),
+            allocated: self.allocated.max(other.allocated),
+            peak: self.peak.max(other.peak),
+        }
+    }
+}
+
+/// Run the function, returning how much memory it allocated if the `allocations` feature is
+/// enabled and `Counting` is the global allocator. Measurements on different threads at the same
+/// time count each other's allocations.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    #[cfg(feature = "allocations")]
+    {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let allocated = ALLOCATED.load(Ordering::Relaxed);
+        let live = LIVE.load(Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+
+        let result = f();
+
+        if !INSTALLED.load(Ordering::Relaxed) {
+            return (result, None);
+        }
+
+        let usage = Usage {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+            allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+            peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+        };
+
+        (result, Some(usage))
+    }
+
+    #[cfg(not(feature = "allocations"))]
+    (f(), None)
+}
+
+/// Formats a number of bytes with a binary unit, like `1.5 MiB`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes(pub u64);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        if self.0 < 1024 {
+            return f.pad(&format!("{} B", self.0));
+        }
+
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+
+        while value >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        f.pad(&format!("{:.1} {}", value, UNITS[unit]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "allocations")]
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(Bytes(0).to_string(), "0 B");
+        assert_eq!(Bytes(1023).to_string(), "1023 B");
+        assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+        assert_eq!(Bytes(3 << 20).to_string(), "3.0 MiB");
+        assert_eq!(Bytes(u64::MAX).to_string(), "16777216.0 TiB");
+        assert_eq!(format!("{:>9}", Bytes(1024)), "  1.0 KiB");
+    }
+
+    #[test]
+    #[cfg(feature = "allocations")]
+    fn test_measure() {
+        let (sum, usage) = measure(|| {
+            let mut values = vec![1u8; 1 << 20];
+            values.extend_from_slice(&[2; 16]);
+            values.iter().map(|&v| v as u64).sum::<u64>()
+        });
+        let usage = usage.unwrap();
+
+        assert_eq!(sum, (1 << 20) + 32);
+        assert!(usage.allocations >= 2);
+        assert!(usage.allocated >= (2 << 20) + 16);
+        assert!(usage.peak >= (1 << 20) + 16);
+    }
+
+    #[test]
+    fn test_most() {
+        let a = Usage {
+            allocations: 3,
+            allocated: 100,
+            peak: 80,
+        };
+        let b = Usage {
+            allocations: 2,
+            allocated: 120,
+            peak: 60,
+        };
+
+        assert_eq!(
+            a.most(b),
+            Usage {
+                allocations: 3,
+                allocated: 120,
+                peak: 80
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "allocations"))]
+    fn test_measure_without_allocator() {
+        assert_eq!(measure(|| 5), (5, None));
+    }
+}
@@ -0,0 +1,404 @@
+//! Puzzle parameters which are fixed by the puzzle text rather than the input, which can be
+//! changed to solve variants of a puzzle
+
+#[cfg(feature = "day14")]
+use crate::day14;
+#[cfg(feature = "day17")]
+use crate::day17;
+#[cfg(feature = "day18")]
+use crate::day18;
+#[cfg(feature = "day20")]
+use crate::day20;
+#[cfg(feature = "day21")]
+use crate::day21;
+#[cfg(feature = "day22")]
+use crate::day22;
+#[cfg(feature = "day4")]
+use crate::day4;
+#[cfg(feature = "day7")]
+use crate::day7;
+use std::{fmt, fs, path::Path};
+
+/// Parameters for every day which has them. The defaults are the values from the puzzles.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    /// Leading zeroes of the hash in each part
+    #[cfg(feature = "day4")]
+    pub day4: day4::Params,
+
+    /// Wire to find the value of, and wire to override in part 2
+    #[cfg(feature = "day7")]
+    pub day7: day7::Params,
+
+    /// Length of the race
+    #[cfg(feature = "day14")]
+    pub day14: day14::Params,
+
+    /// Amount of eggnog to store
+    #[cfg(feature = "day17")]
+    pub day17: day17::Params,
+
+    /// Size of the grid of lights and number of steps
+    #[cfg(feature = "day18")]
+    pub day18: day18::Params,
+
+    /// Presents per house and houses per elf
+    #[cfg(feature = "day20")]
+    pub day20: day20::Params,
+
+    /// Hero's hit points and the shop
+    #[cfg(feature = "day21")]
+    pub day21: day21::Params,
+
+    /// Hero's hit points and mana
+    #[cfg(feature = "day22")]
+    pub day22: day22::Params,
+}
+
+impl Params {
+    /// Set a single parameter from its key, like `day14.seconds`, and its value written as in a
+    /// config file
+    #[cfg_attr(
+        not(any(
+            feature = "day4",
+            feature = "day7",
+            feature = "day14",
+            feature = "day17",
+            feature = "day18",
+            feature = "day20",
+            feature = "day21",
+            feature = "day22"
+        )),
+        allow(unreachable_code, unused_variables)
+    )]
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+
+        match key.trim() {
+            #[cfg(feature = "day4")]
+            "day4.part1_zeroes" => self.day4.part1_zeroes = zeroes(key, value)?,
+            #[cfg(feature = "day4")]
+            "day4.part2_zeroes" => self.day4.part2_zeroes = zeroes(key, value)?,
+            #[cfg(feature = "day7")]
+            "day7.wire" => self.day7.wire = wire(key, value)?,
+            #[cfg(feature = "day7")]
+            "day7.override_wire" => self.day7.override_wire = wire(key, value)?,
+            #[cfg(feature = "day14")]
+            "day14.seconds" => self.day14.seconds = number(key, value)?,
+            #[cfg(feature = "day17")]
+            "day17.litres" => self.day17.litres = number(key, value)?,
+            #[cfg(feature = "day18")]
+            "day18.size" => match number(key, value)? {
+                size if size <= day18::MAX_SIZE => self.day18.size = size,
+                _ => return Err(invalid(key, value, "grids can't be that large")),
+            },
+            #[cfg(feature = "day18")]
+            "day18.steps" => self.day18.steps = number(key, value)?,
+            #[cfg(feature = "day20")]
+            "day20.part1_presents" => self.day20.part1_presents = positive(key, value)?,
+            #[cfg(feature = "day20")]
+            "day20.part2_presents" => self.day20.part2_presents = positive(key, value)?,
+            #[cfg(feature = "day20")]
+            "day20.part2_visits" => self.day20.part2_visits = positive(key, value)?,
+            #[cfg(feature = "day21")]
+            "day21.hero_hp" => self.day21.hero_hp = number(key, value)?,
+            #[cfg(feature = "day21")]
+            "day21.weapons" => match items(key, value)? {
+                weapons if !weapons.is_empty() => self.day21.weapons = weapons,
+                _ => return Err(invalid(key, value, "the hero must be able to buy a weapon")),
+            },
+            #[cfg(feature = "day21")]
+            "day21.armour" => self.day21.armour = items(key, value)?,
+            #[cfg(feature = "day21")]
+            "day21.rings" => self.day21.rings = items(key, value)?,
+            #[cfg(feature = "day22")]
+            "day22.hero_hp" => self.day22.hero_hp = number(key, value)?,
+            #[cfg(feature = "day22")]
+            "day22.hero_mana" => self.day22.hero_mana = number(key, value)?,
+            _ => return Err(format!("Unknown parameter: {}", key.trim())),
+        }
+
+        Ok(())
+    }
+
+    /// Override parameters from a comma separated list like `day14.seconds=1000,day17.litres=25`
+    pub fn set_all(&mut self, overrides: &str) -> Result<(), String> {
+        for setting in overrides.split(',').filter(|s| !s.trim().is_empty()) {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid parameter {:?}, expected key=value", setting))?;
+
+            self.set(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse a config file, starting from the puzzle's parameters. Each line is `<key> = <value>`,
+    /// blank lines and lines starting with `#` are ignored.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut params = Params::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid parameter on line {}: {:?}", i + 1, line))?;
+
+            params
+                .set(key, value)
+                .map_err(|e| format!("{} on line {}", e, i + 1))?;
+        }
+
+        Ok(params)
+    }
+
+    /// Read and parse a config file
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+        Params::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Write every parameter as a config file, which parses back to the same parameters
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[allow(unused_mut)]
+        let mut lines: Vec<String> = Vec::new();
+
+        #[cfg(feature = "day4")]
+        lines.extend([
+            format!("day4.part1_zeroes = {}", self.day4.part1_zeroes),
+            format!("day4.part2_zeroes = {}", self.day4.part2_zeroes),
+        ]);
+
+        #[cfg(feature = "day7")]
+        lines.extend([
+            format!("day7.wire = {}", self.day7.wire),
+            format!("day7.override_wire = {}", self.day7.override_wire),
+        ]);
+
+        #[cfg(feature = "day14")]
+        lines.extend([format!("day14.seconds = {}", self.day14.seconds)]);
+
+        #[cfg(feature = "day17")]
+        lines.extend([format!("day17.litres = {}", self.day17.litres)]);
+
+        #[cfg(feature = "day18")]
+        lines.extend([
+            format!("day18.size = {}", self.day18.size),
+            format!("day18.steps = {}", self.day18.steps),
+        ]);
+
+        #[cfg(feature = "day20")]
+        lines.extend([
+            format!("day20.part1_presents = {}", self.day20.part1_presents),
+            format!("day20.part2_presents = {}", self.day20.part2_presents),
+            format!("day20.part2_visits = {}", self.day20.part2_visits),
+        ]);
+
+        #[cfg(feature = "day21")]
+        {
+            let items = |items: &[day21::Equipment]| {
+                items
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+
+            lines.extend([
+                format!("day21.hero_hp = {}", self.day21.hero_hp),
+                format!("day21.weapons = {}", items(&self.day21.weapons)),
+                format!("day21.armour = {}", items(&self.day21.armour)),
+                format!("day21.rings = {}", items(&self.day21.rings)),
+            ]);
+        }
+
+        #[cfg(feature = "day22")]
+        lines.extend([
+            format!("day22.hero_hp = {}", self.day22.hero_hp),
+            format!("day22.hero_mana = {}", self.day22.hero_mana),
+        ]);
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(any(
+    feature = "day4",
+    feature = "day7",
+    feature = "day14",
+    feature = "day17",
+    feature = "day18",
+    feature = "day20",
+    feature = "day21",
+    feature = "day22"
+))]
+fn invalid(key: &str, value: &str, reason: impl fmt::Display) -> String {
+    format!("Invalid value for {} {:?}: {}", key.trim(), value, reason)
+}
+
+#[cfg(any(
+    feature = "day4",
+    feature = "day14",
+    feature = "day17",
+    feature = "day18",
+    feature = "day20",
+    feature = "day21",
+    feature = "day22"
+))]
+fn number<T>(key: &str, value: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    value.parse().map_err(|e| invalid(key, value, e))
+}
+
+#[cfg(feature = "day20")]
+fn positive(key: &str, value: &str) -> Result<usize, String> {
+    match number(key, value)? {
+        0 => Err(invalid(key, value, "must be at least 1")),
+        n => Ok(n),
+    }
+}
+
+#[cfg(feature = "day4")]
+fn zeroes(key: &str, value: &str) -> Result<usize, String> {
+    match number(key, value)? {
+        n if n <= day4::MAX_ZEROES => Ok(n),
+        _ => Err(invalid(
+            key,
+            value,
+            format!("hashes only have {} digits", day4::MAX_ZEROES),
+        )),
+    }
+}
+
+#[cfg(feature = "day7")]
+fn wire(key: &str, value: &str) -> Result<String, String> {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_lowercase()) {
+        Ok(value.to_string())
+    } else {
+        Err(invalid(
+            key,
+            value,
+            "wires are named with lowercase letters",
+        ))
+    }
+}
+
+/// Parse a space separated list of items, each written as `cost/damage/armour`
+#[cfg(feature = "day21")]
+fn items(key: &str, value: &str) -> Result<Vec<day21::Equipment>, String> {
+    value
+        .split_whitespace()
+        .map(|item| item.parse().map_err(|e| invalid(key, value, e)))
+        .collect()
+}
+
+// Every test sets parameters for several days
+#[cfg(all(
+    test,
+    feature = "day4",
+    feature = "day7",
+    feature = "day14",
+    feature = "day17",
+    feature = "day18",
+    feature = "day20",
+    feature = "day21",
+    feature = "day22"
+))]
+mod tests {
+    use super::*;
+    use crate::day21::Equipment;
+
+    #[test]
+    fn test_parse() {
+        let params = Params::parse(
+            "# variant\n\nday14.seconds = 1000\n day7.wire=x \nday21.rings = 25/1/0 20/0/1\n",
+        )
+        .unwrap();
+
+        assert_eq!(params.day14.seconds, 1000);
+        assert_eq!(params.day7.wire, "x");
+        assert_eq!(
+            params.day21.rings,
+            vec![Equipment::new(25, 1, 0), Equipment::new(20, 0, 1)]
+        );
+        assert_eq!(params.day17, Params::default().day17);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut params = Params::default();
+        params.set_all("day18.size=6,day21.armour=").unwrap();
+
+        assert_eq!(Params::parse(&params.to_string()), Ok(params));
+        assert_eq!(
+            Params::parse(&Params::default().to_string()),
+            Ok(Params::default())
+        );
+    }
+
+    #[test]
+    fn test_set_all() {
+        let mut params = Params::default();
+        params
+            .set_all("day4.part1_zeroes=3, day22.hero_mana=250,")
+            .unwrap();
+
+        assert_eq!(params.day4.part1_zeroes, 3);
+        assert_eq!(params.day22.hero_mana, 250);
+        assert_eq!(
+            params.set_all("day17.litres"),
+            Err("Invalid parameter \"day17.litres\", expected key=value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut params = Params::default();
+
+        assert_eq!(
+            params.set("day14.speed", "1"),
+            Err("Unknown parameter: day14.speed".to_string())
+        );
+        assert_eq!(
+            params.set("day17.litres", "-1"),
+            Err("Invalid value for day17.litres \"-1\": invalid digit found in string".to_string())
+        );
+        assert_eq!(
+            params.set("day4.part2_zeroes", "33"),
+            Err(
+                "Invalid value for day4.part2_zeroes \"33\": hashes only have 32 digits"
+                    .to_string()
+            )
+        );
+        assert!(params.set("day7.wire", "A").is_err());
+        assert!(params.set("day18.size", "100000").is_err());
+        assert!(params.set("day20.part2_visits", "0").is_err());
+        assert!(params.set("day21.weapons", "").is_err());
+        assert!(params.set("day21.rings", "1/2").is_err());
+        assert_eq!(params, Params::default());
+
+        assert_eq!(
+            Params::parse("day14.seconds = 10\nday14.seconds 20"),
+            Err("Invalid parameter on line 2: \"day14.seconds 20\"".to_string())
+        );
+        assert_eq!(
+            Params::parse("\nday22.hero_hp = lots"),
+            Err(
+                "Invalid value for day22.hero_hp \"lots\": invalid digit found in string on line 2"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Shared nom combinators for parsing puzzle input, whose errors point at the column where parsing
+//! failed
+
+use crate::error::{Error, Line};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, char, digit1, one_of},
+    combinator::{opt, recognize},
+    error::{ContextError, ErrorKind, FromExternalError, ParseError},
+    sequence::{pair, separated_pair},
+    IResult,
+};
+use std::{fmt, str::FromStr};
+
+/// Where a parser failed, and why if it said
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    /// Input remaining at the point of failure
+    pub input: &'a str,
+
+    /// Description of the problem, or `None` if the parser which failed didn't give one
+    pub message: Option<String>,
+}
+
+impl<'a> ParseError<&'a str> for Failure<'a> {
+    fn from_error_kind(input: &'a str, _kind: ErrorKind) -> Self {
+        Failure {
+            input,
+            message: None,
+        }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+/// Keeps the innermost context, which describes the problem most precisely
+impl<'a> ContextError<&'a str> for Failure<'a> {
+    fn add_context(_input: &'a str, context: &'static str, mut other: Self) -> Self {
+        other.message.get_or_insert_with(|| context.to_string());
+        other
+    }
+}
+
+/// Describes the problem with the error returned by a function passed to `map_res`
+impl<'a, E: fmt::Display> FromExternalError<&'a str, E> for Failure<'a> {
+    fn from_external_error(input: &'a str, _kind: ErrorKind, e: E) -> Self {
+        Failure {
+            input,
+            message: Some(e.to_string()),
+        }
+    }
+}
+
+/// Result of a parser which reports a [Failure]
+pub type Parsed<'a, T> = IResult<&'a str, T, Failure<'a>>;
+
+/// Parse an unsigned decimal integer
+pub fn integer<T>(s: &str) -> Parsed<'_, T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    number(digit1)(s)
+}
+
+/// Parse a decimal integer with an optional sign, like the offsets `+7` and `-3`
+pub fn signed<T>(s: &str) -> Parsed<'_, T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    number(recognize(pair(opt(one_of("+-")), digit1)))(s)
+}
+
+/// Parse a name made of ASCII letters, like a person, city or wire
+pub fn name(s: &str) -> Parsed<'_, &str> {
+    alpha1(s)
+}
+
+/// Parse a `key: value` pair whose key is a [name], returning both
+pub fn key_value<'a, T>(
+    value: impl FnMut(&'a str) -> Parsed<'a, T>,
+) -> impl FnMut(&'a str) -> Parsed<'a, (&'a str, T)> {
+    separated_pair(name, tag(": "), value)
+}
+
+/// Parse a `key: value` pair with the given key, returning the value
+pub fn field<'a, T>(
+    key: &'a str,
+    mut value: impl FnMut(&'a str) -> Parsed<'a, T>,
+) -> impl FnMut(&'a str) -> Parsed<'a, T> {
+    move |s| {
+        let (s, _) = tag(key)(s)?;
+        let (s, _) = tag(": ")(s)?;
+        value(s)
+    }
+}
+
+/// Parse two values separated by a comma, like the coordinate `3,4`
+pub fn comma_pair<'a, T>(
+    mut item: impl FnMut(&'a str) -> Parsed<'a, T>,
+) -> impl FnMut(&'a str) -> Parsed<'a, (T, T)> {
+    move |s| {
+        let (s, first) = item(s)?;
+        let (s, _) = char(',')(s)?;
+        let (s, second) = item(s)?;
+        Ok((s, (first, second)))
+    }
+}
+
+/// Parse the whole of a line, ignoring whitespace around it. A failure is reported at the column
+/// where it happened, described by the parser if it gave a message or by `expected` otherwise.
+pub fn line<'a, T>(
+    line: &Line<'a>,
+    mut parser: impl FnMut(&'a str) -> Parsed<'a, T>,
+    expected: &str,
+) -> Result<T, Error> {
+    match parser(line.text.trim()) {
+        Ok(("", value)) => Ok(value),
+        Ok((rest, _)) => Err(line.error(
+            rest,
+            format!("Unexpected {:?} at the end of the line", rest),
+        )),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+            Err(line.error(e.input, e.message.unwrap_or_else(|| expected.to_string())))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(line.error(&line.text[line.text.len()..], expected)),
+    }
+}
+
+/// Parse the whole of a string, such as a value given as a parameter, describing a failure by the
+/// parser's message if it gave one or by `expected` otherwise
+pub fn all<'a, T>(
+    s: &'a str,
+    mut parser: impl FnMut(&'a str) -> Parsed<'a, T>,
+    expected: &str,
+) -> Result<T, String> {
+    match parser(s) {
+        Ok(("", value)) => Ok(value),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+            Err(e.message.unwrap_or_else(|| expected.to_string()))
+        }
+        _ => Err(expected.to_string()),
+    }
+}
+
+/// Parse the digits recognised by `digits` as a number, describing numbers which don't fit
+fn number<'a, T>(
+    mut digits: impl FnMut(&'a str) -> Parsed<'a, &'a str>,
+) -> impl FnMut(&'a str) -> Parsed<'a, T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    move |s| {
+        let (rest, digits) = digits(s)?;
+
+        match digits.parse() {
+            Ok(value) => Ok((rest, value)),
+            Err(e) => Err(nom::Err::Error(Failure {
+                input: s,
+                message: Some(format!("Invalid number {:?}: {}", digits, e)),
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::lines;
+    use nom::{combinator::map_res, error::context};
+
+    fn parse<'a, T>(
+        text: &'a str,
+        parser: impl FnMut(&'a str) -> Parsed<'a, T>,
+    ) -> Result<T, (usize, String)> {
+        let source = lines(1, text).next().unwrap();
+
+        line(&source, parser, "Expected something").map_err(|e| match e {
+            Error::Parse {
+                column, message, ..
+            } => (column, message),
+            e => panic!("Unexpected error {}", e),
+        })
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(parse("123", integer::<u32>), Ok(123));
+        assert_eq!(parse("+7", signed::<i32>), Ok(7));
+        assert_eq!(parse(" -3 ", signed::<i32>), Ok(-3));
+        assert_eq!(
+            parse("-3", integer::<u32>),
+            Err((1, "Expected something".to_string()))
+        );
+        assert_eq!(
+            parse("  300", integer::<u8>),
+            Err((
+                3,
+                "Invalid number \"300\": number too large to fit in target type".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(parse("cats: 7", key_value(integer::<u32>)), Ok(("cats", 7)));
+        assert_eq!(
+            parse("Hit Points: 12", field("Hit Points", integer::<u32>)),
+            Ok(12)
+        );
+        assert_eq!(
+            parse("Damage: 12", field("Armor", integer::<u32>)),
+            Err((1, "Expected something".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_comma_pair() {
+        assert_eq!(parse("3,4", comma_pair(integer::<u32>)), Ok((3, 4)));
+        assert_eq!(
+            parse("3,4,5", comma_pair(integer::<u32>)),
+            Err((4, "Unexpected \",5\" at the end of the line".to_string()))
+        );
+        assert_eq!(
+            parse("3;4", comma_pair(integer::<u32>)),
+            Err((2, "Expected something".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_messages() {
+        let even = |n: u32| match n % 2 {
+            0 => Ok(n),
+            _ => Err(format!("{} is odd", n)),
+        };
+
+        assert_eq!(
+            parse("x 5", pair(tag("x "), map_res(integer, even))),
+            Err((3, "5 is odd".to_string()))
+        );
+        assert_eq!(
+            parse(
+                "x y",
+                pair(tag("x "), context("Expected a number", integer::<u32>))
+            ),
+            Err((3, "Expected a number".to_string()))
+        );
+        assert_eq!(parse("Ab", name), Ok("Ab"));
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(
+            all("1,2", comma_pair(integer::<u8>), "Expected a pair"),
+            Ok((1, 2))
+        );
+        assert_eq!(
+            all("1,2 ", comma_pair(integer::<u8>), "Expected a pair"),
+            Err("Expected a pair".to_string())
+        );
+        assert_eq!(
+            all("1,256", comma_pair(integer::<u8>), "Expected a pair"),
+            Err("Invalid number \"256\": number too large to fit in target type".to_string())
+        );
+    }
+}
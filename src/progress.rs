@@ -0,0 +1,155 @@
+//! Progress reporting and cancellation for long-running solvers
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Returned by a solver which was stopped before it found an answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cancelled")
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    done: AtomicU64,
+    total: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+/// Handle which long-running solvers report their progress to and check for cancellation. Clones
+/// share the same state, so one clone can be passed to the solver while another is used to watch
+/// its progress or cancel it from a different thread.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    state: Arc<State>,
+    deadline: Option<Instant>,
+}
+
+impl Progress {
+    /// Create a handle with no total, which is never cancelled unless asked to be
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the solver automatically once the given time has passed
+    pub fn with_budget(budget: Duration) -> Self {
+        Progress {
+            state: Arc::default(),
+            deadline: Some(Instant::now() + budget),
+        }
+    }
+
+    /// Ask the solver to stop at the next opportunity
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Check whether the solver has been cancelled or has run out of time
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Return an error if the solver should stop
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set the number of steps the solver expects to take, which may be an upper bound
+    pub fn set_total(&self, total: u64) {
+        self.state.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Record that a step has been completed, returning an error if the solver should stop
+    pub fn tick(&self) -> Result<(), Cancelled> {
+        self.state.done.fetch_add(1, Ordering::Relaxed);
+        self.check()
+    }
+
+    /// Fraction of the expected steps which have been completed, if the solver has set a total
+    pub fn fraction(&self) -> Option<f64> {
+        let total = self.state.total.load(Ordering::Relaxed);
+        let done = self.state.done.load(Ordering::Relaxed);
+
+        match total {
+            0 => None,
+            _ => Some((done as f64 / total as f64).min(1.0)),
+        }
+    }
+}
+
+/// Number of orderings of `n` items, saturating at `u64::MAX`, for solvers which try every
+/// permutation
+pub fn factorial(n: usize) -> u64 {
+    (1..=n as u64)
+        .try_fold(1u64, |acc, i| acc.checked_mul(i))
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new();
+        assert_eq!(progress.fraction(), None);
+
+        progress.set_total(4);
+        progress.tick().unwrap();
+        assert_eq!(progress.fraction(), Some(0.25));
+
+        for _ in 0..5 {
+            progress.tick().unwrap();
+        }
+
+        assert_eq!(progress.fraction(), Some(1.0));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let progress = Progress::new();
+        let handle = progress.clone();
+
+        thread::spawn(move || handle.cancel()).join().unwrap();
+
+        assert!(progress.is_cancelled());
+        assert_eq!(progress.tick(), Err(Cancelled));
+    }
+
+    #[test]
+    fn test_factorial() {
+        assert_eq!(factorial(0), 1);
+        assert_eq!(factorial(8), 40320);
+        assert_eq!(factorial(30), u64::MAX);
+    }
+
+    #[test]
+    fn test_budget() {
+        assert_eq!(
+            Progress::with_budget(Duration::ZERO).check(),
+            Err(Cancelled)
+        );
+        assert_eq!(
+            Progress::with_budget(Duration::from_secs(60)).check(),
+            Ok(())
+        );
+    }
+}
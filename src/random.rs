@@ -0,0 +1,704 @@
+//! Generating random but valid puzzle inputs
+
+use itertools::Itertools;
+use std::{
+    cmp::Ordering::{self, Greater, Less},
+    ops::RangeInclusive,
+};
+
+/// Small deterministic random number generator (SplitMix64), so that a seed always produces the
+/// same input regardless of platform or dependency versions
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Create a generator which always produces the same values for the same seed
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    /// Random value across the whole range of `u64`
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random value in the given inclusive range
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Empty range {}..={}", start, end);
+
+        start + (self.next_u64() % (end - start + 1) as u64) as usize
+    }
+
+    /// Random signed value in the given inclusive range
+    pub fn signed(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Empty range {}..={}", start, end);
+
+        start + (self.next_u64() % (end - start + 1) as u64) as i64
+    }
+
+    /// Return true with a probability of `numerator / denominator`
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.next_u64() % denominator < numerator
+    }
+
+    /// Pick a random item from a non-empty slice
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..=items.len() - 1)]
+    }
+
+    /// Put the items in a random order
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+/// Days whose inputs have a fixed shape, so [generate] ignores the size: an eight letter password,
+/// a number of presents and the boss's stats
+pub const FIXED_SIZE: [u8; 4] = [11, 20, 21, 22];
+
+/// Generate a random but valid input for the given day, or `None` if there is no generator for
+/// that day. `size` is the number of lines or the length of single-line inputs, the number of
+/// ingredients for day 15 and the width and height of the grid for day 18. It is ignored by the
+/// days in [FIXED_SIZE].
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+
+    Some(match day {
+        1 => day1(rng, size),
+        2 => lines(rng, size, |rng| {
+            format!("{}x{}x{}", rng.range(1..=30), rng.range(1..=30), rng.range(1..=30))
+        }),
+        3 => (0..size).map(|_| *rng.choose(&['^', 'v', '<', '>'])).collect(),
+        4 => letters(rng, size, "abcdefghijklmnopqrstuvwxyz"),
+        5 => lines(rng, size, |rng| letters(rng, 16, "abcdefghijklmnopqrstuvwxyz")),
+        6 => lines(rng, size, day6_instruction),
+        7 => day7(rng, size),
+        8 => lines(rng, size, day8_string),
+        9 => pairs(rng, size.max(2), false, |rng, a, b| {
+            format!("{} to {} = {}", a, b, rng.range(1..=200))
+        }),
+        10 => day10(rng, size),
+        11 => letters(rng, 8, "abcdefghjkmnpqrstuvwxyz"),
+        12 => day12_value(rng, &mut { size }, 0),
+        13 => pairs(rng, size.max(2), true, |rng, a, b| {
+            let direction = if rng.chance(1, 2) { "gain" } else { "lose" };
+            let units = rng.range(0..=100);
+            format!(
+                "{} would {} {} happiness units by sitting next to {}.",
+                a, direction, units, b
+            )
+        }),
+        14 => (0..size)
+            .map(|i| {
+                format!(
+                    "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.",
+                    name(i),
+                    rng.range(1..=30),
+                    rng.range(1..=20),
+                    rng.range(10..=200)
+                )
+            })
+            .join("\n"),
+        15 => (0..size)
+            .map(|i| {
+                format!(
+                    "{}: capacity {}, durability {}, flavor {}, texture {}, calories {}",
+                    name(i),
+                    rng.signed(-5..=5),
+                    rng.signed(-5..=5),
+                    rng.signed(-5..=5),
+                    rng.signed(-5..=5),
+                    rng.range(1..=9)
+                )
+            })
+            .join("\n"),
+        16 => day16(rng, size.max(2)),
+        17 => day17(rng, size.max(6)),
+        18 => (0..size)
+            .map(|_| letters(rng, size, "#."))
+            .join("\n"),
+        19 => day19(rng, size),
+        20 => rng.range(1_000..=40_000_000).to_string(),
+        // bosses which beat a hero with only a dagger but lose to the best items, so both parts
+        // have an answer
+        21 => format!(
+            "Hit Points: {}\nDamage: {}\nArmor: {}",
+            rng.range(100..=150),
+            rng.range(6..=10),
+            rng.range(0..=4)
+        ),
+        22 => format!(
+            "Hit Points: {}\nDamage: {}",
+            rng.range(10..=60),
+            rng.range(4..=10)
+        ),
+        23 => day23(rng, size),
+        24 => day24(rng, size.max(12)),
+        25 => format!(
+            "To continue, please consult the code grid in the manual.  Enter the code at row {}, column {}.",
+            rng.range(1..=size),
+            rng.range(1..=size)
+        ),
+        _ => return None,
+    })
+}
+
+/// Generate the given number of lines
+fn lines(rng: &mut Rng, count: usize, mut line: impl FnMut(&mut Rng) -> String) -> String {
+    (0..count).map(|_| line(rng)).join("\n")
+}
+
+/// Generate a string of random characters from the given alphabet
+fn letters(rng: &mut Rng, length: usize, alphabet: &str) -> String {
+    let alphabet = alphabet.chars().collect::<Vec<_>>();
+    (0..length).map(|_| *rng.choose(&alphabet)).collect()
+}
+
+/// Generate a unique capitalised name for every index, e.g. `A`, `B`, ..., `Aa`, `Ba`, ...
+fn name(i: usize) -> String {
+    let mut name = ((b'A' + (i % 26) as u8) as char).to_string();
+
+    if i >= 26 {
+        name.push_str(&wire(i / 26 - 1));
+    }
+
+    name
+}
+
+/// Generate a unique lowercase wire name for every index, e.g. `a`, `b`, ..., `aa`, `ab`, ...
+fn wire(i: usize) -> String {
+    let mut i = i;
+    let mut name = Vec::new();
+
+    loop {
+        name.push(b'a' + (i % 26) as u8);
+
+        if i < 26 {
+            break;
+        }
+
+        i = i / 26 - 1;
+    }
+
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+/// Generate a line for every pair of people or places, in one or both directions
+fn pairs(
+    rng: &mut Rng,
+    count: usize,
+    both_directions: bool,
+    mut line: impl FnMut(&mut Rng, &str, &str) -> String,
+) -> String {
+    let names = (0..count).map(name).collect::<Vec<_>>();
+
+    names
+        .iter()
+        .tuple_combinations()
+        .flat_map(|(a, b)| {
+            let mut lines = vec![line(rng, a, b)];
+
+            if both_directions {
+                lines.push(line(rng, b, a));
+            }
+
+            lines
+        })
+        .join("\n")
+}
+
+fn day1(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::with_capacity(size);
+    let mut floor = 0;
+    let mut basement = false;
+
+    for _ in 0..size {
+        let up = rng.chance(1, 2);
+        floor += if up { 1 } else { -1 };
+        basement |= floor == -1;
+        input.push(if up { '(' } else { ')' });
+    }
+
+    // part 2 needs Santa to enter the basement at some point
+    while !basement {
+        floor -= 1;
+        basement = floor == -1;
+        input.push(')');
+    }
+
+    input
+}
+
+fn day6_instruction(rng: &mut Rng) -> String {
+    let action = *rng.choose(&["turn on", "turn off", "toggle"]);
+    let (x1, x2) = (rng.range(0..=999), rng.range(0..=999));
+    let (y1, y2) = (rng.range(0..=999), rng.range(0..=999));
+
+    format!(
+        "{} {},{} through {},{}",
+        action,
+        x1.min(x2),
+        y1.min(y2),
+        x1.max(x2),
+        y1.max(y2)
+    )
+}
+
+/// Generate a circuit where wire `a` depends on every other wire only through wires with a higher
+/// index, so that the circuit never contains a loop
+fn day7(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(2);
+
+    let mut instructions = (0..count)
+        .map(|i| {
+            let source = |rng: &mut Rng| {
+                if i + 1 < count && rng.chance(4, 5) {
+                    wire(rng.range(i + 1..=count - 1))
+                } else {
+                    rng.range(0..=u16::MAX as usize).to_string()
+                }
+            };
+
+            let operation = match rng.range(0..=5) {
+                0 => source(rng),
+                1 => format!("NOT {}", source(rng)),
+                2 => format!("{} AND {}", source(rng), source(rng)),
+                3 => format!("{} OR {}", source(rng), source(rng)),
+                4 => format!("{} LSHIFT {}", source(rng), rng.range(0..=15)),
+                _ => format!("{} RSHIFT {}", source(rng), rng.range(0..=15)),
+            };
+
+            format!("{} -> {}", operation, wire(i))
+        })
+        .collect::<Vec<_>>();
+
+    rng.shuffle(&mut instructions);
+    instructions.join("\n")
+}
+
+fn day8_string(rng: &mut Rng) -> String {
+    let length = rng.range(0..=20);
+    let mut s = String::from("\"");
+
+    for _ in 0..length {
+        match rng.range(0..=9) {
+            0 => s.push_str("\\\\"),
+            1 => s.push_str("\\\""),
+            2 => s.push_str(&format!("\\x{:02x}", rng.range(0..=255))),
+            _ => s.push_str(&letters(rng, 1, "abcdefghijklmnopqrstuvwxyz")),
+        }
+    }
+
+    s.push('"');
+    s
+}
+
+/// Generate digits 1-3 without any digit repeated more than 3 times in a row, so that every
+/// look-and-say count stays a single digit
+fn day10(rng: &mut Rng, size: usize) -> String {
+    let mut digits = Vec::<u8>::with_capacity(size);
+
+    while digits.len() < size {
+        let digit = b'0' + rng.range(1..=3) as u8;
+
+        if digits.len() >= 3 && digits[digits.len() - 3..].iter().all(|&d| d == digit) {
+            continue;
+        }
+
+        digits.push(digit);
+    }
+
+    String::from_utf8(digits).unwrap()
+}
+
+/// Generate a JSON document containing roughly `budget` values
+fn day12_value(rng: &mut Rng, budget: &mut usize, depth: usize) -> String {
+    *budget = budget.saturating_sub(1);
+
+    let container = depth == 0 || (*budget > 0 && depth < 8 && rng.chance(1, 3));
+
+    if !container {
+        return if rng.chance(1, 2) {
+            rng.signed(-200..=200).to_string()
+        } else {
+            format!(
+                "{:?}",
+                rng.choose(&["red", "green", "blue", "orange", "violet"])
+            )
+        };
+    }
+
+    let length = rng.range(0..=(*budget).min(8));
+    let values = (0..length)
+        .map(|_| day12_value(rng, budget, depth + 1))
+        .collect::<Vec<_>>();
+
+    if rng.chance(1, 2) {
+        format!("[{}]", values.join(","))
+    } else {
+        let members = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| format!("\"{}\":{}", wire(i), value))
+            .join(",");
+
+        format!("{{{}}}", members)
+    }
+}
+
+/// Things detected by the MFCSAM, with the amount for part 1 and the comparison for part 2
+const MFCSAM: [(&str, u32, Option<Ordering>); 10] = [
+    ("children", 3, None),
+    ("cats", 7, Some(Greater)),
+    ("samoyeds", 2, None),
+    ("pomeranians", 3, Some(Less)),
+    ("akitas", 0, None),
+    ("vizslas", 0, None),
+    ("goldfish", 5, Some(Less)),
+    ("trees", 3, Some(Greater)),
+    ("cars", 2, None),
+    ("perfumes", 1, None),
+];
+
+/// Generate Sues where exactly one matches the MFCSAM readings in each part. Most Sues remember
+/// three things like the real input, but some remember more or fewer.
+fn day16(rng: &mut Rng, size: usize) -> String {
+    let matches = |things: &[(usize, u32)], exact: bool| {
+        things.iter().all(|&(i, amount)| match MFCSAM[i] {
+            (_, expected, Some(ordering)) if !exact => amount.cmp(&expected) == ordering,
+            (_, expected, _) => amount == expected,
+        })
+    };
+
+    let pick_things = |rng: &mut Rng, count: usize| {
+        let mut indices = (0..MFCSAM.len()).collect::<Vec<_>>();
+        rng.shuffle(&mut indices);
+        indices.truncate(count);
+        indices
+    };
+
+    let mut sues = (0..size)
+        .map(|_| loop {
+            let count = *rng.choose(&[1, 2, 3, 3, 3, 3, 4, 5]);
+            let things = pick_things(rng, count)
+                .into_iter()
+                .map(|i| (i, rng.range(0..=10) as u32))
+                .collect::<Vec<_>>();
+
+            if !matches(&things, true) && !matches(&things, false) {
+                break things;
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // both matching Sues need one of the ranged readings so that each only matches one part
+    let planted = |rng: &mut Rng, exact: bool| loop {
+        let indices = pick_things(rng, 3);
+
+        if indices.iter().all(|&i| MFCSAM[i].2.is_none()) {
+            continue;
+        }
+
+        return indices
+            .into_iter()
+            .map(|i| match MFCSAM[i] {
+                (_, expected, Some(Greater)) if !exact => (i, expected + rng.range(1..=3) as u32),
+                (_, expected, Some(Less)) if !exact => {
+                    (i, rng.range(0..=expected as usize - 1) as u32)
+                }
+                (_, expected, _) => (i, expected),
+            })
+            .collect::<Vec<_>>();
+    };
+
+    let first = rng.range(0..=size - 1);
+    let second = (first + rng.range(1..=size - 1)) % size;
+    sues[first] = planted(rng, true);
+    sues[second] = planted(rng, false);
+
+    sues.iter()
+        .enumerate()
+        .map(|(i, things)| {
+            let things = things
+                .iter()
+                .map(|&(thing, amount)| format!("{}: {}", MFCSAM[thing].0, amount))
+                .join(", ");
+
+            format!("Sue {}: {}", i + 1, things)
+        })
+        .join("\n")
+}
+
+/// Generate containers where at least one combination holds exactly 150 litres
+fn day17(rng: &mut Rng, size: usize) -> String {
+    let mut containers = loop {
+        let mut parts = (0..rng.range(3..=5))
+            .map(|_| rng.range(5..=50))
+            .collect::<Vec<_>>();
+        let total = parts.iter().sum::<usize>();
+
+        if (total + 5..=total + 50).contains(&150) {
+            parts.push(150 - total);
+            break parts;
+        }
+    };
+
+    while containers.len() < size {
+        containers.push(rng.range(5..=50));
+    }
+
+    rng.shuffle(&mut containers);
+    containers.iter().join("\n")
+}
+
+/// Elements which can be replaced, in the style of the real input
+const ELEMENTS: [&str; 12] = [
+    "Al", "B", "Ca", "F", "H", "Mg", "N", "O", "P", "Si", "Th", "Ti",
+];
+
+/// Generate replacement rules, then build the medicine molecule by applying `size` random
+/// replacements starting from `e`
+fn day19(rng: &mut Rng, size: usize) -> String {
+    let mut rules = Vec::new();
+
+    for &input in ["e"].iter().chain(ELEMENTS.iter()) {
+        for _ in 0..rng.range(1..=3) {
+            let mut output = vec![*rng.choose(&ELEMENTS), *rng.choose(&ELEMENTS)];
+
+            if input != "e" && rng.chance(1, 3) {
+                // wrap the second element like `CaRnFAr` or `CaRnFYMgAr`
+                output.insert(1, "Rn");
+
+                if rng.chance(1, 2) {
+                    output.extend_from_slice(&["Y", *rng.choose(&ELEMENTS)]);
+                }
+
+                output.push("Ar");
+            }
+
+            rules.push((input, output));
+        }
+    }
+
+    let mut molecule = vec!["e"];
+
+    for _ in 0..size {
+        let replaceable = molecule
+            .iter()
+            .enumerate()
+            .filter(|(_, element)| rules.iter().any(|(input, _)| input == *element))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let i = *rng.choose(&replaceable);
+        let options = rules
+            .iter()
+            .filter(|(input, _)| *input == molecule[i])
+            .collect::<Vec<_>>();
+        let (_, output) = rng.choose(&options);
+
+        molecule.splice(i..=i, output.iter().copied());
+    }
+
+    let rules = rules
+        .iter()
+        .map(|(input, output)| format!("{} => {}", input, output.concat()))
+        .join("\n");
+
+    format!("{}\n\n{}", rules, molecule.concat())
+}
+
+/// Generate a program which only ever jumps forwards, so that it always terminates
+fn day23(rng: &mut Rng, size: usize) -> String {
+    let mut tripled = 0;
+
+    (0..size)
+        .map(|i| {
+            let register = *rng.choose(&["a", "b"]);
+            let offset = rng.range(1..=(size - i).min(10));
+
+            match rng.range(0..=5) {
+                0 => format!("hlf {}", register),
+                // limit the number of triples so that the registers can't overflow
+                1 if tripled < 20 => {
+                    tripled += 1;
+                    format!("tpl {}", register)
+                }
+                1 | 2 => format!("inc {}", register),
+                3 => format!("jmp +{}", offset),
+                4 => format!("jie {}, +{}", register, offset),
+                _ => format!("jio {}, +{}", register, offset),
+            }
+        })
+        .join("\n")
+}
+
+/// Generate packages which can be split into 12 groups of equal weight, so that they can always
+/// be split into 3 or 4 groups of equal weight
+fn day24(rng: &mut Rng, size: usize) -> String {
+    let mut groups = vec![Vec::new(); 12];
+
+    for i in 0..size {
+        groups[i % 12].push(rng.range(1..=100));
+    }
+
+    // replace the last package of each group so that every group weighs the same
+    let partial = |group: &Vec<usize>| group[..group.len() - 1].iter().sum::<usize>();
+    let weight = groups.iter().map(partial).max().unwrap() + rng.range(1..=100);
+
+    for group in groups.iter_mut() {
+        let last = group.len() - 1;
+        group[last] = weight - partial(group);
+    }
+
+    let mut packages = groups.concat();
+    rng.shuffle(&mut packages);
+    packages.iter().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    // Some helpers are only used by tests of days which may not be compiled in
+    #![cfg_attr(not(feature = "all-days"), allow(unused_imports, dead_code))]
+
+    use super::*;
+    use crate::{solver, Part};
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(42);
+        let values = (0..1000).map(|_| rng.range(3..=7)).collect::<Vec<_>>();
+
+        assert!(values.iter().all(|v| (3..=7).contains(v)));
+        assert_eq!(values.iter().unique().count(), 5);
+        assert_eq!(Rng::new(42).next_u64(), Rng::new(42).next_u64());
+        assert_ne!(Rng::new(42).next_u64(), Rng::new(43).next_u64());
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            [0, 1, 25, 26, 27, 701, 702]
+                .iter()
+                .map(|&i| wire(i))
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "z", "aa", "ab", "zz", "aaa"]
+        );
+        assert_eq!(
+            [0, 25, 26, 27, 52]
+                .iter()
+                .map(|&i| name(i))
+                .collect::<Vec<_>>(),
+            vec!["A", "Z", "Aa", "Ba", "Ab"]
+        );
+    }
+
+    #[test]
+    fn test_deterministic() {
+        assert_eq!(generate(7, 50, 1), generate(7, 50, 1));
+        assert_ne!(generate(7, 50, 1), generate(7, 50, 2));
+        assert_eq!(generate(26, 50, 1), None);
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(generate(4, 12, 1).unwrap().len(), 12);
+        assert_eq!(generate(15, 3, 1).unwrap().lines().count(), 3);
+
+        let grid = generate(18, 6, 1).unwrap();
+        assert_eq!(grid.lines().count(), 6);
+        assert!(grid.lines().all(|line| line.len() == 6));
+
+        for &day in FIXED_SIZE.iter() {
+            assert_eq!(generate(day, 1, 1), generate(day, 100, 1));
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_parse() {
+        for solver in solver::solvers() {
+            for &size in &[1, 10, 100] {
+                for seed in 0..5 {
+                    let input = generate(solver.day(), size, seed).unwrap();
+
+                    if let Err(e) = solver.parse(&input) {
+                        panic!("Day {} size {} seed {}: {}", solver.day(), size, seed, e);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_generated_inputs_solve() {
+        // skip the days which take too long to run in a debug build
+        let days = [
+            (1, 100),
+            (2, 100),
+            (3, 100),
+            (5, 100),
+            (6, 5),
+            (7, 100),
+            (8, 100),
+            (9, 5),
+            (12, 100),
+            (13, 5),
+            (14, 10),
+            (16, 100),
+            (17, 12),
+            (21, 1),
+            (23, 100),
+            (25, 100),
+        ];
+
+        for &(day, size) in days.iter() {
+            let solver = solver::solver(day).unwrap();
+
+            for seed in 0..3 {
+                let input = generate(day, size, seed).unwrap();
+                let parsed = solver.parse(&input).unwrap();
+
+                for &part in [Part::One, Part::Two].iter() {
+                    if solver.has_part(part) {
+                        if let Err(e) = solver::try_solve(solver, part, parsed.as_ref()) {
+                            panic!("Day {} part {} seed {}: {}", day, part, seed, e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day16")]
+    fn test_day16_has_one_match_per_part() {
+        let input = generate(16, 50, 3).unwrap();
+        let sues = crate::day16::generator(&input).unwrap();
+
+        assert_ne!(crate::day16::part1(&sues), crate::day16::part2(&sues));
+    }
+
+    #[test]
+    fn test_day24_splits_evenly() {
+        let input = generate(24, 30, 5).unwrap();
+        let total = input
+            .lines()
+            .map(|l| l.parse::<usize>().unwrap())
+            .sum::<usize>();
+
+        assert_eq!(input.lines().count(), 30);
+        assert_eq!(total % 12, 0);
+    }
+}
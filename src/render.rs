@@ -0,0 +1,419 @@
+//! Rendering grid-based days as PGM or PPM images
+
+#[cfg(feature = "day18")]
+use crate::day18;
+#[cfg(feature = "day3")]
+use crate::day3;
+#[cfg(feature = "day6")]
+use crate::day6;
+use crate::{params::Params, Part};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Grayscale raster image, where each pixel is a value between 0 and `max`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width in pixels
+    pub width: usize,
+
+    /// Height in pixels
+    pub height: usize,
+
+    /// Value of the brightest possible pixel, which is at least 1
+    pub max: u8,
+
+    /// Pixel values in rows from the top left
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Create an image with every pixel set to 0
+    pub fn new(width: usize, height: usize, max: u8) -> Self {
+        Image {
+            width,
+            height,
+            max: max.max(1),
+            pixels: vec![0; width * height],
+        }
+    }
+
+    /// Create an image from pixel values in rows from the top left, using the largest value as
+    /// the maximum
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+        assert_eq!(pixels.len(), width * height, "Wrong number of pixels");
+
+        Image {
+            width,
+            height,
+            max: pixels.iter().copied().max().unwrap_or(0).max(1),
+            pixels,
+        }
+    }
+
+    /// Value of the pixel at the given column and row
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Set a pixel, raising the maximum if necessary
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        self.pixels[y * self.width + x] = value;
+        self.max = self.max.max(value);
+    }
+
+    /// Enlarge the image so that each pixel becomes a `factor` by `factor` square
+    pub fn scaled(&self, factor: usize) -> Self {
+        let mut pixels = Vec::with_capacity(self.pixels.len() * factor * factor);
+
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let scaled = row
+                .iter()
+                .flat_map(|&p| std::iter::repeat_n(p, factor))
+                .collect::<Vec<_>>();
+
+            for _ in 0..factor {
+                pixels.extend_from_slice(&scaled);
+            }
+        }
+
+        Image {
+            width: self.width * factor,
+            height: self.height * factor,
+            max: self.max,
+            pixels,
+        }
+    }
+
+    /// Encode the image as a binary PGM or PPM file
+    pub fn encode(&self, format: Format) -> Vec<u8> {
+        let magic = match format {
+            Format::Pgm => "P5",
+            Format::Ppm => "P6",
+        };
+
+        let mut bytes = format!("{}\n{} {}\n", magic, self.width, self.height).into_bytes();
+
+        match format {
+            Format::Pgm => {
+                bytes.extend(format!("{}\n", self.max).bytes());
+                bytes.extend_from_slice(&self.pixels);
+            }
+            Format::Ppm => {
+                bytes.extend(b"255\n");
+                bytes.extend(self.pixels.iter().flat_map(|&p| heat(p, self.max)));
+            }
+        }
+
+        bytes
+    }
+
+    /// Write the image to a file, choosing the format from its extension
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let format = Format::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Unsupported image format {}, use .pgm or .ppm",
+                    path.display()
+                ),
+            )
+        })?;
+
+        fs::File::create(path)?.write_all(&self.encode(format))
+    }
+}
+
+/// Map a value onto a black, red, yellow, white colour scale
+fn heat(value: u8, max: u8) -> [u8; 3] {
+    let t = value as f64 / max as f64 * 3.0;
+    let channel = |offset: f64| ((t - offset).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    [channel(0.0), channel(1.0), channel(2.0)]
+}
+
+/// Raster formats which images can be written in
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Format {
+    /// Grayscale portable graymap
+    Pgm,
+
+    /// Colour portable pixmap, shading values from black through red and yellow to white
+    Ppm,
+}
+
+impl Format {
+    /// Choose a format from the extension of a file name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "pgm" => Some(Format::Pgm),
+            "ppm" => Some(Format::Ppm),
+            _ => None,
+        }
+    }
+
+    /// File extension used for the format, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Pgm => "pgm",
+            Format::Ppm => "ppm",
+        }
+    }
+}
+
+/// Receives an image of the state of a grid after each step of a solver
+pub trait Recorder {
+    /// Record a step. The image is only rendered if the recorder wants this frame.
+    fn frame(&mut self, render: &dyn Fn() -> Image);
+}
+
+/// [Recorder] which ignores every step, used when solving normally
+pub struct NoopRecorder;
+
+impl Recorder for NoopRecorder {
+    fn frame(&mut self, _: &dyn Fn() -> Image) {}
+}
+
+/// [Recorder] which writes numbered frames like `frame-0001.pgm` to a directory
+#[derive(Debug)]
+pub struct FrameWriter {
+    dir: PathBuf,
+    format: Format,
+    every: usize,
+    scale: usize,
+    steps: usize,
+    frames: usize,
+    error: Option<io::Error>,
+}
+
+impl FrameWriter {
+    /// Write every step to the given directory, which must already exist
+    pub fn new(dir: &Path, format: Format) -> Self {
+        FrameWriter {
+            dir: dir.to_path_buf(),
+            format,
+            every: 1,
+            scale: 1,
+            steps: 0,
+            frames: 0,
+            error: None,
+        }
+    }
+
+    /// Only write one frame in every `every` steps, starting with the first
+    pub fn every(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    /// Enlarge each frame by the given factor
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Return the number of frames written, or the first error encountered while writing them
+    pub fn finish(self) -> io::Result<usize> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.frames),
+        }
+    }
+}
+
+impl Recorder for FrameWriter {
+    fn frame(&mut self, render: &dyn Fn() -> Image) {
+        let step = self.steps;
+        self.steps += 1;
+
+        if self.error.is_some() || !step.is_multiple_of(self.every) {
+            return;
+        }
+
+        self.frames += 1;
+
+        let path = self.dir.join(format!(
+            "frame-{:04}.{}",
+            self.frames,
+            self.format.extension()
+        ));
+        let image = render().scaled(self.scale);
+
+        if let Err(e) = fs::write(&path, image.encode(self.format)) {
+            self.error = Some(e);
+        }
+    }
+}
+
+/// Parse the input for a grid-based day and render its final state using the given puzzle
+/// parameters, recording each step
+#[cfg_attr(not(feature = "day18"), allow(unused_variables))]
+pub fn render(
+    day: u8,
+    part: Part,
+    input: &str,
+    params: &Params,
+    recorder: &mut dyn Recorder,
+) -> Result<Image, String> {
+    match day {
+        #[cfg(feature = "day3")]
+        3 => {
+            let input = day3::generator(input).map_err(|e| format!("{:#}", e))?;
+            Ok(day3::render(&input, part, recorder))
+        }
+        #[cfg(feature = "day6")]
+        6 => {
+            let input = day6::generator(input).map_err(|e| format!("{:#}", e))?;
+            day6::render(&input, part, recorder).map_err(|e| e.to_string())
+        }
+        #[cfg(feature = "day18")]
+        18 => {
+            let input = day18::generator(input).map_err(|e| format!("{:#}", e))?;
+            Ok(day18::render(&input, part, &params.day18, recorder))
+        }
+        _ => Err(format!("No renderer for day {}", day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Some helpers are only used by tests of days which may not be compiled in
+    #![cfg_attr(not(feature = "all-days"), allow(unused_imports, dead_code))]
+
+    use super::*;
+    use std::env;
+
+    /// Collects every frame in memory
+    struct Frames(Vec<Image>);
+
+    impl Recorder for Frames {
+        fn frame(&mut self, render: &dyn Fn() -> Image) {
+            self.0.push(render());
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut image = Image::new(2, 1, 1);
+        image.set(1, 0, 4);
+
+        assert_eq!(image.encode(Format::Pgm), b"P5\n2 1\n4\n\x00\x04");
+        assert_eq!(
+            image.encode(Format::Ppm),
+            b"P6\n2 1\n255\n\x00\x00\x00\xff\xff\xff"
+        );
+    }
+
+    #[test]
+    fn test_scaled() {
+        let image = Image::from_pixels(2, 1, vec![1, 2]).scaled(2);
+
+        assert_eq!((image.width, image.height, image.max), (4, 2, 2));
+        assert_eq!(image.pixels, vec![1, 1, 2, 2, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a/b.ppm")), Some(Format::Ppm));
+        assert_eq!(Format::from_path(Path::new("b.pgm")), Some(Format::Pgm));
+        assert_eq!(Format::from_path(Path::new("b.png")), None);
+        assert!(Image::new(1, 1, 1).write(Path::new("b.png")).is_err());
+    }
+
+    #[test]
+    fn test_frame_writer() {
+        let dir = env::temp_dir().join("advent-2015-render-frames");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut writer = FrameWriter::new(&dir, Format::Pgm).every(2).scale(3);
+
+        for _ in 0..5 {
+            writer.frame(&|| Image::new(1, 1, 1));
+        }
+
+        assert_eq!(writer.finish().unwrap(), 3);
+        assert_eq!(
+            fs::read(dir.join("frame-0003.pgm")).unwrap(),
+            b"P5\n3 3\n1\n\0\0\0\0\0\0\0\0\0"
+        );
+        assert!(!dir.join("frame-0004.pgm").exists());
+    }
+
+    #[test]
+    fn test_frame_writer_error() {
+        let dir = env::temp_dir().join("advent-2015-render-missing");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut writer = FrameWriter::new(&dir, Format::Pgm);
+        writer.frame(&|| Image::new(1, 1, 1));
+
+        assert!(writer.finish().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "day3")]
+    fn test_render_day3() {
+        let mut frames = Frames(Vec::new());
+        let image = render(3, Part::Two, "^v^v^v^v^v", &Params::default(), &mut frames).unwrap();
+
+        assert_eq!(frames.0.len(), 10);
+        assert_eq!((image.width, image.height, image.max), (1, 11, 2));
+        assert_eq!(image.get(0, 5), 2);
+        assert!(image.pixels.iter().all(|&p| p > 0));
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn test_render_day6() {
+        let input = "turn on 0,0 through 2,1\ntoggle 1,1 through 3,1";
+        let image = render(6, Part::One, input, &Params::default(), &mut NoopRecorder).unwrap();
+
+        assert_eq!(image.get(0, 1), 1);
+        assert_eq!(image.get(1, 1), 0);
+        assert_eq!(image.get(3, 1), 1);
+        assert_eq!(image.pixels.iter().filter(|&&p| p > 0).count(), 5);
+
+        let image = render(6, Part::Two, input, &Params::default(), &mut NoopRecorder).unwrap();
+        assert_eq!((image.get(1, 1), image.max), (3, 3));
+    }
+
+    #[test]
+    #[cfg(feature = "day18")]
+    fn test_render_day18() {
+        let mut frames = Frames(Vec::new());
+        let image = render(18, Part::Two, "#.\n.#", &Params::default(), &mut frames).unwrap();
+
+        assert_eq!(frames.0.len(), 101);
+        assert_eq!((image.width, image.height), (100, 100));
+        assert_eq!(frames.0[0].get(0, 0), 1);
+        assert_eq!(frames.0[0].get(1, 0), 0);
+        assert_eq!(image.pixels.iter().filter(|&&p| p > 0).count(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "day18")]
+    fn test_render_day18_params() {
+        let mut frames = Frames(Vec::new());
+        let mut params = Params::default();
+        params.day18.size = 2;
+        params.day18.steps = 3;
+
+        let image = render(18, Part::One, "##\n#.", &params, &mut frames).unwrap();
+
+        assert_eq!(frames.0.len(), 4);
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.pixels, vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_render_errors() {
+        assert_eq!(
+            render(4, Part::One, "abc", &Params::default(), &mut NoopRecorder),
+            Err("No renderer for day 4".to_string())
+        );
+        assert!(render(3, Part::One, "^x", &Params::default(), &mut NoopRecorder).is_err());
+    }
+}
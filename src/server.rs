@@ -0,0 +1,521 @@
+//! A small HTTP server, built on the standard library alone, which solves puzzle input posted to
+//! `POST /day/{n}/part/{p}` and answers in JSON
+//!
+//! Every response is a JSON object. Solving a part gives its `answer` as a string, or an `error`
+//! if the input couldn't be parsed or the solver failed, along with the time taken to parse and
+//! solve in nanoseconds:
+//!
+//! ```json
+//! {"day":2,"part":1,"answer":"58","error":null,"parse_ns":5120,"solve_ns":830}
+//! ```
+//!
+//! A fixed number of connections are handled at once, and any more wait to be accepted until one
+//! of them finishes. Each part is solved on a thread of its own, and the response is given once
+//! it runs out of time even if the solver can't be cancelled, leaving it to finish in the
+//! background.
+
+use crate::{
+    params::Params,
+    progress::Progress,
+    solver::{self, Answer, Solver},
+    Part,
+};
+use json::{object, JsonValue};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Largest request body accepted, which is far larger than any puzzle input
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+/// Longest request line or header accepted
+const MAX_LINE: usize = 8 * 1024;
+
+/// Most headers accepted in a request
+const MAX_HEADERS: usize = 100;
+
+/// Stack given to the thread solving each part, as some solvers go deep on large inputs
+const SOLVER_STACK: usize = 64 * 1024 * 1024;
+
+/// Longest the server waits for a client to send the next part of its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Limits on the work the server does at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// Most connections handled at once, each on its own thread
+    pub connections: usize,
+
+    /// Longest a part may take to solve before it's cancelled
+    pub timeout: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            connections: 16,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Method, path and body of a request
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// Time taken to parse the input, then either the parse error or the time taken to solve it and
+/// the answer
+type Solved = (Duration, Result<(Duration, Result<Answer, String>), String>);
+
+/// Status and JSON body of a response
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: JsonValue,
+}
+
+impl Response {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: object! { error: message.into() },
+        }
+    }
+}
+
+/// Answer requests on the listener until it fails, solving with the given puzzle parameters. A
+/// pool of `limits.connections` threads handles the connections, one at a time each.
+pub fn serve(listener: TcpListener, params: &Params, limits: &Limits) -> io::Result<()> {
+    assert!(
+        limits.connections > 0,
+        "At least one connection is required"
+    );
+
+    // connections are only accepted once a thread is free to take them
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(0);
+    let receiver = Mutex::new(receiver);
+
+    thread::scope(|scope| {
+        for _ in 0..limits.connections {
+            let receiver = &receiver;
+
+            scope.spawn(move || loop {
+                let stream = match receiver.lock().map(|receiver| receiver.recv()) {
+                    Ok(Ok(stream)) => stream,
+                    _ => return,
+                };
+
+                // the client hanging up early only affects its own request
+                let _ = handle(stream, params, limits.timeout);
+            });
+        }
+
+        for stream in listener.incoming() {
+            if sender.send(stream?).is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Read a single request from the connection and write the response, closing it afterwards
+fn handle(mut stream: TcpStream, params: &Params, timeout: Duration) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let response = match read_request(&mut stream)? {
+        Ok(request) => respond(
+            &request.method,
+            &request.path,
+            &request.body,
+            params,
+            timeout,
+        ),
+        Err(response) => response,
+    };
+
+    let body = response.body.dump();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Read a request, or the response to give if it's malformed
+fn read_request(stream: &mut TcpStream) -> io::Result<Result<Request, Response>> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let request_line = match read_line(&mut reader)? {
+        Some(line) => line,
+        None => return Ok(Err(Response::error(414, "Request line is too long"))),
+    };
+
+    let (method, path) = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+        [method, path, version] if version.starts_with("HTTP/1.") => {
+            (method.to_string(), path.to_string())
+        }
+        _ => return Ok(Err(Response::error(400, "Malformed request line"))),
+    };
+
+    let mut length = None;
+    let mut expect_continue = false;
+
+    for headers in 0.. {
+        let header = match read_line(&mut reader)? {
+            Some(header) if header.is_empty() => {
+                return Ok(Err(Response::error(
+                    400,
+                    "Request ended before its headers",
+                )))
+            }
+            Some(header) => header,
+            None => return Ok(Err(Response::error(431, "Header is too long"))),
+        };
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if headers == MAX_HEADERS {
+            return Ok(Err(Response::error(431, "Too many headers")));
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+
+            if name.eq_ignore_ascii_case("content-length") {
+                match value.parse::<usize>() {
+                    Ok(n) => length = Some(n),
+                    Err(_) => return Ok(Err(Response::error(400, "Invalid Content-Length"))),
+                }
+            } else if name.eq_ignore_ascii_case("expect") {
+                expect_continue = value.eq_ignore_ascii_case("100-continue");
+            }
+        }
+    }
+
+    let length = match (method.as_str(), length) {
+        (_, Some(length)) if length > MAX_BODY => {
+            return Ok(Err(Response::error(413, "Input is too large")))
+        }
+        (_, Some(length)) => length,
+        ("POST", None) => return Ok(Err(Response::error(411, "Missing Content-Length"))),
+        (_, None) => 0,
+    };
+
+    // clients like curl wait for permission before sending a large body
+    if expect_continue {
+        stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Ok(Request { method, path, body }))
+}
+
+/// Read a line, including its line ending, or `None` if it's longer than `MAX_LINE`. The line is
+/// empty if the client has stopped sending.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = Vec::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE as u64)
+        .read_until(b'\n', &mut line)?;
+
+    if line.len() == MAX_LINE && !line.ends_with(b"\n") {
+        return Ok(None);
+    }
+
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+/// Work out the response to a request, giving up on solving it after the timeout
+fn respond(method: &str, path: &str, body: &[u8], params: &Params, timeout: Duration) -> Response {
+    let path = path.split('?').next().unwrap_or_default();
+
+    let (day, part) = match path.split('/').collect::<Vec<_>>()[..] {
+        ["", "day", day, "part", part] => (day, part),
+        _ => return Response::error(404, "Expected a path like /day/7/part/1"),
+    };
+
+    if method != "POST" {
+        return Response::error(405, "Post the puzzle input to solve it");
+    }
+
+    let day = match day.parse::<u8>() {
+        Ok(day) => day,
+        Err(_) => return Response::error(404, format!("Invalid day: {}", day)),
+    };
+    let part = match part {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return Response::error(404, format!("Invalid part: {}", part)),
+    };
+
+    let solver = match solver::solver(day) {
+        Some(solver) => solver,
+        None => return Response::error(404, format!("No solver for day {}", day)),
+    };
+
+    if !solver.has_part(part) {
+        return Response::error(404, format!("Day {} has no part {}", day, part));
+    }
+
+    let input = match std::str::from_utf8(body) {
+        Ok(input) => input,
+        Err(_) => return Response::error(400, "Input must be UTF-8"),
+    };
+
+    let mut body = object! {
+        day: day,
+        part: if part == Part::One { 1 } else { 2 },
+        answer: JsonValue::Null,
+        error: JsonValue::Null,
+        parse_ns: JsonValue::Null,
+        solve_ns: JsonValue::Null,
+    };
+
+    // the solver runs on a thread of its own so that the response can be given at the deadline
+    // even if it doesn't check for cancellation, in which case it's left to finish unseen
+    let progress = Progress::with_budget(timeout);
+    let (sender, receiver) = mpsc::channel();
+    let spawned = {
+        let (input, params, progress) = (input.to_string(), params.clone(), progress.clone());
+
+        thread::Builder::new()
+            .stack_size(SOLVER_STACK)
+            .spawn(move || {
+                let _ = sender.send(solve(solver, part, &input, &params, &progress));
+            })
+    };
+
+    if let Err(e) = spawned {
+        body["error"] = format!("Couldn't start solving: {}", e).into();
+        return Response { status: 500, body };
+    }
+
+    let (parse, solved) = match receiver.recv_timeout(timeout) {
+        Ok(solved) => solved,
+        Err(RecvTimeoutError::Timeout) => {
+            progress.cancel();
+            body["error"] =
+                format!("Day {} part {} took longer than {:?}", day, part, timeout).into();
+            return Response { status: 503, body };
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            body["error"] = "The solver stopped without an answer".into();
+            return Response { status: 500, body };
+        }
+    };
+
+    body["parse_ns"] = (parse.as_nanos() as u64).into();
+
+    let (solve, answer) = match solved {
+        Ok(solved) => solved,
+        Err(e) => {
+            body["error"] = e.into();
+            return Response { status: 422, body };
+        }
+    };
+
+    body["solve_ns"] = (solve.as_nanos() as u64).into();
+
+    match answer {
+        Ok(answer) => {
+            body["answer"] = answer.to_string().into();
+            Response { status: 200, body }
+        }
+        Err(_) if progress.is_cancelled() => {
+            body["error"] =
+                format!("Day {} part {} took longer than {:?}", day, part, timeout).into();
+            Response { status: 503, body }
+        }
+        Err(e) => {
+            body["error"] = e.into();
+            Response { status: 500, body }
+        }
+    }
+}
+
+/// Parse the input and solve a part, timing each
+fn solve(
+    solver: &dyn Solver,
+    part: Part,
+    input: &str,
+    params: &Params,
+    progress: &Progress,
+) -> Solved {
+    let start = Instant::now();
+    let parsed = solver::try_parse(solver, input);
+    let parse = start.elapsed();
+
+    let solved = parsed.map(|parsed| {
+        let start = Instant::now();
+        let answer = solver::try_run_with(solver, part, parsed.as_ref(), params, progress);
+        (start.elapsed(), answer)
+    });
+
+    (parse, solved)
+}
+
+/// Reason phrase for each status the server responds with
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(path: &str, body: &str) -> (u16, JsonValue) {
+        let response = respond(
+            "POST",
+            path,
+            body.as_bytes(),
+            &Params::default(),
+            Limits::default().timeout,
+        );
+        (response.status, response.body)
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_respond() {
+        let (status, body) = post("/day/1/part/2", "()())");
+
+        assert_eq!(status, 200);
+        assert_eq!(body["day"], 1);
+        assert_eq!(body["part"], 2);
+        assert_eq!(body["answer"], "5");
+        assert!(body["error"].is_null());
+        assert!(body["parse_ns"].is_number() && body["solve_ns"].is_number());
+    }
+
+    #[test]
+    #[cfg(feature = "day2")]
+    fn test_respond_parse_error() {
+        let (status, body) = post("/day/2/part/1", "1x2");
+
+        assert_eq!(status, 422);
+        assert!(body["answer"].is_null());
+        assert_eq!(
+            body["error"],
+            "Day 2 input, line 1, column 4: Expected dimensions in the form LxWxH (in \"1x2\")"
+        );
+        assert!(body["solve_ns"].is_null());
+    }
+
+    #[test]
+    fn test_respond_errors() {
+        assert_eq!(post("/day/1", "").0, 404);
+        assert_eq!(post("/day/x/part/1", "").0, 404);
+        assert_eq!(post("/day/1/part/3", "").0, 404);
+        assert_eq!(post("/day/26/part/1", "").0, 404);
+        assert_eq!(
+            respond(
+                "GET",
+                "/day/1/part/1",
+                b"",
+                &Params::default(),
+                Limits::default().timeout
+            )
+            .status,
+            405
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day23")]
+    fn test_respond_timeout() {
+        let response = respond(
+            "POST",
+            "/day/23/part/1",
+            b"jmp +0",
+            &Params::default(),
+            Duration::from_millis(10),
+        );
+
+        assert_eq!(response.status, 503);
+        assert_eq!(
+            response.body["error"],
+            "Day 23 part 1 took longer than 10ms"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day15")]
+    fn test_respond_timeout_uncancellable() {
+        let input = crate::random::generate(15, 6, 1).unwrap();
+        let start = Instant::now();
+        let response = respond(
+            "POST",
+            "/day/15/part/1",
+            input.as_bytes(),
+            &Params::default(),
+            Duration::from_millis(10),
+        );
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(response.status, 503);
+        assert_eq!(
+            response.body["error"],
+            "Day 15 part 1 took longer than 10ms"
+        );
+    }
+
+    #[test]
+    fn test_read_line() {
+        let long = "x".repeat(MAX_LINE);
+        let input = format!("GET / HTTP/1.1\r\n{}\r\n", long);
+        let mut reader = io::Cursor::new(input.as_bytes());
+
+        assert_eq!(
+            read_line(&mut reader).unwrap(),
+            Some("GET / HTTP/1.1\r\n".to_string())
+        );
+        assert_eq!(read_line(&mut reader).unwrap(), None);
+
+        let mut reader = io::Cursor::new(&b"Host: x"[..]);
+        assert_eq!(read_line(&mut reader).unwrap(), Some("Host: x".to_string()));
+        assert_eq!(read_line(&mut reader).unwrap(), Some(String::new()));
+    }
+
+    #[test]
+    #[cfg(feature = "day25")]
+    fn test_respond_missing_part() {
+        let (status, body) = post("/day/25/part/2", "");
+
+        assert_eq!(status, 404);
+        assert_eq!(body["error"], "Day 25 has no part 2");
+    }
+}
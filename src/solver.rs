@@ -0,0 +1,836 @@
+//! Registry of every day's solver, allowing days to be parsed and solved dynamically
+
+// With every day's feature turned off there is nothing to register
+#![cfg_attr(
+    not(any(
+        feature = "day1",
+        feature = "day2",
+        feature = "day3",
+        feature = "day4",
+        feature = "day5",
+        feature = "day6",
+        feature = "day7",
+        feature = "day8",
+        feature = "day9",
+        feature = "day10",
+        feature = "day11",
+        feature = "day12",
+        feature = "day13",
+        feature = "day14",
+        feature = "day15",
+        feature = "day16",
+        feature = "day17",
+        feature = "day18",
+        feature = "day19",
+        feature = "day20",
+        feature = "day21",
+        feature = "day22",
+        feature = "day23",
+        feature = "day24",
+        feature = "day25"
+    )),
+    allow(dead_code, unused_macros)
+)]
+
+use crate::{error::Error, params::Params, progress::Progress, trace::Tracer, *};
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::OnceLock,
+};
+
+/// Answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// Numeric answer, wide enough to hold any signed or unsigned answer
+    Integer(i128),
+
+    /// Textual answer, e.g. a password
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Integer(value as i128)
+                }
+            }
+
+            impl Solution for $t {
+                fn into_answer(self) -> Result<Answer, Error> {
+                    Ok(self.into())
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(u16, u32, u64, usize, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// What a day's solving functions return, either an answer or a result which may be an error
+trait Solution {
+    fn into_answer(self) -> Result<Answer, Error>;
+}
+
+impl Solution for String {
+    fn into_answer(self) -> Result<Answer, Error> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>, E: Into<Error>> Solution for Result<T, E> {
+    fn into_answer(self) -> Result<Answer, Error> {
+        self.map(Into::into).map_err(Into::into)
+    }
+}
+
+/// Puzzle input after it has been parsed by a [Solver]
+pub type Parsed = Box<dyn Any>;
+
+/// Object-safe solver for a single day, allowing days to be dispatched dynamically
+pub trait Solver: Sync {
+    /// Day of the puzzle
+    fn day(&self) -> u8;
+
+    /// Parse the raw puzzle input. Every day's parser [normalises](crate::input::normalise) its line
+    /// endings and whitespace first.
+    fn parse(&self, input: &str) -> Result<Parsed, Error>;
+
+    /// Solve part 1 using input previously returned from [Solver::parse]
+    fn part1(&self, input: &dyn Any) -> Result<Answer, Error>;
+
+    /// Solve part 2 using input previously returned from [Solver::parse], if this day has a part 2
+    fn part2(&self, input: &dyn Any) -> Option<Result<Answer, Error>>;
+
+    /// Check whether this day has the given part
+    fn has_part(&self, part: Part) -> bool;
+
+    /// Solve the given part, if this day has it
+    fn solve(&self, part: Part, input: &dyn Any) -> Option<Result<Answer, Error>> {
+        match part {
+            Part::One => Some(self.part1(input)),
+            Part::Two => self.part2(input),
+        }
+    }
+
+    /// Solve the given part, if this day has it, emitting events into the tracer. Days without any
+    /// trace hooks solve normally without emitting anything.
+    fn trace(
+        &self,
+        part: Part,
+        input: &dyn Any,
+        _tracer: &mut dyn Tracer,
+    ) -> Option<Result<Answer, Error>> {
+        self.solve(part, input)
+    }
+
+    /// Solve the given part, if this day has it, reporting progress and stopping early with
+    /// [Error::Cancelled] if cancelled. Days which don't report progress solve normally and can't be
+    /// cancelled.
+    fn run(
+        &self,
+        part: Part,
+        input: &dyn Any,
+        _progress: &Progress,
+    ) -> Option<Result<Answer, Error>> {
+        self.solve(part, input)
+    }
+
+    /// Solve the given part, if this day has it, using the given puzzle parameters instead of the
+    /// ones from the puzzle. Days without parameters solve as [Solver::run] does.
+    fn run_with(
+        &self,
+        part: Part,
+        input: &dyn Any,
+        _params: &Params,
+        progress: &Progress,
+    ) -> Option<Result<Answer, Error>> {
+        self.run(part, input, progress)
+    }
+
+    /// Names of the strategies this day can be solved with, starting with the one its parts use.
+    /// Empty for days with only one way of solving them.
+    fn strategies(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Solve the given part with the named strategy and puzzle parameters, if this day has both
+    fn run_strategy(
+        &self,
+        _strategy: &str,
+        _part: Part,
+        _input: &dyn Any,
+        _params: &Params,
+    ) -> Option<Result<Answer, Error>> {
+        None
+    }
+}
+
+/// Function which solves one part of a day
+type PartFn<T> = fn(&T) -> Result<Answer, Error>;
+
+/// Function which solves either part of a day while emitting trace events
+type TraceFn<T> = fn(&T, Part, &mut dyn Tracer) -> Result<Answer, Error>;
+
+/// Function which solves either part of a day while reporting progress
+type RunFn<T> = fn(&T, Part, &Progress) -> Result<Answer, Error>;
+
+/// Function which solves either part of a day using the given puzzle parameters, reporting
+/// progress if the day supports it
+type ParamsFn<T> = fn(&T, Part, &Params, &Progress) -> Result<Answer, Error>;
+
+/// Function which solves either part of a day in a particular way, using the given puzzle
+/// parameters if the day has any
+type StrategyFn<T> = fn(&T, Part, &Params) -> Result<Answer, Error>;
+
+/// [Solver] built from the free functions exported by a day module
+struct Day<T> {
+    day: u8,
+    parse: fn(&str) -> Result<T, Error>,
+    part1: PartFn<T>,
+    part2: Option<PartFn<T>>,
+    trace: Option<TraceFn<T>>,
+    run: Option<RunFn<T>>,
+    params: Option<ParamsFn<T>>,
+    strategies: Vec<(&'static str, StrategyFn<T>)>,
+}
+
+impl<T: 'static> Day<T> {
+    fn input<'a>(&self, input: &'a dyn Any) -> &'a T {
+        input
+            .downcast_ref::<T>()
+            .unwrap_or_else(|| panic!("Input was not parsed by the day {} solver", self.day))
+    }
+}
+
+impl<T: 'static> Solver for Day<T> {
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        (self.parse)(input).map(|parsed| Box::new(parsed) as Parsed)
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer, Error> {
+        (self.part1)(self.input(input))
+    }
+
+    fn part2(&self, input: &dyn Any) -> Option<Result<Answer, Error>> {
+        self.part2.map(|part2| part2(self.input(input)))
+    }
+
+    fn has_part(&self, part: Part) -> bool {
+        part == Part::One || self.part2.is_some()
+    }
+
+    fn trace(
+        &self,
+        part: Part,
+        input: &dyn Any,
+        tracer: &mut dyn Tracer,
+    ) -> Option<Result<Answer, Error>> {
+        match self.trace {
+            Some(trace) if self.has_part(part) => Some(trace(self.input(input), part, tracer)),
+            _ => self.solve(part, input),
+        }
+    }
+
+    fn run(
+        &self,
+        part: Part,
+        input: &dyn Any,
+        progress: &Progress,
+    ) -> Option<Result<Answer, Error>> {
+        match self.run {
+            Some(run) if self.has_part(part) => Some(run(self.input(input), part, progress)),
+            _ => self.solve(part, input),
+        }
+    }
+
+    fn run_with(
+        &self,
+        part: Part,
+        input: &dyn Any,
+        params: &Params,
+        progress: &Progress,
+    ) -> Option<Result<Answer, Error>> {
+        match self.params {
+            Some(solve) if self.has_part(part) => {
+                Some(solve(self.input(input), part, params, progress))
+            }
+            _ => self.run(part, input, progress),
+        }
+    }
+
+    fn strategies(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|&(name, _)| name).collect()
+    }
+
+    fn run_strategy(
+        &self,
+        strategy: &str,
+        part: Part,
+        input: &dyn Any,
+        params: &Params,
+    ) -> Option<Result<Answer, Error>> {
+        let &(_, solve) = self
+            .strategies
+            .iter()
+            .find(|&&(name, _)| name == strategy)?;
+
+        if self.has_part(part) {
+            Some(solve(self.input(input), part, params))
+        } else {
+            None
+        }
+    }
+}
+
+/// Register a day with the given parse function and part functions, and optionally hooks for
+/// solving either part in other ways:
+///
+/// - `trace = f` solves while emitting trace events
+/// - `progress = f` solves while reporting progress
+/// - `params.dayN = f` solves using the day's puzzle parameters
+/// - `progress.dayN = f` solves using the day's puzzle parameters while reporting progress
+/// - `strategy.name = f` adds a named strategy, the first being the one the parts use
+/// - `strategy.dayN.name = f` adds a named strategy which uses the day's puzzle parameters
+macro_rules! day {
+    (@hook $solver:ident, trace = $trace:path) => {
+        $solver.trace = Some(|input, part, tracer| $trace(input, part, tracer).into_answer());
+    };
+    (@hook $solver:ident, progress = $run:path) => {
+        $solver.run = Some(|input, part, progress| $run(input, part, progress).into_answer());
+    };
+    (@hook $solver:ident, params.$field:ident = $solve:path) => {
+        $solver.params =
+            Some(|input, part, params, _| $solve(input, part, &params.$field).into_answer());
+    };
+    (@hook $solver:ident, progress.$field:ident = $run:path) => {
+        $solver.run = Some(|input, part, progress| {
+            $run(input, part, &Default::default(), progress).into_answer()
+        });
+        $solver.params = Some(|input, part, params, progress| {
+            $run(input, part, &params.$field, progress).into_answer()
+        });
+    };
+    (@hook $solver:ident, strategy.$name:ident = $solve:path) => {
+        $solver.strategies.push((stringify!($name), |input, part, _| $solve(input, part).into_answer()));
+    };
+    (@hook $solver:ident, strategy.$field:ident.$name:ident = $solve:path) => {
+        $solver.strategies.push((stringify!($name), |input, part, params| {
+            $solve(input, part, &params.$field).into_answer()
+        }));
+    };
+    (@build $day:expr, $parse:expr, $part1:path, $part2:expr $(, $hook:ident $(.$field:ident)* = $f:path)*) => {{
+        #[allow(unused_mut)]
+        let mut solver = Day {
+            day: $day,
+            parse: $parse,
+            part1: |input| $part1(input).into_answer(),
+            part2: $part2,
+            trace: None,
+            run: None,
+            params: None,
+            strategies: Vec::new(),
+        };
+        $(day!(@hook solver, $hook $(.$field)* = $f);)*
+        Box::new(solver) as Box<dyn Solver>
+    }};
+    ($day:expr, $parse:expr, $part1:path, $part2:path $(, $hook:ident $(.$field:ident)* = $f:path)*) => {
+        day!(@build $day, $parse, $part1, Some(|input| $part2(input).into_answer()) $(, $hook $(.$field)* = $f)*)
+    };
+    ($day:expr, $parse:expr, $part1:path $(, $hook:ident $(.$field:ident)* = $f:path)*) => {
+        day!(@build $day, $parse, $part1, None $(, $hook $(.$field)* = $f)*)
+    };
+}
+
+/// Parse function for days which operate directly on the raw input, which only normalises it
+#[cfg(any(feature = "day4", feature = "day5"))]
+fn raw(input: &str) -> Result<String, Error> {
+    Ok(input::normalise(input).into_owned())
+}
+
+/// Solvers for every day which was compiled in, keyed by day
+fn registry() -> &'static BTreeMap<u8, &'static dyn Solver> {
+    static REGISTRY: OnceLock<BTreeMap<u8, &'static dyn Solver>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| {
+        let solvers: Vec<Box<dyn Solver>> = vec![
+            #[cfg(feature = "day1")]
+            day!(1, day1::generator, day1::part1, day1::part2),
+            #[cfg(feature = "day2")]
+            day!(2, day2::generator, day2::part1, day2::part2),
+            #[cfg(feature = "day3")]
+            day!(3, day3::generator, day3::part1, day3::part2),
+            #[cfg(feature = "day4")]
+            day!(4, raw, day4::part1, day4::part2, progress.day4 = day4::run),
+            #[cfg(feature = "day5")]
+            day!(5, raw, day5::part1, day5::part2),
+            #[cfg(feature = "day6")]
+            day!(6, day6::generator, day6::part1, day6::part2),
+            #[cfg(feature = "day7")]
+            day!(
+                7,
+                day7::generator,
+                day7::part1,
+                day7::part2,
+                trace = day7::trace,
+                params.day7 = day7::solve
+            ),
+            #[cfg(feature = "day8")]
+            day!(8, day8::generator, day8::part1, day8::part2),
+            #[cfg(feature = "day9")]
+            day!(
+                9,
+                day9::generator,
+                day9::part1,
+                day9::part2,
+                progress = day9::run,
+                strategy.permutations = day9::permutations,
+                strategy.held_karp = day9::held_karp
+            ),
+            #[cfg(feature = "day10")]
+            day!(10, day10::generator, day10::part1, day10::part2),
+            #[cfg(feature = "day11")]
+            day!(11, day11::generator, day11::part1, day11::part2),
+            #[cfg(feature = "day12")]
+            day!(12, day12::generator, day12::part1, day12::part2),
+            #[cfg(feature = "day13")]
+            day!(
+                13,
+                day13::generator,
+                day13::part1,
+                day13::part2,
+                progress = day13::run
+            ),
+            #[cfg(feature = "day14")]
+            day!(
+                14,
+                day14::generator,
+                day14::part1,
+                day14::part2,
+                params.day14 = day14::solve
+            ),
+            #[cfg(feature = "day15")]
+            day!(15, day15::generator, day15::part1, day15::part2),
+            #[cfg(feature = "day16")]
+            day!(16, day16::generator, day16::part1, day16::part2),
+            #[cfg(feature = "day17")]
+            day!(
+                17,
+                day17::generator,
+                day17::part1,
+                day17::part2,
+                params.day17 = day17::solve,
+                strategy.day17.combinations = day17::combinations,
+                strategy.day17.dynamic = day17::dynamic
+            ),
+            #[cfg(feature = "day18")]
+            day!(
+                18,
+                day18::generator,
+                day18::part1,
+                day18::part2,
+                params.day18 = day18::solve
+            ),
+            #[cfg(feature = "day19")]
+            day!(
+                19,
+                day19::generator,
+                day19::part1,
+                day19::part2,
+                progress = day19::run
+            ),
+            #[cfg(feature = "day20")]
+            day!(
+                20,
+                day20::generator,
+                day20::part1,
+                day20::part2,
+                progress.day20 = day20::run,
+                strategy.day20.sieve = day20::sieve,
+                strategy.day20.trial_division = day20::trial_division
+            ),
+            #[cfg(feature = "day21")]
+            day!(
+                21,
+                day21::generator,
+                day21::part1,
+                day21::part2,
+                params.day21 = day21::solve
+            ),
+            #[cfg(feature = "day22")]
+            day!(
+                22,
+                day22::generator,
+                day22::part1,
+                day22::part2,
+                trace = day22::trace,
+                progress.day22 = day22::run
+            ),
+            #[cfg(feature = "day23")]
+            day!(
+                23,
+                day23::generator,
+                day23::part1,
+                day23::part2,
+                trace = day23::trace,
+                progress = day23::run
+            ),
+            #[cfg(feature = "day24")]
+            day!(
+                24,
+                day24::generator,
+                day24::part1,
+                day24::part2,
+                progress = day24::run
+            ),
+            #[cfg(feature = "day25")]
+            day!(
+                25,
+                day25::generator,
+                day25::part1,
+                strategy.iteration = day25::iteration,
+                strategy.modular_exponentiation = day25::modular_exponentiation
+            ),
+        ];
+
+        // The registry lives for the rest of the program, so leaking it lets it be shared freely
+        solvers
+            .into_iter()
+            .map(|solver| (solver.day(), &*Box::leak(solver)))
+            .collect()
+    })
+}
+
+/// Get the solver for the given day, if there is one
+pub fn solver(day: u8) -> Option<&'static dyn Solver> {
+    registry().get(&day).copied()
+}
+
+/// Get the solvers for every day, in day order
+pub fn solvers() -> impl Iterator<Item = &'static dyn Solver> {
+    registry().values().copied()
+}
+
+/// Parse the input, converting any error or panic into an error message
+pub fn try_parse(solver: &dyn Solver, input: &str) -> Result<Parsed, String> {
+    catch_panic(|| solver.parse(input).map_err(|e| e.to_string()))
+}
+
+/// Parse the input like [try_parse], but show the offending line of any parse error with a caret
+/// under the column where parsing failed, for reporting errors on their own rather than in a table
+pub fn try_parse_with_source(solver: &dyn Solver, input: &str) -> Result<Parsed, String> {
+    catch_panic(|| solver.parse(input).map_err(|e| format!("{:#}", e)))
+}
+
+/// Solve one part of an already parsed input, converting any error or panic into an error message
+pub fn try_solve(solver: &dyn Solver, part: Part, input: &dyn Any) -> Result<Answer, String> {
+    catch_panic(|| answer(solver, part, solver.solve(part, input)))
+}
+
+/// Solve one part of an already parsed input while emitting trace events, converting any error or
+/// panic into an error message
+pub fn try_trace(
+    solver: &dyn Solver,
+    part: Part,
+    input: &dyn Any,
+    tracer: &mut dyn Tracer,
+) -> Result<Answer, String> {
+    catch_panic(|| answer(solver, part, solver.trace(part, input, tracer)))
+}
+
+/// Solve one part of an already parsed input while reporting progress, converting any error,
+/// panic or cancellation into an error message
+pub fn try_run(
+    solver: &dyn Solver,
+    part: Part,
+    input: &dyn Any,
+    progress: &Progress,
+) -> Result<Answer, String> {
+    try_run_with(solver, part, input, &Params::default(), progress)
+}
+
+/// Solve one part of an already parsed input using the given puzzle parameters while reporting
+/// progress, converting any error, panic or cancellation into an error message
+pub fn try_run_with(
+    solver: &dyn Solver,
+    part: Part,
+    input: &dyn Any,
+    params: &Params,
+    progress: &Progress,
+) -> Result<Answer, String> {
+    catch_panic(|| answer(solver, part, solver.run_with(part, input, params, progress)))
+}
+
+/// Solve one part of an already parsed input with the named strategy, converting any error or panic
+/// into an error message
+pub fn try_run_strategy(
+    solver: &dyn Solver,
+    strategy: &str,
+    part: Part,
+    input: &dyn Any,
+    params: &Params,
+) -> Result<Answer, String> {
+    let strategies = solver.strategies();
+
+    if !strategies.contains(&strategy) {
+        return Err(match strategies.len() {
+            0 => format!("Day {} only has one strategy", solver.day()),
+            _ => format!(
+                "Day {} has no strategy {}, choose from {}",
+                solver.day(),
+                strategy,
+                strategies.join(", ")
+            ),
+        });
+    }
+
+    catch_panic(|| {
+        answer(
+            solver,
+            part,
+            solver.run_strategy(strategy, part, input, params),
+        )
+    })
+}
+
+/// Convert the outcome of solving a part into an answer or an error message
+fn answer(
+    solver: &dyn Solver,
+    part: Part,
+    solved: Option<Result<Answer, Error>>,
+) -> Result<Answer, String> {
+    match solved {
+        Some(solved) => solved.map_err(|e| e.to_string()),
+        None => Err(format!("Day {} has no part {}", solver.day(), part)),
+    }
+}
+
+/// Run the given function, converting any panic into an error so that the caller can report it
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|e| {
+        let message = e
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "unknown error".to_string());
+
+        Err(format!("Solver panicked: {}", message))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "day1", feature = "day23"))]
+    use crate::trace::Collector;
+
+    #[cfg(feature = "day23")]
+    static INPUT: &str = include_str!("../input/2015/day23.txt");
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_registry() {
+        let days = solvers().map(|s| s.day()).collect::<Vec<_>>();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg(feature = "day23")]
+    fn test_solve() {
+        let solver = solver(23).unwrap();
+        let input = solver.parse(INPUT).unwrap();
+
+        assert_eq!(solver.part1(input.as_ref()), Ok(Answer::Integer(170)));
+        assert_eq!(solver.part2(input.as_ref()), Some(Ok(Answer::Integer(247))));
+    }
+
+    #[test]
+    #[cfg(all(feature = "day6", feature = "checked"))]
+    fn test_solve_overflow() {
+        let solver = solver(6).unwrap();
+        let input = solver
+            .parse(&"toggle 0,0 through 0,0\n".repeat(128))
+            .unwrap();
+
+        assert_eq!(
+            try_solve(solver, Part::Two, input.as_ref()),
+            Err("Day 6: Overflow while turning up a light".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day23")]
+    fn test_trace() {
+        let solver = solver(23).unwrap();
+        let input = solver.parse(INPUT).unwrap();
+        let mut collector = Collector::new();
+
+        let answer = try_trace(solver, Part::Two, input.as_ref(), &mut collector);
+
+        assert_eq!(answer, Ok(Answer::Integer(247)));
+        assert!(!collector.events.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_trace_without_hooks() {
+        let solver = solver(1).unwrap();
+        let input = solver.parse("(()").unwrap();
+        let mut collector = Collector::new();
+
+        assert_eq!(
+            solver.trace(Part::One, input.as_ref(), &mut collector),
+            Some(Ok(Answer::Integer(1)))
+        );
+        assert!(collector.events.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "day9")]
+    fn test_run() {
+        let solver = solver(9).unwrap();
+        let input = solver
+            .parse("London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141")
+            .unwrap();
+        let progress = Progress::new();
+
+        let answer = try_run(solver, Part::One, input.as_ref(), &progress);
+
+        assert_eq!(answer, Ok(Answer::Integer(605)));
+        assert_eq!(progress.fraction(), Some(1.0));
+
+        progress.cancel();
+        assert_eq!(
+            try_run(solver, Part::Two, input.as_ref(), &progress),
+            Err("Cancelled".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_run_without_progress() {
+        let solver = solver(1).unwrap();
+        let input = solver.parse("(()").unwrap();
+        let progress = Progress::new();
+        progress.cancel();
+
+        assert_eq!(
+            try_run(solver, Part::One, input.as_ref(), &progress),
+            Ok(Answer::Integer(1))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day4", feature = "day17"))]
+    fn test_run_with() {
+        let solver = solver(17).unwrap();
+        let input = solver.parse("20\n15\n10\n5\n5").unwrap();
+        let mut params = Params::default();
+        params.day17.litres = 25;
+
+        assert_eq!(
+            try_run_with(solver, Part::One, input.as_ref(), &params, &Progress::new()),
+            Ok(Answer::Integer(4))
+        );
+        assert_eq!(
+            try_run(solver, Part::One, input.as_ref(), &Progress::new()),
+            Ok(Answer::Integer(0))
+        );
+
+        let solver = super::solver(4).unwrap();
+        let input = solver.parse("abcdef").unwrap();
+        params.day4.part2_zeroes = 3;
+
+        assert_eq!(
+            try_run_with(solver, Part::Two, input.as_ref(), &params, &Progress::new()),
+            Ok(Answer::Integer(3337))
+        );
+
+        let solver = super::solver(1).unwrap();
+        let input = solver.parse("(()").unwrap();
+
+        assert_eq!(
+            try_run_with(solver, Part::One, input.as_ref(), &params, &Progress::new()),
+            Ok(Answer::Integer(1))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_strategies() {
+        let days = solvers()
+            .filter(|s| !s.strategies().is_empty())
+            .map(|s| s.day())
+            .collect::<Vec<_>>();
+        assert_eq!(days, vec![9, 17, 20, 25]);
+
+        let solver = solver(17).unwrap();
+        let input = solver.parse("20\n15\n10\n5\n5").unwrap();
+        let mut params = Params::default();
+        params.day17.litres = 25;
+
+        assert_eq!(solver.strategies(), vec!["combinations", "dynamic"]);
+        assert_eq!(
+            try_run_strategy(solver, "dynamic", Part::Two, input.as_ref(), &params),
+            Ok(Answer::Integer(3))
+        );
+        assert_eq!(
+            try_run_strategy(solver, "greedy", Part::Two, input.as_ref(), &params),
+            Err("Day 17 has no strategy greedy, choose from combinations, dynamic".to_string())
+        );
+
+        let solver = super::solver(25).unwrap();
+        let input = solver.parse("row 2, column 1").unwrap();
+
+        assert_eq!(
+            try_run_strategy(solver, "iteration", Part::Two, input.as_ref(), &params),
+            Err("Day 25 has no part 2".to_string())
+        );
+
+        let solver = super::solver(1).unwrap();
+        let input = solver.parse("(()").unwrap();
+
+        assert_eq!(
+            try_run_strategy(solver, "iteration", Part::One, input.as_ref(), &params),
+            Err("Day 1 only has one strategy".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day25")]
+    fn test_day25_has_no_part2() {
+        let solver = solver(25).unwrap();
+        assert!(solver.has_part(Part::One));
+        assert!(!solver.has_part(Part::Two));
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(10439961859usize).to_string(), "10439961859");
+        assert_eq!(Answer::from(-5isize).to_string(), "-5");
+        assert_eq!(Answer::from("cqjxxyzz".to_string()).to_string(), "cqjxxyzz");
+    }
+}
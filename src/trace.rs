@@ -0,0 +1,237 @@
+//! Explaining how solvers reached their answers as a series of events
+
+#[cfg(feature = "json")]
+use json::{object, JsonValue};
+use std::fmt;
+
+/// Something a solver did while working out an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Day 7: the value of a wire was worked out
+    WireResolved {
+        /// Wire ID
+        wire: String,
+
+        /// Signal on the wire
+        value: u16,
+    },
+
+    /// Day 22: a spell was cast in the cheapest winning game, with the state at the end of the
+    /// round
+    SpellCast {
+        /// Name of the spell
+        spell: &'static str,
+
+        /// Hit points the hero has left
+        hero_hp: usize,
+
+        /// Mana the hero has left
+        hero_mana: usize,
+
+        /// Hit points the boss has left
+        boss_hp: usize,
+
+        /// Mana spent on spells so far
+        total_mana: usize,
+    },
+
+    /// Day 23: an instruction was executed, with the register values afterwards
+    InstructionExecuted {
+        /// Position of the instruction in the program
+        pointer: usize,
+
+        /// The instruction as it's written in the program
+        instruction: String,
+
+        /// Value of register `a`
+        a: usize,
+
+        /// Value of register `b`
+        b: usize,
+    },
+}
+
+impl Event {
+    /// Convert the event to a JSON object, with the kind of event in the `event` field
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            Event::WireResolved { wire, value } => object! {
+                event: "wire_resolved",
+                wire: wire.as_str(),
+                value: *value,
+            },
+            Event::SpellCast {
+                spell,
+                hero_hp,
+                hero_mana,
+                boss_hp,
+                total_mana,
+            } => object! {
+                event: "spell_cast",
+                spell: *spell,
+                hero_hp: *hero_hp,
+                hero_mana: *hero_mana,
+                boss_hp: *boss_hp,
+                total_mana: *total_mana,
+            },
+            Event::InstructionExecuted {
+                pointer,
+                instruction,
+                a,
+                b,
+            } => object! {
+                event: "instruction_executed",
+                pointer: *pointer,
+                instruction: instruction.as_str(),
+                a: *a,
+                b: *b,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::WireResolved { wire, value } => write!(f, "wire {} = {}", wire, value),
+            Event::SpellCast {
+                spell,
+                hero_hp,
+                hero_mana,
+                boss_hp,
+                total_mana,
+            } => write!(
+                f,
+                "cast {}: hero {} hp {} mana, boss {} hp, {} mana spent",
+                spell, hero_hp, hero_mana, boss_hp, total_mana
+            ),
+            Event::InstructionExecuted {
+                pointer,
+                instruction,
+                a,
+                b,
+            } => write!(
+                f,
+                "{:>4}: {:<12} a = {}, b = {}",
+                pointer, instruction, a, b
+            ),
+        }
+    }
+}
+
+/// Receives [events](Event) emitted by solvers
+pub trait Tracer {
+    /// Record an event
+    fn event(&mut self, event: Event);
+
+    /// Check whether events are wanted, so that solvers can avoid building events which would be
+    /// thrown away
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// [Tracer] which ignores every event, used when solving normally
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {
+    fn event(&mut self, _: Event) {}
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+/// [Tracer] which keeps every event so that they can be printed or saved afterwards
+#[derive(Debug, Default)]
+pub struct Collector {
+    /// Every event received, in order
+    pub events: Vec<Event>,
+}
+
+impl Collector {
+    /// Create a collector with no events
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serialise the events to a JSON array
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let events = self.events.iter().map(Event::to_json).collect::<Vec<_>>();
+        JsonValue::Array(events).pretty(2)
+    }
+}
+
+impl Tracer for Collector {
+    fn event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+}
+
+impl fmt::Display for Collector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector() {
+        let mut collector = Collector::new();
+        collector.event(Event::WireResolved {
+            wire: "b".to_string(),
+            value: 3,
+        });
+        collector.event(Event::InstructionExecuted {
+            pointer: 7,
+            instruction: "jio a, +2".to_string(),
+            a: 1,
+            b: 4,
+        });
+
+        assert_eq!(
+            collector.to_string(),
+            "wire b = 3\n   7: jio a, +2    a = 1, b = 4\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_collector_json() {
+        let mut collector = Collector::new();
+        collector.event(Event::WireResolved {
+            wire: "b".to_string(),
+            value: 3,
+        });
+        collector.event(Event::InstructionExecuted {
+            pointer: 7,
+            instruction: "jio a, +2".to_string(),
+            a: 1,
+            b: 4,
+        });
+
+        let json = json::parse(&collector.to_json()).unwrap();
+        assert_eq!(json[0]["event"], "wire_resolved");
+        assert_eq!(json[0]["value"], 3);
+        assert_eq!(json[1]["instruction"], "jio a, +2");
+    }
+
+    #[test]
+    fn test_noop_tracer() {
+        let mut tracer = NoopTracer;
+        assert!(!tracer.enabled());
+        tracer.event(Event::WireResolved {
+            wire: "a".to_string(),
+            value: 1,
+        });
+    }
+}
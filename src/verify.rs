@@ -0,0 +1,345 @@
+//! Checking solver answers against recorded answers
+
+use crate::{
+    solver::{self, Solver},
+    Part,
+};
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+/// Name of the answers file expected alongside the input files
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Recorded answers for one set of inputs, keyed by day and part
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u8, Part), String>);
+
+impl Answers {
+    /// Parse an answers file. Each line is `<day> <part> <answer>`, blank lines and lines
+    /// starting with `#` are ignored.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut answers = BTreeMap::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || format!("Invalid answer on line {}: {:?}", i + 1, line);
+            let parts = line.splitn(3, char::is_whitespace).collect::<Vec<_>>();
+
+            let (day, part, answer) = match parts[..] {
+                [day, part, answer] => (day, part, answer.trim()),
+                _ => return Err(invalid()),
+            };
+
+            let day = day.parse::<u8>().map_err(|_| invalid())?;
+            let part = match part {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _ => return Err(invalid()),
+            };
+
+            if answers.insert((day, part), answer.to_string()).is_some() {
+                return Err(format!(
+                    "Duplicate answer for day {} part {} on line {}",
+                    day,
+                    part,
+                    i + 1
+                ));
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+
+    /// Read and parse an answers file
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+        Answers::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Get the recorded answer for the given day and part
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Compare a solver's result for the given day and part to the recorded answer, returning the
+    /// answer as text if there was one along with its status
+    pub fn check(
+        &self,
+        day: u8,
+        part: Part,
+        result: Result<impl fmt::Display, String>,
+    ) -> (Option<String>, Status) {
+        match result {
+            Ok(answer) => {
+                let answer = answer.to_string();
+                let status = match self.get(day, part) {
+                    Some(expected) if expected == answer => Status::Pass,
+                    Some(expected) => Status::Fail(expected.to_string()),
+                    None => Status::Missing,
+                };
+
+                (Some(answer), status)
+            }
+            Err(e) => (None, Status::Error(e)),
+        }
+    }
+}
+
+/// Result of checking a single day and part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The solver produced the recorded answer
+    Pass,
+
+    /// The solver produced a different answer to the one recorded
+    Fail(String),
+
+    /// There is no recorded answer to check against
+    Missing,
+
+    /// The solver couldn't produce an answer, e.g. because the input failed to parse
+    Error(String),
+}
+
+/// Outcome of verifying a single day and part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Day of the puzzle
+    pub day: u8,
+
+    /// Part of the puzzle
+    pub part: Part,
+
+    /// Answer produced by the solver, if there was one
+    pub answer: Option<String>,
+
+    /// Comparison with the recorded answer
+    pub status: Status,
+}
+
+/// Run every solver against its input in the given directory and compare the results to the
+/// recorded answers. Days with neither an input file nor a recorded answer are skipped.
+pub fn verify(inputs: &Path, answers: &Answers) -> Vec<Outcome> {
+    solver::solvers()
+        .flat_map(|solver| verify_day(solver, inputs, answers))
+        .collect()
+}
+
+fn verify_day(solver: &dyn Solver, inputs: &Path, answers: &Answers) -> Vec<Outcome> {
+    let day = solver.day();
+    let parts = [Part::One, Part::Two]
+        .iter()
+        .copied()
+        .filter(|&part| solver.has_part(part))
+        .collect::<Vec<_>>();
+
+    let path = inputs.join(format!("day{}.txt", day));
+
+    if !path.exists() && parts.iter().all(|&part| answers.get(day, part).is_none()) {
+        return Vec::new();
+    }
+
+    let outcome = |part, answer: Option<String>, status| Outcome {
+        day,
+        part,
+        answer,
+        status,
+    };
+
+    let parsed = fs::read_to_string(&path)
+        .map_err(|e| format!("Unable to read {}: {}", path.display(), e))
+        .and_then(|input| solver::try_parse(solver, &input));
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return parts
+                .into_iter()
+                .map(|part| outcome(part, None, Status::Error(e.clone())))
+                .collect()
+        }
+    };
+
+    parts
+        .into_iter()
+        .map(|part| {
+            let result = solver::try_solve(solver, part, parsed.as_ref());
+            let (answer, status) = answers.check(day, part, result);
+            outcome(part, answer, status)
+        })
+        .collect()
+}
+
+/// Formats verification outcomes as a table
+pub struct Table<'a>(pub &'a [Outcome]);
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .iter()
+            .filter_map(|o| o.answer.as_ref().map(String::len))
+            .max()
+            .unwrap_or(0)
+            .max("Answer".len());
+
+        writeln!(
+            f,
+            "{:>3}  {:>4}  {:<7}  {:<width$}  Expected",
+            "Day",
+            "Part",
+            "Status",
+            "Answer",
+            width = width
+        )?;
+
+        for outcome in self.0 {
+            let (status, detail) = match &outcome.status {
+                Status::Pass => ("pass", ""),
+                Status::Fail(expected) => ("FAIL", expected.as_str()),
+                Status::Missing => ("missing", ""),
+                Status::Error(e) => ("ERROR", e.as_str()),
+            };
+
+            let row = format!(
+                "{:>3}  {:>4}  {:<7}  {:<width$}  {}",
+                outcome.day,
+                outcome.part,
+                status,
+                outcome.answer.as_deref().unwrap_or("-"),
+                detail,
+                width = width
+            );
+
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        let count = |f: fn(&Status) -> bool| self.0.iter().filter(|o| f(&o.status)).count();
+
+        write!(
+            f,
+            "\n{} passed, {} failed, {} missing, {} errors",
+            count(|s| *s == Status::Pass),
+            count(|s| matches!(s, Status::Fail(_))),
+            count(|s| *s == Status::Missing),
+            count(|s| matches!(s, Status::Error(_)))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Some helpers are only used by tests of days which may not be compiled in
+    #![cfg_attr(not(feature = "all-days"), allow(unused_imports, dead_code))]
+
+    use super::*;
+    use std::{env, path::PathBuf};
+
+    #[cfg(all(feature = "day2", feature = "day23"))]
+    static INPUT: &str = include_str!("../input/2015/day23.txt");
+
+    /// Create an empty inputs directory unique to the calling test
+    fn inputs_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent-2015-verify-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("# day part answer\n\n1 1 138\n11 2 cqkaabcc\n").unwrap();
+
+        assert_eq!(answers.get(1, Part::One), Some("138"));
+        assert_eq!(answers.get(11, Part::Two), Some("cqkaabcc"));
+        assert_eq!(answers.get(1, Part::Two), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Answers::parse("1 1 138\n1 3 5"),
+            Err("Invalid answer on line 2: \"1 3 5\"".to_string())
+        );
+        assert_eq!(
+            Answers::parse("1 1 138\n1 1 139"),
+            Err("Duplicate answer for day 1 part 1 on line 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("1 1 138").unwrap();
+
+        assert_eq!(
+            answers.check(1, Part::One, Ok(138)),
+            (Some("138".to_string()), Status::Pass)
+        );
+        assert_eq!(
+            answers.check(1, Part::One, Ok(137)),
+            (Some("137".to_string()), Status::Fail("138".to_string()))
+        );
+        assert_eq!(
+            answers.check(1, Part::Two, Ok(1771)),
+            (Some("1771".to_string()), Status::Missing)
+        );
+        assert_eq!(
+            answers.check(1, Part::One, Err::<u32, _>("Overflow".to_string())),
+            (None, Status::Error("Overflow".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_recorded_answers() {
+        let answers = Answers::read(Path::new("input/2015/answers.txt")).unwrap();
+        assert_eq!(answers.get(7, Part::One), Some("16076"));
+        assert_eq!(answers.get(25, Part::One), Some("19980801"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "day2", feature = "day23"))]
+    fn test_verify() {
+        let dir = inputs_dir("statuses");
+        fs::write(dir.join("day23.txt"), INPUT).unwrap();
+        fs::write(dir.join("day2.txt"), "1x2").unwrap();
+
+        let answers = Answers::parse("2 1 58\n23 1 170\n23 2 1\n24 1 99").unwrap();
+        let outcomes = verify(&dir, &answers);
+
+        let statuses = outcomes
+            .iter()
+            .map(|o| (o.day, o.part, o.status.clone()))
+            .collect::<Vec<_>>();
+
+        let parse_error = Status::Error(
+            "Day 2 input, line 1, column 4: Expected dimensions in the form LxWxH (in \"1x2\")"
+                .to_string(),
+        );
+        let missing_input = Status::Error(format!(
+            "Unable to read {}: No such file or directory (os error 2)",
+            dir.join("day24.txt").display()
+        ));
+
+        assert_eq!(
+            statuses,
+            vec![
+                (2, Part::One, parse_error.clone()),
+                (2, Part::Two, parse_error),
+                (23, Part::One, Status::Pass),
+                (23, Part::Two, Status::Fail("1".to_string())),
+                (24, Part::One, missing_input.clone()),
+                (24, Part::Two, missing_input),
+            ]
+        );
+
+        let table = Table(&outcomes).to_string();
+        assert!(table.contains(" 23     1  pass     170"));
+        assert!(table.contains(" 23     2  FAIL     247     1"));
+        assert!(table.ends_with("1 passed, 1 failed, 0 missing, 4 errors"));
+    }
+}
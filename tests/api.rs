@@ -0,0 +1,110 @@
+//! Uses the library the way another crate would, so that accidentally making part of the public
+//! API private or changing its signature fails the build
+
+use advent_2015::compass::{Direction, Point};
+#[cfg(feature = "day21")]
+use advent_2015::day21::{self, Battle, Equipment, Outcome, Player};
+#[cfg(feature = "day22")]
+use advent_2015::day22::{self, GameState, Spell};
+#[cfg(feature = "day23")]
+use advent_2015::day23::{self, Computer, Instruction, Register};
+#[cfg(all(feature = "day7", feature = "day23"))]
+use advent_2015::day7;
+#[cfg(feature = "day17")]
+use advent_2015::{day17, params::Params, progress::Progress};
+#[cfg(any(feature = "day17", all(feature = "day7", feature = "day23")))]
+use advent_2015::{solver, Part};
+
+#[test]
+fn test_compass() {
+    let point = Point::new(1, 2).move_direction(&Direction::North);
+
+    assert_eq!(point, Point { x: 1, y: 3 });
+    assert_eq!(Direction::from_char(&'<'), Some(Direction::West));
+    assert_eq!(point.neighbours().len(), 8);
+}
+
+#[test]
+#[cfg(feature = "day21")]
+fn test_day21() {
+    let boss = Player {
+        hp: 12,
+        damage: 7,
+        armour: 2,
+    };
+    let battle = Battle::new(boss);
+
+    assert_eq!(
+        battle.fight(&[&Equipment::new(8, 4, 0)]),
+        Ok(Outcome::Win(8))
+    );
+    assert_eq!(day21::part1(&battle), Ok(8));
+}
+
+#[test]
+#[cfg(feature = "day22")]
+fn test_day22() {
+    let game = GameState::new(13, 8).with_hero(10, 250);
+
+    assert_eq!(Spell::ALL.len(), 5);
+    assert_eq!(Spell::Poison.cost(), 173);
+    assert_eq!(game.play(), Ok(226));
+    assert_eq!(day22::part1(&GameState::new(13, 10)), Ok(212));
+
+    let next = game.next_round(&Spell::Poison).unwrap().unwrap();
+    assert_eq!(next.total_mana(), 173);
+}
+
+#[test]
+#[cfg(feature = "day23")]
+fn test_day23() {
+    let mut computer = Computer::new(vec![
+        Instruction::Inc(Register::B),
+        Instruction::Triple(Register::B),
+    ]);
+    computer.run().unwrap();
+
+    assert_eq!(computer.value(Register::B), 3);
+    assert_eq!(
+        day23::part1(&day23::generator("inc b\ntpl b").unwrap()),
+        Ok(3)
+    );
+}
+
+#[test]
+#[cfg(all(feature = "day7", feature = "day23"))]
+fn test_generators() {
+    let wires = day7::generator("123 -> a").unwrap();
+    assert_eq!(day7::part1(&wires), Ok(123));
+
+    let solver = solver::solver(23).unwrap();
+    let input = solver.parse("inc b").unwrap();
+    assert_eq!(
+        solver
+            .solve(Part::One, input.as_ref())
+            .unwrap()
+            .unwrap()
+            .to_string(),
+        "1"
+    );
+}
+
+#[test]
+#[cfg(feature = "day17")]
+fn test_params() {
+    let containers = day17::generator("20\n15\n10\n5\n5").unwrap();
+    assert_eq!(
+        day17::solve(&containers, Part::Two, &day17::Params { litres: 25 }),
+        Ok(3)
+    );
+
+    let params = Params::parse("day17.litres = 25").unwrap();
+    let solver = solver::solver(17).unwrap();
+    let input = solver.parse("20\n15\n10\n5\n5").unwrap();
+    assert_eq!(
+        solver::try_run_with(solver, Part::One, input.as_ref(), &params, &Progress::new())
+            .unwrap()
+            .to_string(),
+        "4"
+    );
+}
@@ -0,0 +1,70 @@
+//! Compiles a small C program against the generated header and links it to the `cdylib`, the way
+//! a C or C++ test harness would
+
+#![cfg(all(
+    target_os = "linux",
+    feature = "day1",
+    feature = "day2",
+    feature = "day25"
+))]
+
+use std::{env, path::Path, process::Command};
+
+#[test]
+fn test_header_is_current() {
+    let root = env!("CARGO_MANIFEST_DIR");
+    let mut generated = Vec::new();
+
+    cbindgen::generate(root)
+        .expect("Unable to generate the C header")
+        .write(&mut generated);
+
+    let checked_in = std::fs::read(Path::new(root).join("include/advent_2015.h")).unwrap();
+
+    assert!(
+        generated == checked_in,
+        "include/advent_2015.h is out of date with src/ffi.rs. Regenerate it by running `cargo build --features header`."
+    );
+}
+
+#[test]
+fn test_c_harness() {
+    // cargo builds the cdylib into target/<profile>/deps alongside the integration tests
+    let exe = env::current_exe().unwrap();
+    let libraries = exe.parent().unwrap();
+    assert!(
+        libraries.join("libadvent_2015.so").exists(),
+        "Missing cdylib in {}",
+        libraries.display()
+    );
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let harness = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi_harness");
+
+    let status = Command::new("cc")
+        .arg(root.join("tests/ffi/harness.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(libraries)
+        .arg(format!("-Wl,-rpath,{}", libraries.display()))
+        .args(["-ladvent_2015", "-Wall", "-Werror", "-o"])
+        .arg(&harness)
+        .status()
+        .expect("Unable to run cc");
+    assert!(status.success(), "Unable to compile the C harness");
+
+    let output = Command::new(&harness).output().unwrap();
+    assert!(output.status.success(), "C harness failed");
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "\
+0 3
+0 48
+3 Day 2 input, line 2, column 3: Expected dimensions in the form LxWxH (in \"4xAx6\")
+2 Day 25 has no part 2
+1 Invalid part: 3
+"
+    );
+}
@@ -0,0 +1,30 @@
+/* Calls the solvers through the C header, printing the status and answer of each call */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "advent_2015.h"
+
+static void solve(uint8_t day, uint8_t part, const char *input)
+{
+    char *answer = NULL;
+    AdventStatus status = advent_solve(day, part, (const uint8_t *)input, strlen(input), &answer);
+
+    printf("%d %s\n", (int)status, answer);
+    advent_free(answer);
+}
+
+int main(void)
+{
+    solve(1, 1, "(()(()(");
+    solve(2, 2, "2x3x4\n1x1x10");
+    solve(2, 1, "2x3x4\n4xAx6");
+    solve(25, 2, "");
+    solve(1, 3, "(");
+
+    if (advent_solve(1, 1, NULL, 0, NULL) != ADVENT_STATUS_INVALID_ARGUMENT) {
+        return 1;
+    }
+
+    return 0;
+}
@@ -0,0 +1,103 @@
+//! Talks to the HTTP server over localhost, the way a dashboard would
+
+#![cfg(all(feature = "server", feature = "day1", feature = "day2"))]
+
+use advent_2015::{
+    params::Params,
+    server::{self, Limits},
+};
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread,
+};
+
+/// Start a server on any free port, returning its address
+fn start(limits: Limits) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    thread::spawn(move || server::serve(listener, &Params::default(), &limits));
+
+    address
+}
+
+/// Send a request and return the status code and parsed JSON body of the response
+fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, json::JsonValue) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.contains("Content-Type: application/json"));
+
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, json::parse(body).unwrap())
+}
+
+#[test]
+fn test_solve() {
+    let address = start(Limits::default());
+
+    let (status, body) = request(address, "POST", "/day/1/part/1", "(()(()(");
+    assert_eq!(status, 200);
+    assert_eq!(body["day"], 1);
+    assert_eq!(body["part"], 1);
+    assert_eq!(body["answer"], "3");
+    assert!(body["error"].is_null());
+    assert!(body["parse_ns"].as_u64().is_some());
+    assert!(body["solve_ns"].as_u64().is_some());
+
+    let (status, body) = request(address, "POST", "/day/2/part/2", "2x3x4\n1x1x10\n");
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], "48");
+}
+
+#[test]
+fn test_errors() {
+    let address = start(Limits::default());
+
+    let (status, body) = request(address, "POST", "/day/2/part/1", "2x3x4\n4xAx6");
+    assert_eq!(status, 422);
+    assert!(body["answer"].is_null());
+    assert_eq!(
+        body["error"],
+        "Day 2 input, line 2, column 3: Expected dimensions in the form LxWxH (in \"4xAx6\")"
+    );
+
+    let (status, _) = request(address, "GET", "/day/1/part/1", "");
+    assert_eq!(status, 405);
+
+    let (status, body) = request(address, "POST", "/days", "");
+    assert_eq!(status, 404);
+    assert_eq!(body["error"], "Expected a path like /day/7/part/1");
+}
+
+#[test]
+fn test_more_clients_than_connections() {
+    let address = start(Limits {
+        connections: 2,
+        ..Limits::default()
+    });
+
+    // clients beyond the limit wait their turn rather than failing
+    let clients = (0..6)
+        .map(|_| thread::spawn(move || request(address, "POST", "/day/1/part/1", "(()")))
+        .collect::<Vec<_>>();
+
+    for client in clients {
+        let (status, body) = client.join().unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body["answer"], "1");
+    }
+}